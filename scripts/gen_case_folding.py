@@ -0,0 +1,88 @@
+#!/usr/bin/env python3
+"""生成 src/utils/case_folding.txt 和 src/utils/casefold.rs 中的 FOLDS 表。
+
+用法:
+
+    python3 scripts/gen_case_folding.py [CaseFolding.txt]
+
+指定 Unicode 官方的 CaseFolding.txt 时从中取出状态为 C 和 F 的映射；不指定时由 Python 的
+unicodedata 推导（str.casefold 即 C 和 F 映射），此时要求 Python 的 Unicode 数据版本为 UNICODE_VERSION。
+两种方式得到的结果相同，生成后 `cargo test` 中的 test_case_folding_complete 会逐项核对。
+"""
+
+import re
+import sys
+import unicodedata
+from pathlib import Path
+
+UNICODE_VERSION = "14.0.0"
+ROOT = Path(__file__).resolve().parent.parent
+DATA = ROOT / "src" / "utils" / "case_folding.txt"
+TABLE = ROOT / "src" / "utils" / "casefold.rs"
+
+
+def from_official(path):
+    """从官方 CaseFolding.txt 中读取 C 和 F 映射。"""
+    text = Path(path).read_text(encoding="utf-8")
+    version = re.search(r"CaseFolding-(\d+\.\d+\.\d+)\.txt", text)
+    if not version or version.group(1) != UNICODE_VERSION:
+        sys.exit(f"需要 CaseFolding-{UNICODE_VERSION}.txt")
+    folds = {}
+    for line in text.splitlines():
+        line = line.split("#", 1)[0].strip()
+        if not line:
+            continue
+        code, status, mapping = (field.strip() for field in line.split(";")[:3])
+        if status in ("C", "F"):
+            folds[int(code, 16)] = "".join(chr(int(c, 16)) for c in mapping.split())
+    return folds
+
+
+def from_unicodedata():
+    """由 str.casefold 推导 C 和 F 映射。"""
+    if unicodedata.unidata_version != UNICODE_VERSION:
+        sys.exit(f"Python 的 Unicode 数据版本为 {unicodedata.unidata_version}，需要 {UNICODE_VERSION}")
+    folds = {}
+    for code in range(0x110000):
+        if 0xD800 <= code <= 0xDFFF:
+            continue
+        folded = chr(code).casefold()
+        if folded != chr(code):
+            folds[code] = folded
+    return folds
+
+
+def write_data(folds):
+    lines = [
+        f"# CaseFolding-{UNICODE_VERSION}.txt 中状态为 C 和 F 的映射，由 scripts/gen_case_folding.py 生成。",
+        "# 格式与 CaseFolding.txt 相同: <code>; <status>; <mapping>; # <name>",
+        "",
+    ]
+    for code, folded in sorted(folds.items()):
+        status = "F" if len(folded) > 1 else "C"
+        mapping = " ".join(f"{ord(c):04X}" for c in folded)
+        lines.append(f"{code:04X}; {status}; {mapping}; # {unicodedata.name(chr(code), '')}")
+    DATA.write_text("\n".join(lines) + "\n", encoding="utf-8")
+
+
+def escape(s):
+    return "".join(c if c.isascii() and c.isprintable() else f"\\u{{{ord(c):x}}}" for c in s)
+
+
+def write_table(folds):
+    source = TABLE.read_text(encoding="utf-8")
+    start = source.index("const FOLDS: &[(char, &str)] = &[\n") + len("const FOLDS: &[(char, &str)] = &[\n")
+    end = source.index("];\n", start)
+    rows = "".join(f"    ('\\u{{{code:x}}}', \"{escape(folded)}\"),\n" for code, folded in sorted(folds.items()))
+    TABLE.write_text(source[:start] + rows + source[end:], encoding="utf-8")
+
+
+def main():
+    folds = from_official(sys.argv[1]) if len(sys.argv) > 1 else from_unicodedata()
+    write_data(folds)
+    write_table(folds)
+    print(f"{len(folds)} 项映射")
+
+
+if __name__ == "__main__":
+    main()
@@ -10,67 +10,172 @@
 //! ### 使用示例
 //!
 //! ```rust
-//! use crate::bstree::{BSTNode, BSTree, Order};
+//! use bst_words::bst::BSTree;
 //! let mut bstree = BSTree::new();
-//! bstree.insert_fn(1, |n| {});
-//! bstree.insert_fn(2, |n| {});
-//! let node = bstree.find(1);
+//! bstree.insert_fn(1, |_| {});
+//! bstree.insert_fn(2, |_| {});
+//! let node = bstree.find(&1);
 //! ```
 //!
 //! ## 二叉搜索树结构体 BSTree
 //!
-//! 表示二叉搜索树，包括根结点。
+//! 表示二叉搜索树，包括根结点和平衡策略。
 //!
 //! ### 使用示例
 //!
 //! ```rust
-//! use crate::bstree::{BSTNode, BSTree, Order};
+//! use bst_words::bst::BSTree;
+//! use bst_words::r#type::Order;
 //! let mut bstree = BSTree::new();
-//! bstree.insert_fn(1, |n| {});
-//! bstree.insert_fn(2, |n| {});
-//! bstree.insert_fn(3, |n| {});
+//! bstree.insert_fn(1, |_| {});
+//! bstree.insert_fn(2, |_| {});
+//! bstree.insert_fn(3, |_| {});
 //! bstree.traverse(Order::In);
 //! ```
 //!
 //! ## BSTree 结构体实现的方法
 //!
-//! - `new() -> BSTree<T>`: 创建一个新的二叉搜索树实例。
+//! - `new() -> BSTree<T>`: 创建一个新的二叉搜索树实例（不做平衡）。
+//! - `with_balance(balance: Balance) -> BSTree<T>`: 创建一个使用指定平衡策略的二叉搜索树实例。
+//! - `from_sorted(data: Vec<T>, balance: Balance) -> Result<BSTree<T>, String>`: 由严格递增的数据构建完全平衡的树。
 //! - `insert_fn(data: T, f: impl FnMut(&mut Box<BSTNode<T>>))`: 插入结点并执行指定操作。
-//! - `find(data: T) -> Option<&BSTNode<T>>`: 查找指定数据的结点。
-//! - `delete(data: T) -> Result<(), String>`: 删除指定数据的结点。
+//! - `find(data: &Q) -> Option<&BSTNode<T>>`: 查找指定数据的结点。
+//! - `delete(data: &Q) -> Result<(), String>`: 删除指定数据的结点。
+//!
+//! `find` 和 `delete` 的参数可以是数据的借用形式 `Q`（要求 `T: Borrow<Q>`），例如 `BSTree<Word>` 可以直接用 `&str` 查找，
+//! 不需要为每次查询构造一个 `Word`。
+//!
 //! - `traverse(order: Order)`: 遍历二叉搜索树，打印结点数据。
+//! - `traverse_levels()`: 按层遍历二叉搜索树，每一层打印一行。
+//! - `iter()` / `iter_mut()` / `into_iter()`: 按中序遍历数据的迭代器（引用、可变引用、所有权）。
+//! - `pre_order()` / `in_order()` / `post_order()` / `level_order()`: 各种遍历顺序的迭代器，
+//!   其中 `in_order()` 为双端迭代器。
+//! - `levels()`: 逐层返回数据的迭代器；`iter_order(order: Order)`: 按 `Order` 选择遍历顺序的迭代器。
+//! - `map(f: impl FnMut(&T) -> U) -> BSTree<U>`: 对每个结点的数据应用 `f`，返回形状相同的新树；`map_into` 取得数据的所有权。
+//!
+//! ### 顺序统计
+//!
+//! 每个结点记录以它为根的子树大小，插入、删除和旋转时随高度一起更新，因此以下查询只需沿一条路径下降，
+//! 时间为 O(树高)，使用 AVL 平衡时为 O(log n)：
+//!
+//! - `len() -> usize`: 结点个数，O(1)；`is_empty() -> bool`: 是否为空。
+//! - `rank(key: &Q) -> usize`: 小于 `key` 的数据个数，如按字母序排在 "m" 之前的单词数。
+//! - `select(k: usize) -> Option<&T>`: 第 `k` 小的数据（从 0 开始），如按字母序的第 500 个单词为 `select(499)`。
+//! - `count_range(range: impl RangeBounds<Q>) -> usize`: 落在区间中的数据个数。
+//!
+//! ### 最值和相邻数据
+//!
+//! - `min()` / `max() -> Option<&T>`: 最小和最大的数据；`pop_min()` / `pop_max() -> Option<T>`: 删除并返回最小和最大的数据。
+//! - `floor(key: &Q)` / `ceiling(key: &Q) -> Option<&T>`: 不大于 `key` 的最大数据和不小于 `key` 的最小数据。
+//! - `predecessor(key: &Q)` / `successor(key: &Q) -> Option<&T>`: 严格小于 `key` 的最大数据和严格大于 `key` 的最小数据，
+//!   查询的单词不在树中时可以用它们给出字母序相邻的单词。
+//!
+//! ```rust
+//! use bst_words::bst::BSTree;
+//! use bst_words::r#type::Balance;
+//! let mut bstree = BSTree::with_balance(Balance::Avl);
+//! for i in [10, 20, 30, 40] {
+//!     bstree.insert_fn(i, |_| {});
+//! }
+//! assert_eq!(bstree.floor(&25), Some(&20));
+//! assert_eq!(bstree.ceiling(&30), Some(&30));
+//! assert_eq!(bstree.successor(&30), Some(&40));
+//! assert_eq!(bstree.predecessor(&10), None);
+//! assert_eq!(bstree.pop_min(), Some(10));
+//! assert_eq!(bstree.max(), Some(&40));
+//! ```
+//!
+//! ### 区间和前缀查询
+//!
+//! - `range(range: impl RangeBounds<Q>) -> Range<T>`: 按中序遍历落在区间中的数据的双端迭代器，如 `range(10..20)`。
+//! - `prefix(prefix: &str) -> Range<T>`: 数据可以借用为 `str` 时（如 `Word`），按字典序遍历以 `prefix` 开头的数据。
+//!
+//! ```rust
+//! use bst_words::bst::BSTree;
+//! use bst_words::r#type::{Balance, Word};
+//! let mut bstree = BSTree::with_balance(Balance::Avl);
+//! for word in ["internal", "apple", "interval", "inter", "into", "zoo"] {
+//!     bstree.insert_fn(Word::new(word.to_string()), |_| {});
+//! }
+//! let words: Vec<&str> = bstree.prefix("inter").map(|w| w.value.as_str()).collect();
+//! assert_eq!(words, ["inter", "internal", "interval"]);
+//! let numbers = BSTree::from_sorted((0..100).collect(), Balance::Avl).unwrap();
+//! assert!(numbers.range(10..20).copied().eq(10..20));
+//! ```
+//!
+//! ```rust
+//! use bst_words::bst::BSTree;
+//! use bst_words::r#type::Balance;
+//! let mut bstree = BSTree::with_balance(Balance::Avl);
+//! for i in (0..100).map(|i| i * 2) {
+//!     bstree.insert_fn(i, |_| {});
+//! }
+//! assert_eq!(bstree.len(), 100);
+//! assert_eq!(bstree.rank(&51), 26);
+//! assert_eq!(bstree.select(26), Some(&52));
+//! assert_eq!(bstree.count_range(10..=20), 6);
+//! ```
+//!
+//! ### 迭代器示例
+//!
+//! ```rust
+//! use bst_words::bst::BSTree;
+//! let mut bstree = BSTree::new();
+//! for i in [2, 1, 3] {
+//!     bstree.insert_fn(i, |_| {});
+//! }
+//! let sorted: Vec<i32> = bstree.iter().copied().collect();
+//! assert_eq!(sorted, [1, 2, 3]);
+//! assert_eq!(bstree.in_order().rev().next(), Some(&3));
+//! assert_eq!(bstree.pre_order().next(), Some(&2));
+//! ```
 //!
 //! ## 使用注意事项
 //!
 //! - `T` 类型必须实现 `Ord`, `Clone`, `Display` trait。
+//! - 默认的 `BSTree::new()` 不做平衡，有序输入会使树退化成链表；
+//!   使用 `BSTree::with_balance(Balance::Avl)` 可让插入、查找、删除保持 O(log n)。
+//! - 所有操作（包括复制和析构）都使用显式栈迭代实现，即使树退化成很深的链表也不会栈溢出。
 //!
 //! ## 示例
 //!
 //! ```rust
-//! use crate::bstree::{BSTNode, BSTree, Order};
+//! use bst_words::bst::BSTree;
+//! use bst_words::r#type::Order;
 //!
 //! let mut bstree = BSTree::new();
-//! bstree.insert_fn(1, |n| {});
-//! bstree.insert_fn(2, |n| {});
-//! bstree.insert_fn(3, |n| {});
-//! let node = bstree.find(2);
+//! bstree.insert_fn(1, |_| {});
+//! bstree.insert_fn(2, |_| {});
+//! bstree.insert_fn(3, |_| {});
+//! let node = bstree.find(&2);
 //! match node {
 //!     Some(n) => println!("Found: {}", n.data),
 //!     None => println!("Not found."),
 //! }
-//! bstree.delete(2).unwrap();
+//! bstree.delete(&2).unwrap();
 //! println!("Deleted.");
 //! bstree.traverse(Order::In);
 //! ```
 //!
-// 引入r#type模块中的Order枚举类型，表示二叉树的遍历顺序
-use crate::r#type::Order;
-// 引入serde库中的Deserialize和Serialize两个trait，它们用于实现JSON的反序列化和序列化功能
-use serde::{Deserialize, Serialize};
-// 引入std库中的fmt模块，它用于实现格式化输出的功能
-use std::fmt::Display;
+// 引入r#type模块中的Balance和Order枚举类型，分别表示二叉树的平衡策略和遍历顺序
+use crate::r#type::{Balance, Order};
+// 引入serde库中的Deserialize和Serialize两个trait，它们用于实现JSON的反序列化和序列化功能，以及手动实现这两个trait所需的类型
+use serde::{
+    de::{self, IgnoredAny, MapAccess, Visitor},
+    ser::SerializeStruct,
+    Deserialize, Deserializer, Serialize, Serializer,
+};
+// 引入std库中的borrow模块、cmp模块、fmt模块和ops模块，它们用于以借用形式查找、比较大小、格式化输出和表示区间（包括前缀查询）
+use std::{
+    borrow::Borrow,
+    cmp::Ordering,
+    collections::VecDeque,
+    fmt::{self, Display},
+    marker::PhantomData,
+    ops::{Bound, RangeBounds},
+};
 
-/// 表示二叉搜索树的结点，包括结点的数据、左子结点和右子结点。
+/// 表示二叉搜索树的结点，包括结点的数据、左子结点、右子结点，以及以该结点为根的子树高度和结点个数。
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct BSTNode<T> {
     /// 结点的数据
@@ -79,6 +184,20 @@ pub struct BSTNode<T> {
     pub left: Option<Box<BSTNode<T>>>,
     /// 结点的右子结点
     pub right: Option<Box<BSTNode<T>>>,
+    /// 以该结点为根的子树高度，叶子结点为 1（旧缓存中没有该字段时默认为 1）
+    #[serde(default = "default_height")]
+    pub height: usize,
+    /// 以该结点为根的子树中的结点个数，叶子结点为 1（旧缓存中没有该字段时默认为 1）
+    #[serde(default = "default_size")]
+    pub size: usize,
+}
+
+fn default_height() -> usize {
+    1
+}
+
+fn default_size() -> usize {
+    1
 }
 
 impl<T> BSTNode<T> {
@@ -88,143 +207,1361 @@ impl<T> BSTNode<T> {
             data,
             left: None,
             right: None,
+            height: 1,
+            size: 1,
         }
     }
+
+    /// 根据左右子结点重新计算该结点的高度和子树大小。
+    fn update(&mut self) {
+        self.height = 1 + height(&self.left).max(height(&self.right));
+        self.size = 1 + size(&self.left) + size(&self.right);
+    }
+
+    /// 计算该结点的平衡因子，即左子树高度减去右子树高度。
+    fn balance_factor(&self) -> isize {
+        height(&self.left) as isize - height(&self.right) as isize
+    }
 }
 
-/// 表示二叉搜索树，包括根结点。
-#[derive(Serialize, Deserialize, Debug, Clone)]
+/// 获取子树的高度，空子树高度为 0。
+fn height<T>(node: &Option<Box<BSTNode<T>>>) -> usize {
+    node.as_ref().map_or(0, |n| n.height)
+}
+
+/// 获取子树中的结点个数，空子树为 0。
+fn size<T>(node: &Option<Box<BSTNode<T>>>) -> usize {
+    node.as_ref().map_or(0, |n| n.size)
+}
+
+/// 以 `node` 为根进行右旋，旋转后 `node` 指向原来的左子结点。
+fn rotate_right<T>(node: &mut Box<BSTNode<T>>) {
+    if let Some(mut left) = node.left.take() {
+        node.left = left.right.take();
+        node.update();
+        std::mem::swap(node, &mut left);
+        node.right = Some(left);
+        node.update();
+    }
+}
+
+/// 以 `node` 为根进行左旋，旋转后 `node` 指向原来的右子结点。
+fn rotate_left<T>(node: &mut Box<BSTNode<T>>) {
+    if let Some(mut right) = node.right.take() {
+        node.right = right.left.take();
+        node.update();
+        std::mem::swap(node, &mut right);
+        node.left = Some(right);
+        node.update();
+    }
+}
+
+/// 更新结点高度和子树大小，并在 AVL 模式下通过旋转恢复平衡，返回所做的旋转。
+fn rebalance<T>(node: &mut Box<BSTNode<T>>, balance: Balance) -> Option<Rotation> {
+    node.update();
+    if balance != Balance::Avl {
+        return None;
+    }
+    let factor = node.balance_factor();
+    if factor > 1 {
+        // 左子树过高，若为左右型先对左子结点左旋
+        let double = node.left.as_ref().is_some_and(|l| l.balance_factor() < 0);
+        if double {
+            rotate_left(node.left.as_mut().unwrap());
+        }
+        rotate_right(node);
+        Some(Rotation {
+            side: Dir::Left,
+            double,
+        })
+    } else if factor < -1 {
+        // 右子树过高，若为右左型先对右子结点右旋
+        let double = node.right.as_ref().is_some_and(|r| r.balance_factor() > 0);
+        if double {
+            rotate_right(node.right.as_mut().unwrap());
+        }
+        rotate_left(node);
+        Some(Rotation {
+            side: Dir::Right,
+            double,
+        })
+    } else {
+        None
+    }
+}
+
+/// 表示下降时走向的子结点方向。
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum Dir {
+    Left,
+    Right,
+}
+
+impl Dir {
+    /// 相反的方向。
+    fn opposite(self) -> Dir {
+        match self {
+            Dir::Left => Dir::Right,
+            Dir::Right => Dir::Left,
+        }
+    }
+}
+
+/// `rebalance` 所做的旋转：`side` 一侧的子树过高，`double` 表示先对该侧的子结点做了反向的旋转。
+#[derive(Clone, Copy)]
+struct Rotation {
+    side: Dir,
+    double: bool,
+}
+
+impl Rotation {
+    /// 更新某个结点相对于旋转处结点的路径，使它在旋转后仍然指向同一个结点。
+    fn apply(self, pos: &mut VecDeque<Dir>) {
+        if self.double && pos.front() == Some(&self.side) {
+            pos.pop_front();
+            rotate_path(pos, self.side.opposite());
+            pos.push_front(self.side);
+        }
+        rotate_path(pos, self.side);
+    }
+}
+
+/// 一次单旋把 `up` 一侧的子结点转为子树的根后，更新某个结点相对于子树根的路径。
+fn rotate_path(pos: &mut VecDeque<Dir>, up: Dir) {
+    if pos.front() != Some(&up) {
+        // 原来的根及其另一侧的子树整体下降一层
+        pos.push_front(up.opposite());
+        return;
+    }
+    pos.pop_front();
+    if pos.front() == Some(&up.opposite()) {
+        // 转上来的子结点内侧的子树改挂到原来的根下
+        pos.pop_front();
+        pos.push_front(up);
+        pos.push_front(up.opposite());
+    }
+}
+
+/// 沿着下降路径自底向上把子树重新接回父结点，并逐层更新高度和子树大小、恢复平衡，返回新的根。
+///
+/// `path` 中保存的是下降过程中从树上摘下来的结点以及下降的方向，栈顶是最深的结点。
+fn reattach<T>(
+    mut path: Vec<(Box<BSTNode<T>>, Dir)>,
+    mut child: Option<Box<BSTNode<T>>>,
+    balance: Balance,
+) -> Option<Box<BSTNode<T>>> {
+    while let Some((mut parent, dir)) = path.pop() {
+        match dir {
+            Dir::Left => parent.left = child,
+            Dir::Right => parent.right = child,
+        }
+        rebalance(&mut parent, balance);
+        child = Some(parent);
+    }
+    child
+}
+
+/// 从子树中摘下最小（`dir` 为 `Dir::Left`）或最大（`dir` 为 `Dir::Right`）的结点，
+/// 返回该结点（已断开左右子结点）和剩余的子树。
+fn pop_end_node<T>(
+    mut node: Box<BSTNode<T>>,
+    dir: Dir,
+    balance: Balance,
+) -> (Box<BSTNode<T>>, Option<Box<BSTNode<T>>>) {
+    let mut path = Vec::new();
+    loop {
+        let next = match dir {
+            Dir::Left => node.left.take(),
+            Dir::Right => node.right.take(),
+        };
+        match next {
+            Some(child) => {
+                path.push((node, dir));
+                node = child;
+            }
+            None => break,
+        }
+    }
+    let rest = match dir {
+        Dir::Left => node.right.take(),
+        Dir::Right => node.left.take(),
+    };
+    node.height = 1;
+    node.size = 1;
+    (node, reattach(path, rest, balance))
+}
+
+/// 表示二叉搜索树，包括根结点和平衡策略。
+///
+/// 序列化时不再输出嵌套的结点，而是输出 `{"balance": .., "preorder": [..]}`，
+/// 其中 `preorder` 为前序遍历的数据序列，可以唯一地还原出树的形状，且不受树高限制。
+/// 反序列化时同时兼容旧版本嵌套的 `{"root": {"data": .., "left": .., "right": ..}}` 格式。
+#[derive(Debug)]
 pub struct BSTree<T> {
     /// 树的根结点
     pub root: Option<Box<BSTNode<T>>>,
+    /// 树的平衡策略
+    pub balance: Balance,
+}
+
+impl<T> Drop for BSTree<T> {
+    /// 逐个释放结点，避免退化成链表的深树在递归析构时栈溢出。
+    fn drop(&mut self) {
+        let mut stack: Vec<Box<BSTNode<T>>> = self.root.take().into_iter().collect();
+        while let Some(mut node) = stack.pop() {
+            stack.extend(node.left.take());
+            stack.extend(node.right.take());
+        }
+    }
+}
+
+impl<T: Clone> Clone for BSTree<T> {
+    /// 按后序逐个复制结点，避免深树在递归复制时栈溢出。
+    fn clone(&self) -> Self {
+        self.map(T::clone)
+    }
+}
+
+impl<T: Serialize> Serialize for BSTree<T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.serialize_with(serializer, |data| data)
+    }
+}
+
+impl<T> BSTree<T> {
+    /// 按 `BSTree<U>` 的格式序列化，每个数据先经 `f` 转换，如把 `KeyValue` 转换为借用其字段的视图，
+    /// 不需要先复制出一棵新树。
+    pub(crate) fn serialize_with<'a, S, U, F>(
+        &'a self,
+        serializer: S,
+        f: F,
+    ) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+        U: Serialize,
+        F: Fn(&'a T) -> U,
+    {
+        /// 将前序遍历序列化为数组的包装
+        struct PreOrderSeq<'a, T, F>(&'a BSTree<T>, F);
+
+        impl<'a, T, U, F> Serialize for PreOrderSeq<'a, T, F>
+        where
+            U: Serialize,
+            F: Fn(&'a T) -> U,
+        {
+            fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                serializer.collect_seq(self.0.pre_order().map(&self.1))
+            }
+        }
+
+        let mut state = serializer.serialize_struct("BSTree", 2)?;
+        state.serialize_field("balance", &self.balance)?;
+        state.serialize_field("preorder", &PreOrderSeq(self, f))?;
+        state.end()
+    }
+}
+
+impl<'de, T> Deserialize<'de> for BSTree<T>
+where
+    T: Deserialize<'de> + Ord,
+{
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct TreeVisitor<T>(PhantomData<T>);
+
+        impl<'de, T> Visitor<'de> for TreeVisitor<T>
+        where
+            T: Deserialize<'de> + Ord,
+        {
+            type Value = BSTree<T>;
+
+            fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                f.write_str("a BSTree with a `preorder` sequence or a nested `root` node")
+            }
+
+            fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Self::Value, A::Error> {
+                let mut balance = Balance::None;
+                let mut preorder = None;
+                while let Some(key) = map.next_key::<String>()? {
+                    match key.as_str() {
+                        "balance" => balance = map.next_value()?,
+                        "preorder" => preorder = Some(map.next_value()?),
+                        // 旧版本的嵌套格式，先展开成前序序列再统一重建
+                        "root" => preorder = Some(into_pre_order(map.next_value()?)),
+                        _ => {
+                            map.next_value::<IgnoredAny>()?;
+                        }
+                    }
+                }
+                let preorder = preorder.ok_or_else(|| de::Error::missing_field("preorder"))?;
+                let root = build_from_pre_order(preorder).map_err(de::Error::custom)?;
+                Ok(BSTree { root, balance })
+            }
+        }
+
+        deserializer.deserialize_struct(
+            "BSTree",
+            &["balance", "preorder", "root"],
+            TreeVisitor(PhantomData),
+        )
+    }
+}
+
+/// 按前序拆开一棵子树，返回数据序列。
+fn into_pre_order<T>(root: Option<Box<BSTNode<T>>>) -> Vec<T> {
+    let mut output = Vec::new();
+    let mut stack: Vec<Box<BSTNode<T>>> = root.into_iter().collect();
+    while let Some(node) = stack.pop() {
+        let BSTNode {
+            data, left, right, ..
+        } = *node;
+        output.push(data);
+        stack.extend(right);
+        stack.extend(left);
+    }
+    output
+}
+
+/// 由前序遍历序列重建二叉搜索树，并计算每个结点的高度和子树大小。
+///
+/// 序列中的数据必须两两不同，且确实是某棵二叉搜索树的前序遍历，否则返回错误。
+fn build_from_pre_order<T: Ord>(data: Vec<T>) -> Result<Option<Box<BSTNode<T>>>, String> {
+    // 先用单调栈确定每个位置的左右子结点下标
+    let len = data.len();
+    let mut left = vec![None; len];
+    let mut right = vec![None; len];
+    let mut stack: Vec<usize> = Vec::new();
+    // 后续数据必须大于的下界（最近一次向右转的祖先）
+    let mut lower: Option<usize> = None;
+    for i in 0..len {
+        if lower.is_some_and(|lo| data[i] <= data[lo]) {
+            return Err(format!(
+                "preorder sequence is not a binary search tree at index {i}"
+            ));
+        }
+        let mut parent = None;
+        while let Some(&top) = stack.last() {
+            if data[top] < data[i] {
+                parent = stack.pop();
+            } else {
+                break;
+            }
+        }
+        if let Some(&top) = stack.last() {
+            if data[top] == data[i] {
+                return Err(format!("preorder sequence has a duplicate at index {i}"));
+            }
+        }
+        match parent {
+            Some(p) => {
+                right[p] = Some(i);
+                lower = Some(p);
+            }
+            None => {
+                if let Some(&top) = stack.last() {
+                    left[top] = Some(i);
+                }
+            }
+        }
+        stack.push(i);
+    }
+
+    // 子结点的下标总是大于父结点，倒序构建即可保证子树先于父结点完成
+    let mut built: Vec<Option<Box<BSTNode<T>>>> = (0..len).map(|_| None).collect();
+    for (i, data) in data.into_iter().enumerate().rev() {
+        let mut node = BSTNode::new(data);
+        node.left = left[i].and_then(|c| built[c].take());
+        node.right = right[i].and_then(|c| built[c].take());
+        node.update();
+        built[i] = Some(Box::new(node));
+    }
+    Ok(built.into_iter().next().flatten())
+}
+
+impl<T> Default for BSTree<T>
+where
+    T: Ord + Clone + Display,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: Ord> BSTree<T> {
+    /// 由严格递增的数据构建一棵完全平衡的二叉搜索树，树高为 ⌈log2(n + 1)⌉。
+    ///
+    /// 数据不是严格递增（存在乱序或重复）时返回错误。
+    pub fn from_sorted(data: Vec<T>, balance: Balance) -> Result<BSTree<T>, String> {
+        if let Some(i) = data.windows(2).position(|w| w[0] >= w[1]) {
+            return Err(format!(
+                "data is not strictly increasing at index {}",
+                i + 1
+            ));
+        }
+        // 每次取区间中点作为子树的根，得到平衡树的前序下标序列
+        let mut order = Vec::with_capacity(data.len());
+        let mut ranges = vec![(0, data.len())];
+        while let Some((lo, hi)) = ranges.pop() {
+            if lo < hi {
+                let mid = lo + (hi - lo) / 2;
+                order.push(mid);
+                ranges.push((mid + 1, hi));
+                ranges.push((lo, mid));
+            }
+        }
+        let mut data: Vec<Option<T>> = data.into_iter().map(Some).collect();
+        let preorder = order.into_iter().filter_map(|i| data[i].take()).collect();
+        Ok(BSTree {
+            root: build_from_pre_order(preorder)?,
+            balance,
+        })
+    }
 }
 
 impl<T> BSTree<T>
 where
     T: Ord + Clone + Display,
 {
-    /// 创建一个新的二叉搜索树实例。
+    /// 创建一个新的二叉搜索树实例，不做平衡。
     pub fn new() -> BSTree<T> {
-        BSTree { root: None }
+        Self::with_balance(Balance::None)
+    }
+
+    /// 创建一个使用指定平衡策略的二叉搜索树实例。
+    pub fn with_balance(balance: Balance) -> BSTree<T> {
+        BSTree {
+            root: None,
+            balance,
+        }
+    }
+
+    /// 获取树的高度，空树高度为 0。
+    pub fn height(&self) -> usize {
+        height(&self.root)
     }
 
     /// 插入结点并执行指定操作。
+    ///
+    /// 若树中已存在相等的数据，则不插入新结点，而是对已存在的结点调用 `f`。
     pub fn insert_fn(&mut self, data: T, mut f: impl FnMut(&mut Box<BSTNode<T>>)) {
-        fn insert_node<T: Ord>(
-            node: &mut Option<Box<BSTNode<T>>>,
-            data: T,
-            f: &mut impl FnMut(&mut Box<BSTNode<T>>),
-        ) {
-            match node {
-                None => *node = Some(Box::new(BSTNode::new(data))),
-                Some(node) => {
-                    if data < node.data {
-                        insert_node(&mut node.left, data, f);
-                    } else if data > node.data {
-                        insert_node(&mut node.right, data, f);
-                    } else {
-                        f(node);
-                    }
-                }
+        self.insert_with(data, T::cmp, |node, _| f(node));
+    }
+
+    /// 查找指定数据的结点，`data` 可以是数据的借用形式，如 `BSTree<Word>` 中的 `&str`。
+    pub fn find<Q>(&self, data: &Q) -> Option<&BSTNode<T>>
+    where
+        T: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        self.search(|d| data.cmp(d.borrow()))
+    }
+
+    /// 删除指定数据的结点，`data` 可以是数据的借用形式。
+    pub fn delete<Q>(&mut self, data: &Q) -> Result<(), String>
+    where
+        T: Borrow<Q>,
+        Q: Ord + Display + ?Sized,
+    {
+        match self.remove_with(|d| data.cmp(d.borrow())) {
+            Some(_) => Ok(()),
+            None => Err(format!("Node of data: {data} not found")),
+        }
+    }
+
+    /// 遍历二叉搜索树，打印结点数据。
+    pub fn traverse(&self, order: Order) {
+        fn print_all<'a, T: Display + 'a>(iter: impl Iterator<Item = &'a T>) {
+            for data in iter {
+                print!("{} ", data);
             }
+            println!();
         }
-        insert_node(&mut self.root, data, &mut f);
-    }
-
-    /// 查找指定数据的结点。
-    pub fn find(&self, data: T) -> Option<&BSTNode<T>> {
-        fn find_node<T: Ord + Display>(
-            node: &Option<Box<BSTNode<T>>>,
-            data: T,
-        ) -> Option<&BSTNode<T>> {
-            match node {
-                None => None,
-                Some(node) => {
-                    if data < node.data {
-                        find_node(&node.left, data)
-                    } else if data > node.data {
-                        find_node(&node.right, data)
-                    } else {
-                        Some(node.as_ref())
-                    }
-                }
+        print_all(self.iter_order(order));
+    }
+
+    /// 按层遍历二叉搜索树，每一层的结点数据单独打印一行，行首为层号，同一层的数据之间用 " | " 分隔。
+    pub fn traverse_levels(&self) {
+        for (depth, level) in self.levels().enumerate() {
+            let line: Vec<String> = level.iter().map(|data| data.to_string()).collect();
+            println!("{depth}: {}", line.join(" | "));
+        }
+    }
+}
+
+impl<T> BSTree<T> {
+    /// 按 `cmp` 查找结点，`cmp` 返回要找的目标相对于结点数据的大小关系。
+    pub(crate) fn search(&self, mut cmp: impl FnMut(&T) -> Ordering) -> Option<&BSTNode<T>> {
+        let mut cur = self.root.as_deref();
+        while let Some(node) = cur {
+            match cmp(&node.data) {
+                Ordering::Less => cur = node.left.as_deref(),
+                Ordering::Greater => cur = node.right.as_deref(),
+                Ordering::Equal => return Some(node),
             }
         }
-        find_node(&self.root, data)
-    }
-
-    /// 删除指定数据的结点。
-    pub fn delete(&mut self, data: T) -> Result<(), String> {
-        fn delete_node<T: Ord + Clone + Display>(
-            node: &mut Option<Box<BSTNode<T>>>,
-            data: T,
-        ) -> Result<(), String> {
-            match node {
-                None => Err(format!("Node of data: {data} not found")),
-                Some(n) => {
-                    if data < n.data {
-                        delete_node(&mut n.left, data)
-                    } else if data > n.data {
-                        delete_node(&mut n.right, data)
-                    } else {
-                        if n.left.is_none() && n.right.is_none() {
-                            *node = None;
-                        } else if n.left.is_some() && n.right.is_none() {
-                            *node = n.left.take();
-                        } else if n.left.is_none() && n.right.is_some() {
-                            *node = n.right.take();
-                        } else if n.left.is_some() && n.right.is_some() {
-                            n.data = min_node(&n.right).unwrap().clone();
-                            delete_node(&mut n.right, n.data.clone())?;
-                        }
-                        Ok(())
-                    }
-                }
+        None
+    }
+
+    /// 按 `cmp` 查找结点并返回可变引用，调用者不能修改数据中参与比较的部分。
+    pub(crate) fn search_mut(
+        &mut self,
+        mut cmp: impl FnMut(&T) -> Ordering,
+    ) -> Option<&mut BSTNode<T>> {
+        let mut cur = self.root.as_deref_mut();
+        while let Some(node) = cur {
+            match cmp(&node.data) {
+                Ordering::Less => cur = node.left.as_deref_mut(),
+                Ordering::Greater => cur = node.right.as_deref_mut(),
+                Ordering::Equal => return Some(node),
             }
         }
+        None
+    }
 
-        fn min_node<T>(node: &Option<Box<BSTNode<T>>>) -> Result<&T, String> {
-            match node {
-                None => Err("Node is empty".to_string()),
-                Some(n) => {
-                    if n.left.is_none() {
-                        Ok(&n.data)
-                    } else {
-                        min_node(&n.left)
+    /// 按 `cmp` 插入数据；已存在相等的数据时不插入，而是把已存在的结点和新数据交给 `f`。
+    pub(crate) fn insert_with(
+        &mut self,
+        data: T,
+        cmp: impl Fn(&T, &T) -> Ordering,
+        f: impl FnOnce(&mut Box<BSTNode<T>>, T),
+    ) {
+        let mut path = Vec::new();
+        let mut cur = self.root.take();
+        let node = loop {
+            match cur {
+                None => break Box::new(BSTNode::new(data)),
+                Some(mut n) => match cmp(&data, &n.data) {
+                    Ordering::Less => {
+                        cur = n.left.take();
+                        path.push((n, Dir::Left));
+                    }
+                    Ordering::Greater => {
+                        cur = n.right.take();
+                        path.push((n, Dir::Right));
                     }
+                    Ordering::Equal => {
+                        f(&mut n, data);
+                        break n;
+                    }
+                },
+            }
+        };
+        self.root = reattach(path, Some(node), self.balance);
+    }
+
+    /// 按 `cmp` 查找数据，返回从根下降的方向：找到时为 `Ok(到该结点的路径)`，
+    /// 找不到时为 `Err(应插入的空位的路径)`。`cmp` 返回要找的目标相对于结点数据的大小关系。
+    pub(crate) fn locate(&self, mut cmp: impl FnMut(&T) -> Ordering) -> Result<Vec<Dir>, Vec<Dir>> {
+        let mut path = Vec::new();
+        let mut cur = self.root.as_deref();
+        while let Some(node) = cur {
+            match cmp(&node.data) {
+                Ordering::Less => {
+                    path.push(Dir::Left);
+                    cur = node.left.as_deref();
                 }
+                Ordering::Greater => {
+                    path.push(Dir::Right);
+                    cur = node.right.as_deref();
+                }
+                Ordering::Equal => return Ok(path),
             }
         }
+        Err(path)
+    }
 
-        delete_node(&mut self.root, data)
+    /// 沿 `path` 下降，获取到达的结点的数据，路径超出树时返回 `None`。
+    pub(crate) fn get_at(&self, path: &[Dir]) -> Option<&T> {
+        let mut node = self.root.as_deref()?;
+        for dir in path {
+            node = match dir {
+                Dir::Left => node.left.as_deref()?,
+                Dir::Right => node.right.as_deref()?,
+            };
+        }
+        Some(&node.data)
     }
 
-    /// 遍历二叉搜索树，打印结点数据。
-    pub fn traverse(&self, order: Order) {
-        fn traverse_node<T: Ord + Display>(node: &Option<Box<BSTNode<T>>>, order: Order) {
-            match node {
-                None => return,
-                Some(n) => match order {
-                    Order::Pre => {
-                        print!("{} ", n.data);
-                        traverse_node(&n.left, order.clone());
-                        traverse_node(&n.right, order);
+    /// 沿 `path` 下降，获取到达的结点的数据的可变引用，调用者不能修改数据中参与比较的部分。
+    pub(crate) fn get_at_mut(&mut self, path: &[Dir]) -> Option<&mut T> {
+        let mut node = self.root.as_deref_mut()?;
+        for dir in path {
+            node = match dir {
+                Dir::Left => node.left.as_deref_mut()?,
+                Dir::Right => node.right.as_deref_mut()?,
+            };
+        }
+        Some(&mut node.data)
+    }
+
+    /// 沿 `path` 下降到空位插入数据，不比较数据，返回恢复平衡后新结点的路径。
+    ///
+    /// `path` 必须是 `locate` 对同一数据返回的 `Err(path)`，且期间树没有被修改，否则得到的树不再是二叉搜索树。
+    pub(crate) fn insert_at(&mut self, path: &[Dir], data: T) -> Vec<Dir> {
+        let mut nodes = Vec::with_capacity(path.len());
+        let mut cur = self.root.take();
+        for &dir in path {
+            let mut n = cur.expect("插入路径经过的结点都存在");
+            cur = match dir {
+                Dir::Left => n.left.take(),
+                Dir::Right => n.right.take(),
+            };
+            nodes.push((n, dir));
+        }
+        let mut child = Box::new(BSTNode::new(data));
+        // 新结点相对于当前子树根的路径，自底向上接回父结点并旋转时随之更新
+        let mut pos = VecDeque::new();
+        while let Some((mut parent, dir)) = nodes.pop() {
+            match dir {
+                Dir::Left => parent.left = Some(child),
+                Dir::Right => parent.right = Some(child),
+            }
+            pos.push_front(dir);
+            if let Some(rotation) = rebalance(&mut parent, self.balance) {
+                rotation.apply(&mut pos);
+            }
+            child = parent;
+        }
+        self.root = Some(child);
+        pos.into()
+    }
+
+    /// 按 `cmp` 删除结点，返回被删除的数据，找不到时返回 `None`。
+    pub(crate) fn remove_with(&mut self, mut cmp: impl FnMut(&T) -> Ordering) -> Option<T> {
+        let mut path = Vec::new();
+        let mut cur = self.root.take();
+        let mut removed = None;
+        let replacement = loop {
+            match cur {
+                None => break None,
+                Some(mut n) => match cmp(&n.data) {
+                    Ordering::Less => {
+                        cur = n.left.take();
+                        path.push((n, Dir::Left));
                     }
-                    Order::In => {
-                        traverse_node(&n.left, order.clone());
-                        print!("{} ", n.data);
-                        traverse_node(&n.right, order);
+                    Ordering::Greater => {
+                        cur = n.right.take();
+                        path.push((n, Dir::Right));
                     }
-                    Order::Post => {
-                        traverse_node(&n.left, order.clone());
-                        traverse_node(&n.right, order);
-                        print!("{} ", n.data);
+                    Ordering::Equal => {
+                        let (left, right) = (n.left.take(), n.right.take());
+                        removed = Some(n.data);
+                        match (left, right) {
+                            (None, None) => break None,
+                            (Some(left), None) => break Some(left),
+                            (None, Some(right)) => break Some(right),
+                            (Some(left), Some(right)) => {
+                                // 用右子树中的最小结点顶替被删除的结点
+                                let (mut min, rest) = pop_end_node(right, Dir::Left, self.balance);
+                                min.left = Some(left);
+                                min.right = rest;
+                                rebalance(&mut min, self.balance);
+                                break Some(min);
+                            }
+                        }
                     }
                 },
             }
+        };
+        self.root = reattach(path, replacement, self.balance);
+        removed
+    }
+
+    /// 获取树中的结点个数，由根结点记录的子树大小直接得到，为 O(1)。
+    pub fn len(&self) -> usize {
+        size(&self.root)
+    }
+
+    /// 判断树是否为空。
+    pub fn is_empty(&self) -> bool {
+        self.root.is_none()
+    }
+
+    /// 获取小于 `key` 的数据个数，即 `key` 在中序序列中的排名（从 0 开始），`key` 不必在树中。
+    ///
+    /// 沿查找路径累加左子树的大小，时间为 O(树高)，AVL 平衡时为 O(log n)。
+    pub fn rank<Q>(&self, key: &Q) -> usize
+    where
+        T: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        self.count_below(key, false)
+    }
+
+    /// 获取中序序列中下标为 `k`（从 0 开始）的数据，即第 `k + 1` 小的数据，`k` 超出范围时返回 `None`。
+    ///
+    /// 与 `rank` 互逆：对树中的任意数据 `x`，`select(rank(x))` 就是 `x`。
+    pub fn select(&self, mut k: usize) -> Option<&T> {
+        let mut cur = self.root.as_deref();
+        while let Some(node) = cur {
+            let left = size(&node.left);
+            match k.cmp(&left) {
+                Ordering::Less => cur = node.left.as_deref(),
+                Ordering::Equal => return Some(&node.data),
+                Ordering::Greater => {
+                    k -= left + 1;
+                    cur = node.right.as_deref();
+                }
+            }
+        }
+        None
+    }
+
+    /// 获取落在 `range` 中的数据个数，如 `count_range(10..20)`，区间的端点不必在树中。
+    pub fn count_range<Q, R>(&self, range: R) -> usize
+    where
+        T: Borrow<Q>,
+        Q: Ord + ?Sized,
+        R: RangeBounds<Q>,
+    {
+        self.count_range_with(&range, |key, d| key.cmp(d.borrow()))
+    }
+
+    /// 获取小于 `key`（`inclusive` 为 `true` 时为小于等于）的数据个数。
+    fn count_below<Q>(&self, key: &Q, inclusive: bool) -> usize
+    where
+        T: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        self.count_below_with(|d| key.cmp(d.borrow()), inclusive)
+    }
+
+    /// 按 `cmp` 统计落在 `range` 中的数据个数，`cmp` 返回区间端点相对于结点数据的大小关系。
+    pub(crate) fn count_range_with<Q, R>(
+        &self,
+        range: &R,
+        cmp: impl Fn(&Q, &T) -> Ordering,
+    ) -> usize
+    where
+        Q: ?Sized,
+        R: RangeBounds<Q> + ?Sized,
+    {
+        let below = match range.start_bound() {
+            Bound::Included(start) => self.count_below_with(|d| cmp(start, d), false),
+            Bound::Excluded(start) => self.count_below_with(|d| cmp(start, d), true),
+            Bound::Unbounded => 0,
+        };
+        let upto = match range.end_bound() {
+            Bound::Included(end) => self.count_below_with(|d| cmp(end, d), true),
+            Bound::Excluded(end) => self.count_below_with(|d| cmp(end, d), false),
+            Bound::Unbounded => self.len(),
+        };
+        upto.saturating_sub(below)
+    }
+
+    /// 按 `cmp` 统计小于目标（`inclusive` 为 `true` 时为小于等于）的数据个数，`cmp` 返回目标相对于结点数据的大小关系。
+    ///
+    /// `cmp` 可以把多个数据视为与目标相等（如前缀查询），因此遇到相等的结点时继续下降。
+    pub(crate) fn count_below_with(
+        &self,
+        mut cmp: impl FnMut(&T) -> Ordering,
+        inclusive: bool,
+    ) -> usize {
+        let mut count = 0;
+        let mut cur = self.root.as_deref();
+        while let Some(node) = cur {
+            match cmp(&node.data) {
+                Ordering::Equal if !inclusive => cur = node.left.as_deref(),
+                Ordering::Less => cur = node.left.as_deref(),
+                Ordering::Greater | Ordering::Equal => {
+                    count += size(&node.left) + 1;
+                    cur = node.right.as_deref();
+                }
+            }
+        }
+        count
+    }
+
+    /// 按中序遍历落在 `range` 中的数据的双端迭代器，如 `range(10..20)`，区间的端点不必在树中。
+    ///
+    /// 创建时沿两条路径下降定位区间的两端，时间为 O(树高)，之后每一步均摊 O(1)。
+    pub fn range<Q, R>(&self, range: R) -> Range<'_, T>
+    where
+        T: Borrow<Q>,
+        Q: Ord + ?Sized,
+        R: RangeBounds<Q>,
+    {
+        self.range_with(range, |key, d| key.cmp(d.borrow()))
+    }
+
+    /// 按 `cmp` 遍历落在 `range` 中的数据，`cmp` 返回区间端点相对于结点数据的大小关系。
+    pub(crate) fn range_with<Q, R>(
+        &self,
+        range: R,
+        cmp: impl Fn(&Q, &T) -> Ordering,
+    ) -> Range<'_, T>
+    where
+        Q: ?Sized,
+        R: RangeBounds<Q>,
+    {
+        let remaining = self.count_range_with(&range, &cmp);
+        let mut iter = Iter::empty();
+        // 正向栈保留下降路径上不小于下界的结点，栈顶为区间中最小的数据
+        let mut node = self.root.as_deref();
+        while let Some(n) = node {
+            let above = match range.start_bound() {
+                Bound::Included(start) => cmp(start, &n.data) != Ordering::Greater,
+                Bound::Excluded(start) => cmp(start, &n.data) == Ordering::Less,
+                Bound::Unbounded => true,
+            };
+            if above {
+                iter.front.push(n);
+                node = n.left.as_deref();
+            } else {
+                node = n.right.as_deref();
+            }
+        }
+        // 反向栈保留下降路径上不大于上界的结点，栈顶为区间中最大的数据
+        let mut node = self.root.as_deref();
+        while let Some(n) = node {
+            let below = match range.end_bound() {
+                Bound::Included(end) => cmp(end, &n.data) != Ordering::Less,
+                Bound::Excluded(end) => cmp(end, &n.data) == Ordering::Greater,
+                Bound::Unbounded => true,
+            };
+            if below {
+                iter.back.push(n);
+                node = n.right.as_deref();
+            } else {
+                node = n.left.as_deref();
+            }
         }
-        traverse_node(&self.root, order);
-        println!();
+        Range { iter, remaining }
+    }
+
+    /// 获取最小的数据，空树返回 `None`。
+    pub fn min(&self) -> Option<&T> {
+        let mut node = self.root.as_deref()?;
+        while let Some(left) = node.left.as_deref() {
+            node = left;
+        }
+        Some(&node.data)
+    }
+
+    /// 获取最大的数据，空树返回 `None`。
+    pub fn max(&self) -> Option<&T> {
+        let mut node = self.root.as_deref()?;
+        while let Some(right) = node.right.as_deref() {
+            node = right;
+        }
+        Some(&node.data)
+    }
+
+    /// 删除并返回最小的数据，空树返回 `None`。
+    pub fn pop_min(&mut self) -> Option<T> {
+        let (node, rest) = pop_end_node(self.root.take()?, Dir::Left, self.balance);
+        self.root = rest;
+        Some(node.data)
+    }
+
+    /// 删除并返回最大的数据，空树返回 `None`。
+    pub fn pop_max(&mut self) -> Option<T> {
+        let (node, rest) = pop_end_node(self.root.take()?, Dir::Right, self.balance);
+        self.root = rest;
+        Some(node.data)
+    }
+
+    /// 获取不大于 `key` 的最大数据，`key` 不必在树中。
+    pub fn floor<Q>(&self, key: &Q) -> Option<&T>
+    where
+        T: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        self.nearest(key, Dir::Left, true)
+    }
+
+    /// 获取不小于 `key` 的最小数据，`key` 不必在树中。
+    pub fn ceiling<Q>(&self, key: &Q) -> Option<&T>
+    where
+        T: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        self.nearest(key, Dir::Right, true)
+    }
+
+    /// 获取小于 `key` 的最大数据，即 `key` 按顺序的前一个数据，`key` 不必在树中。
+    pub fn predecessor<Q>(&self, key: &Q) -> Option<&T>
+    where
+        T: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        self.nearest(key, Dir::Left, false)
+    }
+
+    /// 获取大于 `key` 的最小数据，即 `key` 按顺序的后一个数据，`key` 不必在树中。
+    pub fn successor<Q>(&self, key: &Q) -> Option<&T>
+    where
+        T: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        self.nearest(key, Dir::Right, false)
+    }
+
+    /// 沿查找路径寻找 `key` 一侧最近的数据：`side` 为 `Dir::Left` 时找小于 `key` 的最大数据，
+    /// 为 `Dir::Right` 时找大于 `key` 的最小数据；`inclusive` 为 `true` 时与 `key` 相等的数据优先。
+    fn nearest<Q>(&self, key: &Q, side: Dir, inclusive: bool) -> Option<&T>
+    where
+        T: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        let mut found = None;
+        let mut cur = self.root.as_deref();
+        while let Some(node) = cur {
+            match (key.cmp(node.data.borrow()), side) {
+                (Ordering::Equal, _) if inclusive => return Some(&node.data),
+                // 结点在 key 的左侧，是一个候选，继续向右寻找更近的
+                (Ordering::Greater, Dir::Left) => {
+                    found = Some(&node.data);
+                    cur = node.right.as_deref();
+                }
+                (_, Dir::Left) => cur = node.left.as_deref(),
+                // 结点在 key 的右侧，是一个候选，继续向左寻找更近的
+                (Ordering::Less, Dir::Right) => {
+                    found = Some(&node.data);
+                    cur = node.left.as_deref();
+                }
+                (_, Dir::Right) => cur = node.right.as_deref(),
+            }
+        }
+        found
+    }
+
+    /// 按后序逐个转换结点的数据，得到形状和平衡策略都相同的树，深树也不会栈溢出。
+    ///
+    /// `f` 必须保持数据之间的大小关系，否则得到的树不再是二叉搜索树。
+    pub fn map<U>(&self, mut f: impl FnMut(&T) -> U) -> BSTree<U> {
+        enum Step<'a, T> {
+            Enter(&'a Option<Box<BSTNode<T>>>),
+            Build(&'a BSTNode<T>),
+        }
+        let mut steps = vec![Step::Enter(&self.root)];
+        let mut built: Vec<Option<Box<BSTNode<U>>>> = Vec::new();
+        while let Some(step) = steps.pop() {
+            match step {
+                Step::Enter(None) => built.push(None),
+                Step::Enter(Some(n)) => {
+                    steps.push(Step::Build(n));
+                    steps.push(Step::Enter(&n.right));
+                    steps.push(Step::Enter(&n.left));
+                }
+                Step::Build(n) => {
+                    let right = built.pop().flatten();
+                    let left = built.pop().flatten();
+                    built.push(Some(Box::new(BSTNode {
+                        data: f(&n.data),
+                        left,
+                        right,
+                        height: n.height,
+                        size: n.size,
+                    })));
+                }
+            }
+        }
+        BSTree {
+            root: built.pop().flatten(),
+            balance: self.balance,
+        }
+    }
+
+    /// 与 `map` 相同，但取得数据的所有权，转换时不需要复制数据。
+    pub fn map_into<U>(mut self, mut f: impl FnMut(T) -> U) -> BSTree<U> {
+        enum Step<T> {
+            Enter(Option<Box<BSTNode<T>>>),
+            Build(T, usize, usize),
+        }
+        let mut steps = vec![Step::Enter(self.root.take())];
+        let mut built: Vec<Option<Box<BSTNode<U>>>> = Vec::new();
+        while let Some(step) = steps.pop() {
+            match step {
+                Step::Enter(None) => built.push(None),
+                Step::Enter(Some(n)) => {
+                    let BSTNode {
+                        data,
+                        left,
+                        right,
+                        height,
+                        size,
+                    } = *n;
+                    steps.push(Step::Build(data, height, size));
+                    steps.push(Step::Enter(right));
+                    steps.push(Step::Enter(left));
+                }
+                Step::Build(data, height, size) => {
+                    let right = built.pop().flatten();
+                    let left = built.pop().flatten();
+                    built.push(Some(Box::new(BSTNode {
+                        data: f(data),
+                        left,
+                        right,
+                        height,
+                        size,
+                    })));
+                }
+            }
+        }
+        BSTree {
+            root: built.pop().flatten(),
+            balance: self.balance,
+        }
+    }
+
+    /// 按中序（从小到大）遍历树中数据的迭代器，等同于 `in_order()`。
+    pub fn iter(&self) -> Iter<'_, T> {
+        Iter::new(&self.root)
+    }
+
+    /// 按中序遍历树中数据的可变迭代器。
+    ///
+    /// 修改数据时不应改变其排序位置，否则树将不再满足二叉搜索树的性质。
+    pub fn iter_mut(&mut self) -> IterMut<'_, T> {
+        let mut iter = IterMut { stack: Vec::new() };
+        iter.push_left(self.root.as_deref_mut());
+        iter
+    }
+
+    /// 前序遍历（根-左-右）的迭代器。
+    pub fn pre_order(&self) -> PreOrder<'_, T> {
+        PreOrder {
+            stack: self.root.as_deref().into_iter().collect(),
+        }
+    }
+
+    /// 中序遍历（左-根-右）的双端迭代器，`rev()` 即为从大到小遍历。
+    pub fn in_order(&self) -> Iter<'_, T> {
+        self.iter()
+    }
+
+    /// 后序遍历（左-右-根）的迭代器。
+    pub fn post_order(&self) -> PostOrder<'_, T> {
+        PostOrder {
+            stack: self
+                .root
+                .as_deref()
+                .map(|n| (n, false))
+                .into_iter()
+                .collect(),
+        }
+    }
+
+    /// 层序遍历（广度优先）的迭代器。
+    pub fn level_order(&self) -> LevelOrder<'_, T> {
+        LevelOrder {
+            queue: self.root.as_deref().into_iter().collect(),
+        }
+    }
+
+    /// 逐层遍历的迭代器，每次返回同一深度上从左到右的全部数据，第一项为根所在的层。
+    pub fn levels(&self) -> Levels<'_, T> {
+        Levels {
+            level: self.root.as_deref().into_iter().collect(),
+        }
+    }
+
+    /// 按指定遍历顺序返回数据的迭代器。
+    pub fn iter_order(&self, order: Order) -> Box<dyn Iterator<Item = &T> + '_> {
+        match order {
+            Order::Pre => Box::new(self.pre_order()),
+            Order::In => Box::new(self.in_order()),
+            Order::Post => Box::new(self.post_order()),
+            Order::Level => Box::new(self.level_order()),
+            Order::ReverseIn => Box::new(self.in_order().rev()),
+        }
+    }
+}
+
+impl<T: Borrow<str>> BSTree<T> {
+    /// 按字典序遍历以 `prefix` 开头的数据，如 `BSTree<Word>` 中以 "inter" 开头的单词，`prefix` 为空时遍历全部数据。
+    pub fn prefix(&self, prefix: &str) -> Range<'_, T> {
+        self.range_with(
+            (Bound::Included(prefix), Bound::Included(prefix)),
+            |p: &str, d: &T| cmp_prefix(p, d.borrow()),
+        )
+    }
+}
+
+/// 比较 `prefix` 和 `value` 截取到相同长度后的字节，以 `prefix` 开头的值都视为相等。
+///
+/// 该比较与字符串的字典序一致，以它为区间两端的比较即可定位所有以 `prefix` 开头的值。
+pub(crate) fn cmp_prefix(prefix: &str, value: &str) -> Ordering {
+    let head = &value.as_bytes()[..value.len().min(prefix.len())];
+    prefix.as_bytes().cmp(head)
+}
+
+/// 按中序遍历二叉搜索树的双端迭代器，由 `BSTree::iter` 和 `BSTree::in_order` 创建。
+pub struct Iter<'a, T> {
+    /// 正向遍历时待访问的结点，栈顶为下一个结点
+    front: Vec<&'a BSTNode<T>>,
+    /// 反向遍历时待访问的结点，栈顶为下一个结点
+    back: Vec<&'a BSTNode<T>>,
+    /// 正向最后一次访问的结点，用于判断两端是否相遇
+    last_front: Option<&'a BSTNode<T>>,
+    /// 反向最后一次访问的结点，用于判断两端是否相遇
+    last_back: Option<&'a BSTNode<T>>,
+}
+
+impl<'a, T> Iter<'a, T> {
+    fn new(root: &'a Option<Box<BSTNode<T>>>) -> Self {
+        let mut iter = Iter::empty();
+        iter.push_left(root.as_deref());
+        iter.push_right(root.as_deref());
+        iter
+    }
+
+    fn empty() -> Self {
+        Iter {
+            front: Vec::new(),
+            back: Vec::new(),
+            last_front: None,
+            last_back: None,
+        }
+    }
+
+    fn push_left(&mut self, mut node: Option<&'a BSTNode<T>>) {
+        while let Some(n) = node {
+            self.front.push(n);
+            node = n.left.as_deref();
+        }
+    }
+
+    fn push_right(&mut self, mut node: Option<&'a BSTNode<T>>) {
+        while let Some(n) = node {
+            self.back.push(n);
+            node = n.right.as_deref();
+        }
+    }
+
+    /// 两端相遇后清空两个栈，之后的调用都返回 `None`。
+    fn finish(&mut self) -> Option<&'a T> {
+        self.front.clear();
+        self.back.clear();
+        None
+    }
+}
+
+impl<'a, T> Iterator for Iter<'a, T> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let node = self.front.pop()?;
+        if self.last_back.is_some_and(|b| std::ptr::eq(b, node)) {
+            return self.finish();
+        }
+        self.push_left(node.right.as_deref());
+        self.last_front = Some(node);
+        Some(&node.data)
+    }
+}
+
+impl<T> DoubleEndedIterator for Iter<'_, T> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        let node = self.back.pop()?;
+        if self.last_front.is_some_and(|f| std::ptr::eq(f, node)) {
+            return self.finish();
+        }
+        self.push_right(node.left.as_deref());
+        self.last_back = Some(node);
+        Some(&node.data)
+    }
+}
+
+/// 按中序遍历区间内数据的双端迭代器，由 `BSTree::range` 和 `BSTree::prefix` 创建。
+pub struct Range<'a, T> {
+    iter: Iter<'a, T>,
+    /// 区间中尚未访问的数据个数，为 0 时两端都停止
+    remaining: usize,
+}
+
+impl<'a, T> Iterator for Range<'a, T> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.remaining == 0 {
+            return None;
+        }
+        self.remaining -= 1;
+        self.iter.next()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.remaining, Some(self.remaining))
+    }
+}
+
+impl<T> DoubleEndedIterator for Range<'_, T> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        if self.remaining == 0 {
+            return None;
+        }
+        self.remaining -= 1;
+        self.iter.next_back()
+    }
+}
+
+impl<T> ExactSizeIterator for Range<'_, T> {}
+
+/// 按中序遍历二叉搜索树的可变迭代器，由 `BSTree::iter_mut` 创建。
+pub struct IterMut<'a, T> {
+    /// 待访问结点的数据和它的右子树，栈顶为下一个结点
+    stack: Vec<(&'a mut T, Option<&'a mut BSTNode<T>>)>,
+}
+
+impl<'a, T> IterMut<'a, T> {
+    fn push_left(&mut self, mut node: Option<&'a mut BSTNode<T>>) {
+        while let Some(n) = node {
+            let BSTNode {
+                data, left, right, ..
+            } = n;
+            self.stack.push((data, right.as_deref_mut()));
+            node = left.as_deref_mut();
+        }
+    }
+}
+
+impl<'a, T> Iterator for IterMut<'a, T> {
+    type Item = &'a mut T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (data, right) = self.stack.pop()?;
+        self.push_left(right);
+        Some(data)
+    }
+}
+
+/// 按中序遍历并消耗二叉搜索树的迭代器，由 `BSTree::into_iter` 创建。
+pub struct IntoIter<T> {
+    /// 待访问的结点（左子树已摘下），栈顶为下一个结点
+    stack: Vec<Box<BSTNode<T>>>,
+}
+
+impl<T> IntoIter<T> {
+    fn push_left(&mut self, mut node: Option<Box<BSTNode<T>>>) {
+        while let Some(mut n) = node {
+            node = n.left.take();
+            self.stack.push(n);
+        }
+    }
+}
+
+impl<T> Iterator for IntoIter<T> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let node = self.stack.pop()?;
+        let BSTNode { data, right, .. } = *node;
+        self.push_left(right);
+        Some(data)
+    }
+}
+
+impl<T> Drop for IntoIter<T> {
+    /// 逐个释放剩余结点，避免深树在递归析构时栈溢出。
+    fn drop(&mut self) {
+        for _ in self.by_ref() {}
+    }
+}
+
+/// 前序遍历二叉搜索树的迭代器，由 `BSTree::pre_order` 创建。
+pub struct PreOrder<'a, T> {
+    stack: Vec<&'a BSTNode<T>>,
+}
+
+impl<'a, T> Iterator for PreOrder<'a, T> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let node = self.stack.pop()?;
+        self.stack.extend(node.right.as_deref());
+        self.stack.extend(node.left.as_deref());
+        Some(&node.data)
+    }
+}
+
+/// 后序遍历二叉搜索树的迭代器，由 `BSTree::post_order` 创建。
+pub struct PostOrder<'a, T> {
+    /// 待访问的结点，以及它的子结点是否已经压栈
+    stack: Vec<(&'a BSTNode<T>, bool)>,
+}
+
+impl<'a, T> Iterator for PostOrder<'a, T> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let (node, expanded) = self.stack.pop()?;
+            if expanded {
+                return Some(&node.data);
+            }
+            self.stack.push((node, true));
+            self.stack.extend(node.right.as_deref().map(|n| (n, false)));
+            self.stack.extend(node.left.as_deref().map(|n| (n, false)));
+        }
+    }
+}
+
+/// 层序遍历二叉搜索树的迭代器，由 `BSTree::level_order` 创建。
+pub struct LevelOrder<'a, T> {
+    queue: VecDeque<&'a BSTNode<T>>,
+}
+
+impl<'a, T> Iterator for LevelOrder<'a, T> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let node = self.queue.pop_front()?;
+        self.queue.extend(node.left.as_deref());
+        self.queue.extend(node.right.as_deref());
+        Some(&node.data)
+    }
+}
+
+/// 逐层遍历二叉搜索树的迭代器，由 `BSTree::levels` 创建。
+pub struct Levels<'a, T> {
+    /// 下一次要返回的一层结点
+    level: Vec<&'a BSTNode<T>>,
+}
+
+impl<'a, T> Iterator for Levels<'a, T> {
+    type Item = Vec<&'a T>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.level.is_empty() {
+            return None;
+        }
+        let next = self
+            .level
+            .iter()
+            .flat_map(|n| n.left.as_deref().into_iter().chain(n.right.as_deref()))
+            .collect();
+        let level = std::mem::replace(&mut self.level, next);
+        Some(level.into_iter().map(|n| &n.data).collect())
+    }
+}
+
+impl<'a, T> IntoIterator for &'a BSTree<T> {
+    type Item = &'a T;
+    type IntoIter = Iter<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<'a, T> IntoIterator for &'a mut BSTree<T> {
+    type Item = &'a mut T;
+    type IntoIter = IterMut<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter_mut()
+    }
+}
+
+impl<T> IntoIterator for BSTree<T> {
+    type Item = T;
+    type IntoIter = IntoIter<T>;
+
+    fn into_iter(mut self) -> Self::IntoIter {
+        let mut iter = IntoIter { stack: Vec::new() };
+        iter.push_left(self.root.take());
+        iter
     }
 }
 
@@ -232,12 +1569,453 @@ where
 #[test]
 fn test_bst() {
     let mut bstree = BSTree::new();
-    bstree.insert_fn(1, |n| {});
-    bstree.insert_fn(2, |n| {});
-    bstree.insert_fn(3, |n| {});
-    bstree.insert_fn(4, |n| {});
-    let rst = bstree.find(3);
-    bstree.delete(3).unwrap();
+    bstree.insert_fn(1, |_| {});
+    bstree.insert_fn(2, |_| {});
+    bstree.insert_fn(3, |_| {});
+    bstree.insert_fn(4, |_| {});
+    let _rst = bstree.find(&3);
+    bstree.delete(&3).unwrap();
     println!("Deleted.");
     bstree.traverse(Order::In);
 }
+
+/// 测试 AVL 平衡：有序插入后树高保持在 O(log n)，删除后仍然平衡
+#[test]
+fn test_avl_balance() {
+    fn check<T: Ord>(node: &Option<Box<BSTNode<T>>>) -> usize {
+        match node {
+            None => 0,
+            Some(n) => {
+                let (l, r) = (check(&n.left), check(&n.right));
+                assert!(l.abs_diff(r) <= 1, "subtree out of balance");
+                assert!(n.left.as_ref().is_none_or(|c| c.data < n.data));
+                assert!(n.right.as_ref().is_none_or(|c| c.data > n.data));
+                assert_eq!(n.height, 1 + l.max(r));
+                assert_eq!(n.size, 1 + size(&n.left) + size(&n.right));
+                n.height
+            }
+        }
+    }
+
+    let mut bstree = BSTree::with_balance(Balance::Avl);
+    let mut merged = 0;
+    for i in 0..1024 {
+        bstree.insert_fn(i, |_| {});
+    }
+    for i in 0..1024 {
+        bstree.insert_fn(i, |_| merged += 1);
+    }
+    assert_eq!(merged, 1024);
+    assert_eq!(check(&bstree.root), 11);
+    for i in (0..1024).step_by(3) {
+        bstree.delete(&i).unwrap();
+    }
+    check(&bstree.root);
+    assert!(bstree.find(&3).is_none());
+    assert!(bstree.find(&4).is_some());
+    assert!(bstree.delete(&3).is_err());
+
+    let mut plain = BSTree::new();
+    for i in 0..64 {
+        plain.insert_fn(i, |_| {});
+    }
+    assert_eq!(plain.height(), 64);
+}
+
+/// 测试按路径插入：插入和旋转后返回的路径仍然指向新结点
+#[test]
+fn test_insert_at() {
+    for balance in [Balance::None, Balance::Avl] {
+        let mut bstree = BSTree::with_balance(balance);
+        // 升序、降序和交错的插入会触发单旋和双旋
+        let data = (0..64)
+            .chain((100..164).rev())
+            .chain((0..32).map(|i| 64 + i * 7 % 32));
+        for x in data {
+            let path = bstree.locate(|d: &i32| x.cmp(d)).unwrap_err();
+            let path = bstree.insert_at(&path, x);
+            assert_eq!(bstree.get_at(&path), Some(&x));
+        }
+        assert!(bstree.iter().copied().eq((0..96).chain(100..164)));
+        let path = bstree.locate(|d| 70.cmp(d)).unwrap();
+        assert_eq!(bstree.get_at_mut(&path), Some(&mut 70));
+        assert!(bstree.locate(|d| 98.cmp(d)).is_err());
+    }
+    let mut avl = BSTree::with_balance(Balance::Avl);
+    for x in 0..1024 {
+        let path = avl.locate(|d: &i32| x.cmp(d)).unwrap_err();
+        avl.insert_at(&path, x);
+    }
+    assert_eq!(avl.height(), 11);
+}
+
+/// 测试深度为一百万的链状树：插入、查找、删除、遍历、复制和析构都不会栈溢出
+#[test]
+fn test_deep_chain() {
+    const N: usize = 1_000_000;
+    // 直接拼出一条只有右子结点的链，相当于把有序数据插入不平衡的树
+    let mut root: Option<Box<BSTNode<usize>>> = None;
+    for i in (0..N).rev() {
+        let mut node = BSTNode::new(i);
+        node.right = root;
+        node.height = N - i;
+        node.size = N - i;
+        root = Some(Box::new(node));
+    }
+    let mut bstree = BSTree {
+        root,
+        balance: Balance::None,
+    };
+    assert_eq!(bstree.height(), N);
+
+    bstree.insert_fn(N, |_| {});
+    let mut merged = false;
+    bstree.insert_fn(N / 2, |_| merged = true);
+    assert!(merged);
+    assert_eq!(bstree.height(), N + 1);
+    assert_eq!(bstree.find(&N).map(|n| n.data), Some(N));
+    assert!(bstree.find(&(N + 1)).is_none());
+
+    bstree.delete(&(N - 1)).unwrap();
+    bstree.delete(&0).unwrap();
+    assert!(bstree.delete(&0).is_err());
+    assert!(bstree.find(&(N - 1)).is_none());
+    assert_eq!(bstree.height(), N - 1);
+    assert_eq!(bstree.len(), N - 1);
+    assert_eq!(bstree.select(N - 2), Some(&N));
+    assert_eq!(bstree.rank(&N), N - 2);
+
+    // 剩下的数据为 1..N-1 以及 N
+    let expected = || (1..N - 1).chain([N]);
+    assert!(bstree.iter().copied().eq(expected()));
+    assert!(bstree.iter().rev().copied().eq(expected().rev()));
+    assert_eq!(bstree.pre_order().count(), N - 1);
+    assert_eq!(bstree.post_order().next(), Some(&N));
+    assert_eq!(bstree.level_order().count(), N - 1);
+    let copy = bstree.clone();
+    assert_eq!(copy.height(), N - 1);
+    assert_eq!(copy.into_iter().sum::<usize>(), expected().sum::<usize>());
+}
+
+/// 测试各种遍历顺序的迭代器
+#[test]
+fn test_iterators() {
+    //        4
+    //      /   \
+    //     2     6
+    //    / \   / \
+    //   1   3 5   7
+    let mut bstree = BSTree::with_balance(Balance::Avl);
+    for i in [4, 2, 6, 1, 3, 5, 7] {
+        bstree.insert_fn(i, |_| {});
+    }
+    let collect = |iter: &mut dyn Iterator<Item = &i32>| iter.copied().collect::<Vec<_>>();
+    assert_eq!(collect(&mut bstree.pre_order()), [4, 2, 1, 3, 6, 5, 7]);
+    assert_eq!(collect(&mut bstree.in_order()), [1, 2, 3, 4, 5, 6, 7]);
+    assert_eq!(collect(&mut bstree.post_order()), [1, 3, 2, 5, 7, 6, 4]);
+    assert_eq!(collect(&mut bstree.level_order()), [4, 2, 6, 1, 3, 5, 7]);
+    assert_eq!(collect(&mut bstree.iter().rev()), [7, 6, 5, 4, 3, 2, 1]);
+    assert_eq!(
+        collect(&mut bstree.iter_order(Order::Level)),
+        [4, 2, 6, 1, 3, 5, 7]
+    );
+    assert_eq!(
+        collect(&mut bstree.iter_order(Order::ReverseIn)),
+        [7, 6, 5, 4, 3, 2, 1]
+    );
+    let levels: Vec<Vec<i32>> = bstree
+        .levels()
+        .map(|level| level.into_iter().copied().collect())
+        .collect();
+    assert_eq!(levels, [vec![4], vec![2, 6], vec![1, 3, 5, 7]]);
+    bstree.traverse(Order::Level);
+    bstree.traverse_levels();
+
+    // 两端交替取值时不会重复也不会遗漏
+    let mut iter = bstree.iter();
+    assert_eq!(iter.next(), Some(&1));
+    assert_eq!(iter.next_back(), Some(&7));
+    assert_eq!(iter.next_back(), Some(&6));
+    assert_eq!(collect(&mut iter), [2, 3, 4, 5]);
+    assert_eq!(iter.next_back(), None);
+
+    let evens: Vec<i32> = (&bstree)
+        .into_iter()
+        .filter(|n| *n % 2 == 0)
+        .copied()
+        .collect();
+    assert_eq!(evens, [2, 4, 6]);
+    for data in &mut bstree {
+        *data *= 10;
+    }
+    assert_eq!(
+        bstree.into_iter().collect::<Vec<_>>(),
+        [10, 20, 30, 40, 50, 60, 70]
+    );
+    assert_eq!(BSTree::<i32>::new().iter().next(), None);
+}
+
+/// 测试序列化：前序格式能还原出形状相同的深树，并兼容旧的嵌套格式
+#[test]
+fn test_serde() {
+    // 深度远超 serde_json 默认 128 层递归限制的链状树
+    let mut bstree = BSTree::new();
+    for i in 0..2000 {
+        bstree.insert_fn(i, |_| {});
+    }
+    let json = serde_json::to_string(&bstree).unwrap();
+    let loaded: BSTree<i32> = serde_json::from_str(&json).unwrap();
+    assert_eq!(loaded.height(), 2000);
+    assert_eq!(loaded.balance, Balance::None);
+    assert!(loaded.pre_order().eq(bstree.pre_order()));
+
+    let mut avl = BSTree::with_balance(Balance::Avl);
+    for i in [5, 3, 8, 1, 4, 7, 9, 2, 6] {
+        avl.insert_fn(i, |_| {});
+    }
+    let json = serde_json::to_string(&avl).unwrap();
+    assert_eq!(json, r#"{"balance":"Avl","preorder":[5,3,1,2,4,8,7,6,9]}"#);
+    let mut loaded: BSTree<i32> = serde_json::from_str(&json).unwrap();
+    assert!(loaded.pre_order().eq(avl.pre_order()));
+    assert_eq!(loaded.height(), avl.height());
+    loaded.insert_fn(10, |_| {});
+    assert_eq!(loaded.balance, Balance::Avl);
+
+    // 旧版本的嵌套格式
+    let legacy = r#"{"root":{"data":2,"left":{"data":1,"left":null,"right":null},"right":null}}"#;
+    let loaded: BSTree<i32> = serde_json::from_str(legacy).unwrap();
+    assert_eq!(loaded.iter().copied().collect::<Vec<_>>(), [1, 2]);
+    assert_eq!(loaded.height(), 2);
+    let empty: BSTree<i32> = serde_json::from_str(r#"{"root":null}"#).unwrap();
+    assert!(empty.root.is_none());
+
+    // 不是合法二叉搜索树前序序列的数据会被拒绝
+    assert!(serde_json::from_str::<BSTree<i32>>(r#"{"preorder":[2,1,3,0]}"#).is_err());
+    assert!(serde_json::from_str::<BSTree<i32>>(r#"{"preorder":[2,2]}"#).is_err());
+    assert!(serde_json::from_str::<BSTree<i32>>(r#"{"preorder":[3,1,2,1]}"#).is_err());
+}
+
+/// 测试由有序数据构建平衡树
+#[test]
+fn test_from_sorted() {
+    let bstree = BSTree::from_sorted((0..1000).collect(), Balance::Avl).unwrap();
+    assert_eq!(bstree.height(), 10);
+    assert!(bstree.iter().copied().eq(0..1000));
+    let empty = BSTree::<i32>::from_sorted(Vec::new(), Balance::None).unwrap();
+    assert_eq!(empty.height(), 0);
+    assert!(BSTree::from_sorted(vec![1, 3, 2], Balance::None).is_err());
+    assert!(BSTree::from_sorted(vec![1, 1], Balance::None).is_err());
+}
+
+/// 测试顺序统计：插入、删除（包括旋转）后子树大小保持正确，rank、select、count_range 与有序数组一致
+#[test]
+fn test_order_statistics() {
+    for balance in [Balance::None, Balance::Avl] {
+        let mut bstree = BSTree::with_balance(balance);
+        let mut sorted: Vec<u32> = Vec::new();
+        // 简单的线性同余序列，插入和删除交替进行
+        let mut seed: u32 = 7;
+        for step in 0..3000 {
+            seed = seed.wrapping_mul(1_103_515_245).wrapping_add(12345);
+            let value = (seed >> 8) % 500;
+            if step % 3 == 2 {
+                let removed = bstree.delete(&value).is_ok();
+                match sorted.binary_search(&value) {
+                    Ok(i) => {
+                        assert!(removed);
+                        sorted.remove(i);
+                    }
+                    Err(_) => assert!(!removed),
+                }
+            } else {
+                bstree.insert_fn(value, |_| {});
+                if let Err(i) = sorted.binary_search(&value) {
+                    sorted.insert(i, value);
+                }
+            }
+            assert_eq!(bstree.len(), sorted.len());
+        }
+        assert!(bstree.iter().eq(sorted.iter()));
+        for (k, value) in sorted.iter().enumerate() {
+            assert_eq!(bstree.select(k), Some(value));
+            assert_eq!(bstree.rank(value), k);
+        }
+        assert_eq!(bstree.select(sorted.len()), None);
+        for key in [0, 1, 99, 250, 499, 500, 1000] {
+            let below = sorted.partition_point(|&v| v < key);
+            assert_eq!(bstree.rank(&key), below);
+        }
+        let count = |lo: u32, hi: u32| sorted.iter().filter(|&&v| lo <= v && v < hi).count();
+        assert_eq!(bstree.count_range(100..200), count(100, 200));
+        assert_eq!(bstree.count_range(100..=200), count(100, 201));
+        assert_eq!(bstree.count_range(..50), count(0, 50));
+        assert_eq!(bstree.count_range(450..), count(450, u32::MAX));
+        assert_eq!(bstree.count_range(..), sorted.len());
+        assert_eq!(
+            bstree.count_range((Bound::Included(300), Bound::Excluded(100))),
+            0
+        );
+        assert_eq!(
+            bstree.count_range((Bound::Excluded(100), Bound::Unbounded)),
+            count(101, u32::MAX)
+        );
+    }
+
+    // 从前序序列重建、复制和转换后的树同样记录了子树大小
+    let bstree = BSTree::from_sorted((0..1000).collect(), Balance::Avl).unwrap();
+    let json = serde_json::to_string(&bstree).unwrap();
+    let loaded: BSTree<i32> = serde_json::from_str(&json).unwrap();
+    assert_eq!(loaded.len(), 1000);
+    assert_eq!(loaded.map(|i| i * 2).select(500), Some(&1000));
+    assert_eq!(loaded.clone().rank(&500), 500);
+
+    // 以借用形式查询单词树
+    let mut words = BSTree::new();
+    for word in ["pear", "apple", "melon", "kiwi", "zucchini"] {
+        words.insert_fn(crate::r#type::Word::new(word.to_string()), |_| {});
+    }
+    assert_eq!(words.rank("m"), 2);
+    // 区间端点为 str 这样的动态大小类型时，使用 Bound 元组表示区间
+    let range = (Bound::Included("b"), Bound::Excluded("n"));
+    assert_eq!(words.count_range::<str, _>(range), 2);
+    assert_eq!(words.select(4).map(|w| w.value.as_str()), Some("zucchini"));
+}
+
+/// 测试区间和前缀查询，包括各种端点、空区间和双向遍历
+#[test]
+fn test_range() {
+    for balance in [Balance::None, Balance::Avl] {
+        let mut bstree = BSTree::with_balance(balance);
+        for i in [50, 20, 80, 10, 30, 70, 90, 25, 35, 60] {
+            bstree.insert_fn(i, |_| {});
+        }
+        let all: Vec<i32> = bstree.iter().copied().collect();
+        let check = |got: Vec<i32>, pred: &dyn Fn(i32) -> bool| {
+            let expected: Vec<i32> = all.iter().copied().filter(|&i| pred(i)).collect();
+            assert_eq!(got, expected);
+        };
+        check(bstree.range(25..70).copied().collect(), &|i| {
+            (25..70).contains(&i)
+        });
+        check(bstree.range(24..=70).copied().collect(), &|i| {
+            (24..=70).contains(&i)
+        });
+        check(bstree.range(..30).copied().collect(), &|i| i < 30);
+        check(bstree.range(31..).copied().collect(), &|i| i >= 31);
+        check(bstree.range(..).copied().collect(), &|_| true);
+        check(
+            bstree
+                .range((Bound::Excluded(25), Bound::Excluded(90)))
+                .copied()
+                .collect(),
+            &|i| i > 25 && i < 90,
+        );
+        assert_eq!(bstree.range(26..30).count(), 0);
+        assert_eq!(bstree.range(100..).count(), 0);
+        assert_eq!(
+            bstree
+                .range((Bound::Included(70), Bound::Excluded(20)))
+                .count(),
+            0
+        );
+        // 反向遍历以及两端交替遍历
+        let back: Vec<i32> = bstree.range(20..=80).rev().copied().collect();
+        assert_eq!(back, [80, 70, 60, 50, 35, 30, 25, 20]);
+        let mut range = bstree.range(20..=80);
+        assert_eq!(range.len(), 8);
+        assert_eq!(range.next(), Some(&20));
+        assert_eq!(range.next_back(), Some(&80));
+        assert_eq!(range.next(), Some(&25));
+        assert_eq!(range.len(), 5);
+        assert_eq!(range.copied().collect::<Vec<_>>(), [30, 35, 50, 60, 70]);
+    }
+
+    let mut words = BSTree::with_balance(Balance::Avl);
+    for word in [
+        "in",
+        "inter",
+        "internal",
+        "interval",
+        "intern",
+        "into",
+        "inte",
+        "apple",
+        "interz",
+        "以色列",
+        "以色",
+        "以",
+    ] {
+        words.insert_fn(crate::r#type::Word::new(word.to_string()), |_| {});
+    }
+    let prefix = |p: &str| -> Vec<&str> { words.prefix(p).map(|w| w.value.as_str()).collect() };
+    assert_eq!(
+        prefix("inter"),
+        ["inter", "intern", "internal", "interval", "interz"]
+    );
+    assert_eq!(prefix("intern"), ["intern", "internal"]);
+    assert_eq!(prefix("inx"), Vec::<&str>::new());
+    assert_eq!(prefix("以色"), ["以色", "以色列"]);
+    assert_eq!(prefix("").len(), 12);
+    assert_eq!(words.prefix("in").next_back().unwrap().value, "into");
+}
+
+/// 测试最值、相邻数据查询以及弹出最值后树仍然有序、平衡
+#[test]
+fn test_nearest() {
+    let empty: BSTree<i32> = BSTree::new();
+    assert_eq!(empty.min(), None);
+    assert_eq!(empty.floor(&1), None);
+
+    for balance in [Balance::None, Balance::Avl] {
+        let mut bstree = BSTree::with_balance(balance);
+        let values: Vec<i32> = (0..200).map(|i| (i * 37) % 200 * 3).collect();
+        for &i in &values {
+            bstree.insert_fn(i, |_| {});
+        }
+        let mut sorted = values.clone();
+        sorted.sort();
+        for key in -2..602 {
+            let floor = sorted.iter().rev().find(|&&v| v <= key);
+            let ceiling = sorted.iter().find(|&&v| v >= key);
+            let predecessor = sorted.iter().rev().find(|&&v| v < key);
+            let successor = sorted.iter().find(|&&v| v > key);
+            assert_eq!(bstree.floor(&key), floor, "floor {key}");
+            assert_eq!(bstree.ceiling(&key), ceiling, "ceiling {key}");
+            assert_eq!(bstree.predecessor(&key), predecessor, "predecessor {key}");
+            assert_eq!(bstree.successor(&key), successor, "successor {key}");
+        }
+
+        // 交替弹出最小和最大的数据
+        let (mut lo, mut hi) = (0, sorted.len());
+        while lo < hi {
+            assert_eq!(bstree.min(), Some(&sorted[lo]));
+            assert_eq!(bstree.max(), Some(&sorted[hi - 1]));
+            if lo % 2 == 0 {
+                assert_eq!(bstree.pop_min(), Some(sorted[lo]));
+                lo += 1;
+            } else {
+                assert_eq!(bstree.pop_max(), Some(sorted[hi - 1]));
+                hi -= 1;
+            }
+            assert_eq!(bstree.len(), hi - lo);
+            assert!(bstree.iter().eq(sorted[lo..hi].iter()));
+            if balance == Balance::Avl && bstree.len() > 1 {
+                // AVL 树的高度不超过 1.44 log2(n + 2)
+                let bound = 1.45 * ((bstree.len() + 2) as f64).log2();
+                assert!((bstree.height() as f64) <= bound);
+            }
+        }
+        assert_eq!(bstree.pop_min(), None);
+        assert_eq!(bstree.pop_max(), None);
+    }
+
+    // 以借用形式在单词树中查找相邻的单词
+    let mut words = BSTree::new();
+    for word in ["hostage", "hostages", "house", "idf"] {
+        words.insert_fn(crate::r#type::Word::new(word.to_string()), |_| {});
+    }
+    assert_eq!(words.predecessor("hostile").unwrap().value, "hostages");
+    assert_eq!(words.successor("hostile").unwrap().value, "house");
+    assert_eq!(words.floor("idf").unwrap().value, "idf");
+    assert!(words.successor("idf").is_none());
+}
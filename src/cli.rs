@@ -0,0 +1,1232 @@
+//! # 命令行子命令
+//!
+//! 该模块使用 `clap` 定义非交互式的子命令，便于在脚本、管道和 CI 中使用。
+//! 不带子命令运行程序时仍然进入交互式菜单。
+//!
+//! ## 子命令
+//!
+//! - `count <FILES..>`: 统计文件（`-` 表示标准输入）中的单词，输出每个单词及其出现次数，或用 `-o` 保存为缓存，
+//!   `--tokenizer` 选择分词方式（正则表达式分词用 `--pattern` 指定正则表达式）；其余子命令使用缓存记录的分词方式。
+//!   `--contractions`、`--hyphens`、`--normalize-apostrophes`、`--numbers` 和 `--symbols`
+//!   设置缩写、撇号、连字符、数字和符号的处理方式。
+//!   使用中文词典分词（`cjk`）时，可以用 `--dict` 在内置词典的基础上追加用户词典，
+//!   缓存记录词典内容的哈希值，其余子命令需要用 `--dict` 指定同一个词典。
+//!   `--normalize porter` 使用 Porter 词干提取，`--normalize lemma --lemmas <FILE>` 使用词典做词形还原，
+//!   把单词的不同形式计为同一个单词，并记录归并前的原始形式。
+//!   `--case-variants` 记录单词在原文中的各种大小写写法（如 "Israeli"、"ISRAELI"），`query` 时一并输出。
+//!   `--positions` 记录单词每次出现的文档、行号、列号和字节偏移，供 `kwic` 使用。
+//! - `append <CACHE> <FILES..>`: 将文件中的单词追加到已有缓存中，`append`、`query` 和 `stop` 都使用缓存记录的归并方式。
+//! - `query <CACHE> <WORDS..>`: 查询单词的出现次数，单词不在树中时输出按字母序与它相邻的单词，
+//!   以及编辑距离不超过 `--distance` 的相近单词（`--metric` 选择 Levenshtein 或 Damerau 距离）作为拼写建议。
+//! - `kwic <CACHE> <WORDS..>`: 按记录的出现位置重新读取来源文件，输出单词每次出现时前后各 `--width` 个词的原文（KWIC 索引）。
+//! - `range <CACHE> --prefix/--from/--to/--wildcard/--regex`: 按前缀、字母区间、通配符模式（如 `kill*`、`h?st?ge`）或正则表达式列出单词及其出现次数，
+//!   前缀、端点和通配符模式按缓存记录的分词方式转换（如转为小写），但不做归并；模式有字面前缀时只检查以该前缀开头的单词。
+//! - `stop <CACHE> --words/--file`: 从缓存中删除停用词。
+//! - `traverse <CACHE> --order in`: 按指定顺序遍历缓存中的单词。
+//! - `convert <INPUT> <OUTPUT>`: 转换缓存格式，也可用于升级旧版本的缓存。
+//!
+//! ## 退出码
+//!
+//! - `0`: 成功，包括输出管道被另一端提前关闭（如 `| head`）。
+//! - `1`: `query` 或 `kwic` 中有单词不在树中，`range` 没有列出任何单词，或 `stop` 中有停用词不在树中。
+//! - `2`: 参数错误（包括 `query`、`kwic` 的参数切分后没有单词）、文件读写失败或缓存无效。
+// 引入bst库中的BSTMap和KeyValue结构体，Order枚举类型，和WordStats结构体
+use bst_words::map::{BSTMap, KeyValue};
+use bst_words::r#type::{Order, WordStats};
+// 引入bst库中的工具函数，用于分词、追加文本以及缓存的读写
+use bst_words::utils::{
+    append_text, cache_to_json, fuzzy_search, load_cache_file, normalizer_from_name,
+    pattern_search, read_from_file, save_binary_cache, source_text, tokenizer_from_name,
+    write_str_to_file, AsciiTokenizer, CacheFormat, CacheMeta, Concordance, Contractions, Hyphens,
+    Lemmatizer, Metric, Normalizer, Numbers, Pattern, PorterStemmer, RegexTokenizer, Segmenter,
+    Symbols, Tokenizer, UnicodeTokenizer, WhitespaceTokenizer, WordRules,
+};
+// 引入anyhow库中的Error类型，它用于表示任何可能的错误
+use anyhow::{bail, Error};
+// 引入clap库中的派生宏，用于定义命令行参数
+use clap::{Args, Parser, Subcommand, ValueEnum};
+// 引入std库中的Cow类型，它用于表示归并前后的单词
+use std::borrow::Cow;
+// 引入std库中的HashMap类型，它用于按文档编号缓存上下文索引
+use std::collections::HashMap;
+// 引入std库中的io模块、ops模块、path模块和process模块，它们用于读取标准输入和写入输出、表示单词区间、处理路径和设置退出码
+use std::io::{ErrorKind, Read, Write};
+use std::ops::Bound;
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+
+/// 基于二叉搜索树的单词计数程序，不带子命令时进入交互式菜单。
+#[derive(Parser)]
+#[command(version, about)]
+pub struct Cli {
+    #[command(subcommand)]
+    pub command: Option<Command>,
+    /// 中文分词的用户词典，每行一个词，追加到内置词典
+    #[arg(long, global = true)]
+    pub dict: Option<PathBuf>,
+}
+
+/// 非交互式的子命令。
+#[derive(Subcommand)]
+pub enum Command {
+    /// 统计文件中的单词，输出每个单词及其出现次数，或保存为缓存
+    Count {
+        /// 要统计的文本文件，`-` 表示标准输入
+        #[arg(required = true)]
+        files: Vec<PathBuf>,
+        /// 保存为缓存文件，不指定时将结果输出到标准输出
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+        /// 缓存格式，不指定时根据输出文件的扩展名判断
+        #[arg(short, long, value_enum)]
+        format: Option<FormatArg>,
+        /// 分词方式
+        #[arg(short, long, value_enum, default_value_t = TokenizerArg::Ascii)]
+        tokenizer: TokenizerArg,
+        /// 正则表达式分词使用的正则表达式，每个匹配是一个单词
+        #[arg(long, required_if_eq("tokenizer", "regex"))]
+        pattern: Option<String>,
+        #[command(flatten)]
+        rules: RuleArgs,
+        /// 把单词的不同形式归并为同一个单词，不指定时不归并
+        #[arg(short, long, value_enum)]
+        normalize: Option<NormalizerArg>,
+        /// 词形还原使用的词典，每行一个词元及其各种形式
+        #[arg(long, required_if_eq("normalize", "lemma"))]
+        lemmas: Option<PathBuf>,
+        /// 记录单词在原文中的大小写写法
+        #[arg(long)]
+        case_variants: bool,
+        /// 记录单词每次出现的位置，供 `kwic` 使用
+        #[arg(long)]
+        positions: bool,
+    },
+    /// 将文件中的单词追加到已有的缓存中，使用缓存记录的分词方式
+    Append {
+        /// 已有的缓存文件
+        cache: PathBuf,
+        /// 要追加的文本文件，`-` 表示标准输入
+        #[arg(required = true)]
+        files: Vec<PathBuf>,
+        /// 保存到另一个缓存文件，不指定时覆盖原缓存
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+        /// 缓存格式，不指定时根据输出文件的扩展名判断
+        #[arg(short, long, value_enum)]
+        format: Option<FormatArg>,
+    },
+    /// 查询单词的出现次数，有单词不在树中时退出码为 1
+    Query {
+        /// 缓存文件
+        cache: PathBuf,
+        /// 要查询的单词
+        #[arg(required = true)]
+        words: Vec<String>,
+        /// 单词不在树中时，列出编辑距离不超过该值的单词作为拼写建议，为 0 时不列出
+        #[arg(short, long, default_value_t = 2)]
+        distance: usize,
+        /// 编辑距离的计算方式
+        #[arg(short, long, value_enum, default_value_t = MetricArg::Damerau)]
+        metric: MetricArg,
+        /// 最多列出的拼写建议数
+        #[arg(short, long, default_value_t = 5)]
+        suggestions: usize,
+    },
+    /// 输出单词每次出现时的上下文，需要缓存记录了出现位置且来源文件没有被修改，有单词不在树中时退出码为 1
+    Kwic {
+        /// 缓存文件
+        cache: PathBuf,
+        /// 要查询的单词
+        #[arg(required = true)]
+        words: Vec<String>,
+        /// 单词前后各输出的词数
+        #[arg(short, long, default_value_t = 5)]
+        width: usize,
+        /// 每个单词最多输出的出现次数，不指定时全部输出
+        #[arg(short, long)]
+        limit: Option<usize>,
+    },
+    /// 按前缀、字母区间、通配符模式或正则表达式列出单词及其出现次数，没有单词符合条件时退出码为 1
+    Range {
+        /// 缓存文件
+        cache: PathBuf,
+        /// 只列出以该前缀开头的单词
+        #[arg(short, long, conflicts_with_all = ["from", "to"])]
+        prefix: Option<String>,
+        /// 只列出匹配通配符模式的单词，`*` 匹配任意多个字符，`?` 匹配一个字符，如 "kill*"、"*ing"、"h?st?ge"
+        #[arg(short, long, conflicts_with_all = ["prefix", "from", "to", "regex"])]
+        wildcard: Option<String>,
+        /// 只列出匹配正则表达式的单词，以 "^" 开头的字面前缀用于缩小查找的区间
+        #[arg(short, long, conflicts_with_all = ["prefix", "from", "to"])]
+        regex: Option<String>,
+        /// 区间的起点（包含），不指定时从第一个单词开始
+        #[arg(long)]
+        from: Option<String>,
+        /// 区间的终点（包含），不指定时到最后一个单词为止
+        #[arg(long)]
+        to: Option<String>,
+    },
+    /// 从缓存中删除停用词
+    Stop {
+        /// 缓存文件
+        cache: PathBuf,
+        /// 停用词
+        #[arg(short, long, num_args = 1.., required_unless_present = "file")]
+        words: Vec<String>,
+        /// 包含停用词的文本文件
+        #[arg(long)]
+        file: Option<PathBuf>,
+        /// 保存到另一个缓存文件，不指定时覆盖原缓存
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+        /// 缓存格式，不指定时根据输出文件的扩展名判断
+        #[arg(short = 'F', long, value_enum)]
+        format: Option<FormatArg>,
+    },
+    /// 按指定顺序遍历缓存中的单词，每行输出一个单词及其出现次数
+    Traverse {
+        /// 缓存文件
+        cache: PathBuf,
+        /// 遍历顺序
+        #[arg(short, long, value_enum, default_value_t = OrderArg::In)]
+        order: OrderArg,
+        /// 层序遍历时每一层输出一行
+        #[arg(long)]
+        levels: bool,
+    },
+    /// 转换缓存格式，也可用于将旧版本的缓存升级为当前版本
+    Convert {
+        /// 输入的缓存文件
+        input: PathBuf,
+        /// 输出的缓存文件
+        output: PathBuf,
+        /// 缓存格式，不指定时根据输出文件的扩展名判断
+        #[arg(short, long, value_enum)]
+        format: Option<FormatArg>,
+    },
+}
+
+/// 命令行中可选的缓存格式。
+#[derive(Clone, Copy, ValueEnum)]
+pub enum FormatArg {
+    /// 按单词排序的 `[单词, 次数]` 数组
+    Compact,
+    /// 保留树形状的前序序列
+    Tree,
+    /// 二进制格式
+    Binary,
+}
+
+/// 命令行中可选的分词方式。
+#[derive(Clone, Copy, ValueEnum)]
+pub enum TokenizerArg {
+    /// 只保留 ASCII 字母
+    Ascii,
+    /// 按 Unicode 单词边界（UAX #29）切分
+    Unicode,
+    /// 按 Unicode 单词边界切分并做大小写折叠
+    UnicodeFold,
+    /// 只按空白切分，保留标点
+    Whitespace,
+    /// 取 `--pattern` 的每个匹配作为单词
+    Regex,
+    /// 连续的汉字按词典正向最大匹配切分，其余文字按 Unicode 单词边界切分
+    Cjk,
+}
+
+/// 命令行中可选的归并方式。
+#[derive(Clone, Copy, ValueEnum)]
+pub enum NormalizerArg {
+    /// Porter 英文词干提取（killed、killing 计为 kill）
+    Porter,
+    /// 使用 `--lemmas` 指定的词典做词形还原（went 计为 go）
+    Lemma,
+}
+
+/// 缩写、撇号、连字符、数字和符号的处理规则，只对 `ascii`、`unicode` 和 `unicode-fold` 分词有效，不指定时使用分词器的默认规则。
+#[derive(Args)]
+pub struct RuleArgs {
+    /// 缩写（如 don't）的处理方式
+    #[arg(long, value_enum)]
+    contractions: Option<ContractionsArg>,
+    /// 连字符复合词（如 three-hour）的处理方式
+    #[arg(long, value_enum)]
+    hyphens: Option<HyphensArg>,
+    /// 将弯撇号 ’ 统一为 '
+    #[arg(long)]
+    normalize_apostrophes: bool,
+    /// 数字的处理方式
+    #[arg(long, value_enum)]
+    numbers: Option<NumbersArg>,
+    /// 允许出现在单词中的符号类别，用逗号分隔
+    #[arg(long, value_enum, value_delimiter = ',')]
+    symbols: Vec<SymbolArg>,
+}
+
+impl RuleArgs {
+    /// 在分词器的默认规则上应用命令行指定的规则。
+    fn over(&self, default: WordRules) -> WordRules {
+        WordRules {
+            contractions: self.contractions.map_or(default.contractions, Into::into),
+            hyphens: self.hyphens.map_or(default.hyphens, Into::into),
+            normalize_apostrophes: self.normalize_apostrophes || default.normalize_apostrophes,
+            numbers: self.numbers.map_or(default.numbers, Into::into),
+            symbols: self
+                .symbols
+                .iter()
+                .fold(default.symbols, |symbols, symbol| {
+                    symbols | (*symbol).into()
+                }),
+        }
+    }
+}
+
+/// 命令行中可选的缩写处理方式。
+#[derive(Clone, Copy, ValueEnum)]
+pub enum ContractionsArg {
+    /// 在撇号处拆开（don + t）
+    Split,
+    /// 作为一个单词保留（don't）
+    Keep,
+    /// 展开为完整的单词（do + not）
+    Expand,
+}
+
+impl From<ContractionsArg> for Contractions {
+    fn from(contractions: ContractionsArg) -> Contractions {
+        match contractions {
+            ContractionsArg::Split => Contractions::Split,
+            ContractionsArg::Keep => Contractions::Keep,
+            ContractionsArg::Expand => Contractions::Expand,
+        }
+    }
+}
+
+/// 命令行中可选的数字处理方式。
+#[derive(Clone, Copy, ValueEnum)]
+pub enum NumbersArg {
+    /// 丢弃纯数字
+    Drop,
+    /// 保留数字和字母数字混合的单词
+    Keep,
+    /// 纯数字统一计为 <NUM>
+    Bucket,
+}
+
+impl From<NumbersArg> for Numbers {
+    fn from(numbers: NumbersArg) -> Numbers {
+        match numbers {
+            NumbersArg::Drop => Numbers::Drop,
+            NumbersArg::Keep => Numbers::Keep,
+            NumbersArg::Bucket => Numbers::Bucket,
+        }
+    }
+}
+
+/// 命令行中可选的符号类别。
+#[derive(Clone, Copy, ValueEnum)]
+pub enum SymbolArg {
+    /// 下划线（x86_64）
+    Underscore,
+    /// 点号（node.js）
+    Dot,
+    /// @（admin@example）
+    At,
+    /// 斜杠（and/or）
+    Slash,
+    /// 单词末尾的 + 和 #（c++、c#）
+    Trailing,
+}
+
+impl From<SymbolArg> for Symbols {
+    fn from(symbol: SymbolArg) -> Symbols {
+        match symbol {
+            SymbolArg::Underscore => Symbols::UNDERSCORE,
+            SymbolArg::Dot => Symbols::DOT,
+            SymbolArg::At => Symbols::AT,
+            SymbolArg::Slash => Symbols::SLASH,
+            SymbolArg::Trailing => Symbols::TRAILING,
+        }
+    }
+}
+
+/// 命令行中可选的连字符处理方式。
+#[derive(Clone, Copy, ValueEnum)]
+pub enum HyphensArg {
+    /// 在连字符处拆开（three + hour）
+    Split,
+    /// 作为一个单词保留（three-hour）
+    Keep,
+}
+
+impl From<HyphensArg> for Hyphens {
+    fn from(hyphens: HyphensArg) -> Hyphens {
+        match hyphens {
+            HyphensArg::Split => Hyphens::Split,
+            HyphensArg::Keep => Hyphens::Keep,
+        }
+    }
+}
+
+/// 命令行中可选的遍历顺序。
+#[derive(Clone, Copy, ValueEnum)]
+pub enum OrderArg {
+    Pre,
+    In,
+    Post,
+    Level,
+    ReverseIn,
+}
+
+impl From<OrderArg> for Order {
+    fn from(order: OrderArg) -> Order {
+        match order {
+            OrderArg::Pre => Order::Pre,
+            OrderArg::In => Order::In,
+            OrderArg::Post => Order::Post,
+            OrderArg::Level => Order::Level,
+            OrderArg::ReverseIn => Order::ReverseIn,
+        }
+    }
+}
+
+/// 命令行中可选的编辑距离计算方式。
+#[derive(Clone, Copy, ValueEnum)]
+pub enum MetricArg {
+    /// 插入、删除和替换一个字符计为 1
+    Levenshtein,
+    /// 另外交换相邻的两个字符也计为 1
+    Damerau,
+}
+
+impl From<MetricArg> for Metric {
+    fn from(metric: MetricArg) -> Metric {
+        match metric {
+            MetricArg::Levenshtein => Metric::Levenshtein,
+            MetricArg::Damerau => Metric::Damerau,
+        }
+    }
+}
+
+/// 执行子命令，返回进程的退出码，错误信息输出到标准错误。
+pub fn run(command: Command, dict: Option<PathBuf>) -> ExitCode {
+    let result = execute(command, dict.as_deref(), &mut std::io::stdout().lock());
+    match &result {
+        Err(e) if !is_broken_pipe(e) => eprintln!("错误: {e:#}"),
+        _ => {}
+    }
+    ExitCode::from(exit_status(&result))
+}
+
+/// 由子命令的执行结果得到退出码：成功为 0，有单词未找到或没有单词符合条件为 1，出错为 2。
+///
+/// 输出被管道的另一端提前关闭（如 `| head`）时视为正常结束。
+pub fn exit_status(result: &Result<bool, Error>) -> u8 {
+    match result {
+        Ok(true) => 0,
+        Ok(false) => 1,
+        Err(e) if is_broken_pipe(e) => 0,
+        Err(_) => 2,
+    }
+}
+
+/// 判断错误是否是写入已关闭的管道。
+fn is_broken_pipe(e: &Error) -> bool {
+    e.downcast_ref::<std::io::Error>()
+        .is_some_and(|e| e.kind() == ErrorKind::BrokenPipe)
+}
+
+/// 执行子命令，结果写入 `out`，返回 `false` 表示命令完成但有单词未找到。
+fn execute(command: Command, dict: Option<&Path>, out: &mut dyn Write) -> Result<bool, Error> {
+    match command {
+        Command::Count {
+            files,
+            output,
+            format,
+            tokenizer,
+            pattern,
+            rules,
+            normalize,
+            lemmas,
+            case_variants,
+            positions,
+        } => {
+            let tokenizer: Box<dyn Tokenizer> = match tokenizer {
+                TokenizerArg::Ascii => Box::new(
+                    AsciiTokenizer::new().with_rules(rules.over(AsciiTokenizer::DEFAULT_RULES)),
+                ),
+                TokenizerArg::Unicode => Box::new(
+                    UnicodeTokenizer::new().with_rules(rules.over(UnicodeTokenizer::DEFAULT_RULES)),
+                ),
+                TokenizerArg::UnicodeFold => Box::new(
+                    UnicodeTokenizer::folding()
+                        .with_rules(rules.over(UnicodeTokenizer::DEFAULT_RULES)),
+                ),
+                TokenizerArg::Whitespace => Box::new(WhitespaceTokenizer),
+                TokenizerArg::Regex => Box::new(RegexTokenizer::new(&pattern.unwrap_or_default())?),
+                TokenizerArg::Cjk => Box::new(make_segmenter(dict)?),
+            };
+            let normalizer: Option<Box<dyn Normalizer>> = match normalize {
+                None => None,
+                Some(NormalizerArg::Porter) => Some(Box::new(PorterStemmer)),
+                Some(NormalizerArg::Lemma) => {
+                    let path = lemmas.unwrap_or_default();
+                    let lemmatizer = Lemmatizer::from_file(&path.to_string_lossy())
+                        .map_err(|e| e.context(format!("无法读取词典 {}", path.display())))?;
+                    Some(Box::new(lemmatizer))
+                }
+            };
+            let mut bstree = BSTMap::new();
+            let mut meta = CacheMeta::new(tokenizer.name());
+            meta.normalizer = normalizer.as_ref().map(|n| n.name().to_string());
+            meta.case_variants = case_variants;
+            meta.positions = positions;
+            append_files(
+                &mut bstree,
+                &mut meta,
+                &*tokenizer,
+                normalizer.as_deref(),
+                &files,
+            )?;
+            match output {
+                Some(output) => save(&output, &bstree, &meta, format)?,
+                None => {
+                    for word in bstree.tree() {
+                        writeln!(out, "{word}")?;
+                    }
+                }
+            }
+            Ok(true)
+        }
+        Command::Append {
+            cache,
+            files,
+            output,
+            format,
+        } => {
+            let (mut bstree, mut meta) = load(&cache)?;
+            let tokenizer = make_tokenizer(&meta.tokenizer, dict)?;
+            let normalizer = make_normalizer(&meta)?;
+            append_files(
+                &mut bstree,
+                &mut meta,
+                &*tokenizer,
+                normalizer.as_deref(),
+                &files,
+            )?;
+            save(output.as_ref().unwrap_or(&cache), &bstree, &meta, format)?;
+            Ok(true)
+        }
+        Command::Query {
+            cache,
+            words,
+            distance,
+            metric,
+            suggestions,
+        } => {
+            let (bstree, meta) = load(&cache)?;
+            let mut all_found = true;
+            let tokenizer = make_tokenizer(&meta.tokenizer, dict)?;
+            let normalizer = make_normalizer(&meta)?;
+            let input = words.join(" ");
+            let words = tokenizer.tokenize(&input);
+            if words.is_empty() {
+                bail!("没有可查询的单词");
+            }
+            for word in words {
+                let stem = normalize(normalizer.as_deref(), &word);
+                match bstree.get_key_value(stem.as_ref()) {
+                    Some(word) => match word.value.variants_summary(&word.key) {
+                        Some(variants) => writeln!(out, "{word} [{variants}]")?,
+                        None => writeln!(out, "{word}")?,
+                    },
+                    None => {
+                        writeln!(out, "{word}: 不在bstree中.")?;
+                        if let Some(line) = neighbours_line(&bstree, &stem) {
+                            writeln!(out, "{line}")?;
+                        }
+                        let metric = metric.into();
+                        if let Some(line) =
+                            suggestions_line(&bstree, &stem, distance, metric, suggestions)
+                        {
+                            writeln!(out, "{line}")?;
+                        }
+                        all_found = false;
+                    }
+                }
+            }
+            Ok(all_found)
+        }
+        Command::Kwic {
+            cache,
+            words,
+            width,
+            limit,
+        } => {
+            let (bstree, meta) = load(&cache)?;
+            if !meta.positions {
+                return Err(Error::msg(
+                    "缓存没有记录出现位置，请使用 count --positions 重新统计",
+                ));
+            }
+            let mut all_found = true;
+            let tokenizer = make_tokenizer(&meta.tokenizer, dict)?;
+            let normalizer = make_normalizer(&meta)?;
+            let mut view = KwicView::default();
+            let input = words.join(" ");
+            let words = tokenizer.tokenize(&input);
+            if words.is_empty() {
+                bail!("没有可查询的单词");
+            }
+            for word in words {
+                let stem = normalize(normalizer.as_deref(), &word);
+                match bstree.get_key_value(stem.as_ref()) {
+                    Some(word) => {
+                        writeln!(out, "{word}")?;
+                        for line in view.lines(&meta, &*tokenizer, &word.value, width, limit) {
+                            writeln!(out, "{line}")?;
+                        }
+                    }
+                    None => {
+                        writeln!(out, "{word}: 不在bstree中.")?;
+                        all_found = false;
+                    }
+                }
+            }
+            Ok(all_found)
+        }
+        Command::Range {
+            cache,
+            prefix,
+            wildcard,
+            regex,
+            from,
+            to,
+        } => {
+            let (bstree, meta) = load(&cache)?;
+            let tokenizer = make_tokenizer(&meta.tokenizer, dict)?;
+            let range = match (prefix, wildcard, regex) {
+                (Some(prefix), ..) => WordRange::Prefix(prefix),
+                (_, Some(wildcard), _) => WordRange::Wildcard(wildcard),
+                (.., Some(regex)) => WordRange::Regex(Pattern::regex(&regex)?),
+                _ => WordRange::Between(from, to),
+            };
+            let matched = range_words(&bstree, &*tokenizer, &range);
+            if matched.is_empty() {
+                writeln!(out, "没有符合条件的单词.")?;
+                return Ok(false);
+            }
+            for word in matched {
+                writeln!(out, "{word}")?;
+            }
+            Ok(true)
+        }
+        Command::Stop {
+            cache,
+            words,
+            file,
+            output,
+            format,
+        } => {
+            let (mut bstree, meta) = load(&cache)?;
+            let mut input = words.join(" ");
+            if let Some(file) = file {
+                input.push(' ');
+                input += &read_text(&file)?;
+            }
+            let mut all_found = true;
+            let tokenizer = make_tokenizer(&meta.tokenizer, dict)?;
+            let normalizer = make_normalizer(&meta)?;
+            for word in tokenizer.tokenize(&input) {
+                let stem = normalize(normalizer.as_deref(), &word);
+                match bstree.remove(stem.as_ref()) {
+                    Some(_) => writeln!(out, "停用词:{word} 删除成功")?,
+                    None => {
+                        writeln!(out, "停用词:{word} 不在bstree中.")?;
+                        all_found = false;
+                    }
+                }
+            }
+            save(output.as_ref().unwrap_or(&cache), &bstree, &meta, format)?;
+            Ok(all_found)
+        }
+        Command::Traverse {
+            cache,
+            order,
+            levels,
+        } => {
+            let (bstree, _) = load(&cache)?;
+            if levels {
+                // 单词的显示中含有空格，同一层的单词用 " | " 分隔
+                for (depth, level) in bstree.tree().levels().enumerate() {
+                    let line: Vec<String> = level.iter().map(|w| w.to_string()).collect();
+                    writeln!(out, "{depth}: {}", line.join(" | "))?;
+                }
+            } else {
+                for word in bstree.tree().iter_order(order.into()) {
+                    writeln!(out, "{word}")?;
+                }
+            }
+            Ok(true)
+        }
+        Command::Convert {
+            input,
+            output,
+            format,
+        } => {
+            let (bstree, meta) = load(&input)?;
+            save(&output, &bstree, &meta, format)?;
+            Ok(true)
+        }
+    }
+}
+
+/// 读取文本文件，路径为 `-` 时读取标准输入。
+fn read_text(path: &Path) -> Result<String, Error> {
+    if path == Path::new("-") {
+        let mut text = String::new();
+        std::io::stdin().read_to_string(&mut text)?;
+        Ok(text)
+    } else {
+        read_from_file(path).map_err(|e| e.context(format!("无法读取 {}", path.display())))
+    }
+}
+
+/// 创建中文分词器，指定用户词典时追加到内置词典。
+fn make_segmenter(dict: Option<&Path>) -> Result<Segmenter, Error> {
+    let mut segmenter = Segmenter::bundled();
+    if let Some(path) = dict {
+        segmenter
+            .load_file(&path.to_string_lossy())
+            .map_err(|e| e.context(format!("无法读取词典 {}", path.display())))?;
+    }
+    Ok(segmenter)
+}
+
+/// 根据缓存记录的名称创建分词器，中文分词时将用户词典追加到内置词典，词典与统计时不一致则返回错误。
+fn make_tokenizer(name: &str, dict: Option<&Path>) -> Result<Box<dyn Tokenizer>, Error> {
+    match dict {
+        Some(path) if name.split(';').next() == Some(Segmenter::NAME) => {
+            let segmenter = make_segmenter(dict)?;
+            if segmenter.name() != name {
+                bail!(
+                    "词典 {} 与统计时使用的词典不一致（缓存记录 {}，当前 {}）",
+                    path.display(),
+                    name,
+                    segmenter.name()
+                );
+            }
+            Ok(Box::new(segmenter))
+        }
+        _ => tokenizer_from_name(name),
+    }
+}
+
+/// 根据缓存记录的名称创建归并方式，缓存没有启用归并时返回 `None`。
+fn make_normalizer(meta: &CacheMeta) -> Result<Option<Box<dyn Normalizer>>, Error> {
+    meta.normalizer
+        .as_deref()
+        .map(normalizer_from_name)
+        .transpose()
+}
+
+/// 按归并方式归并单词，没有归并方式时返回原单词。
+pub fn normalize<'a>(normalizer: Option<&dyn Normalizer>, word: &'a str) -> Cow<'a, str> {
+    match normalizer {
+        Some(normalizer) => normalizer.normalize(word),
+        None => Cow::Borrowed(word),
+    }
+}
+
+/// 单词不在树中时，列出按字母序排在 `key` 前后的两个单词及其出现次数，便于发现拼写或归并上的差异。
+///
+/// 返回要输出的一行，树为空时返回 `None`。
+pub fn neighbours_line(words: &BSTMap<String, WordStats>, key: &str) -> Option<String> {
+    if words.is_empty() {
+        return None;
+    }
+    let show = |word: Option<&KeyValue<String, WordStats>>| {
+        word.map_or_else(|| "(无)".to_string(), |word| word.to_string())
+    };
+    let tree = words.tree();
+    Some(format!(
+        "  字母序相邻的单词: {} | {}",
+        show(tree.predecessor(key)),
+        show(tree.successor(key))
+    ))
+}
+
+/// 单词不在树中时，列出编辑距离不超过 `max_distance` 的单词及其出现次数作为拼写建议，
+/// 按距离从小到大、出现次数从多到少排列，最多列出 `limit` 个。
+///
+/// 返回要输出的一行，没有相近的单词或 `max_distance`、`limit` 为 0 时返回 `None`。
+pub fn suggestions_line(
+    words: &BSTMap<String, WordStats>,
+    key: &str,
+    max_distance: usize,
+    metric: Metric,
+    limit: usize,
+) -> Option<String> {
+    if max_distance == 0 || limit == 0 {
+        return None;
+    }
+    let matches = fuzzy_search(words.tree(), key, max_distance, metric, |word| {
+        word.value.count()
+    });
+    if matches.is_empty() {
+        return None;
+    }
+    let shown: Vec<String> = matches
+        .iter()
+        .take(limit)
+        .map(|m| m.item.to_string())
+        .collect();
+    Some(format!("  您是不是要找: {}", shown.join(", ")))
+}
+
+/// 按前缀、字母区间或模式指定的一组单词。
+pub enum WordRange {
+    /// 以该前缀开头的单词
+    Prefix(String),
+    /// 按字母序落在起点和终点之间（均包含）的单词，`None` 表示不限
+    Between(Option<String>, Option<String>),
+    /// 匹配通配符模式（`*` 匹配任意多个字符，`?` 匹配一个字符）的单词
+    Wildcard(String),
+    /// 匹配正则表达式的单词
+    Regex(Pattern),
+}
+
+/// 按字母序列出单词范围中的每个单词及其出现次数。
+///
+/// 前缀、端点和通配符模式中的各段字面文字先按分词器转换为缓存中单词的写法（如转为小写），切分出多个词时保持原样；
+/// 正则表达式按原样匹配。都不做归并，因为归并后的词干（如 "hostag"）通常不是单词前缀的归并结果。
+pub fn range_words<'a>(
+    words: &'a BSTMap<String, WordStats>,
+    tokenizer: &dyn Tokenizer,
+    range: &WordRange,
+) -> Vec<&'a KeyValue<String, WordStats>> {
+    let key = |text: &str| match tokenizer.tokenize(text).as_slice() {
+        [word] => word.to_string(),
+        _ => text.trim().to_string(),
+    };
+    let wildcard;
+    let matched: Box<dyn Iterator<Item = &'a KeyValue<String, WordStats>>> = match range {
+        WordRange::Prefix(prefix) => Box::new(words.tree().prefix(&key(prefix))),
+        WordRange::Between(from, to) => {
+            let (from, to) = (from.as_deref().map(key), to.as_deref().map(key));
+            let bounds = (
+                from.as_deref().map_or(Bound::Unbounded, Bound::Included),
+                to.as_deref().map_or(Bound::Unbounded, Bound::Included),
+            );
+            Box::new(words.tree().range::<str, _>(bounds))
+        }
+        WordRange::Wildcard(pattern) => {
+            // 只转换通配符之间的字面文字，通配符本身会被分词器去掉
+            let mut converted = String::new();
+            for part in pattern.trim().split_inclusive(['*', '?']) {
+                let literal = part.trim_end_matches(['*', '?']);
+                match tokenizer.tokenize(literal).as_slice() {
+                    [word] => converted.push_str(word),
+                    _ => converted.push_str(literal),
+                }
+                converted.push_str(&part[literal.len()..]);
+            }
+            wildcard = Pattern::wildcard(&converted);
+            Box::new(pattern_search(words.tree(), &wildcard))
+        }
+        WordRange::Regex(pattern) => Box::new(pattern_search(words.tree(), pattern)),
+    };
+    matched.collect()
+}
+
+/// 按文档编号缓存上下文索引，同一篇文档只读取和分词一次，读取失败的文档只提示一次。
+#[derive(Default)]
+pub struct KwicView {
+    docs: HashMap<u32, Option<Concordance>>,
+}
+
+impl KwicView {
+    /// 加入没有保存为文件的文档（如直接输入的文本），使其也能输出上下文。
+    pub fn add_text(&mut self, doc: u32, text: String, tokenizer: &dyn Tokenizer) {
+        self.docs
+            .insert(doc, Some(Concordance::new(text, tokenizer)));
+    }
+
+    /// 列出单词每次出现的位置及其前后各 `width` 个词，每处一行，位置左对齐、单词上下对齐；
+    /// 无法读取原文的位置只列出位置，读取失败的提示输出到标准错误。`limit` 为 `None` 时全部列出。
+    pub fn lines(
+        &mut self,
+        meta: &CacheMeta,
+        tokenizer: &dyn Tokenizer,
+        word: &WordStats,
+        width: usize,
+        limit: Option<usize>,
+    ) -> Vec<String> {
+        let shown = limit.unwrap_or(usize::MAX).min(word.positions().len());
+        let positions = &word.positions()[..shown];
+        for position in positions {
+            self.docs.entry(position.doc).or_insert_with(|| {
+                match source_text(meta, position.doc) {
+                    Ok(text) => Some(Concordance::new(text, tokenizer)),
+                    Err(e) => {
+                        eprintln!("警告: {e:#}");
+                        None
+                    }
+                }
+            });
+        }
+        let lines: Vec<_> = positions
+            .iter()
+            .map(|position| {
+                let line = self.docs[&position.doc]
+                    .as_ref()
+                    .and_then(|concordance| concordance.line(position.span(), width));
+                (position.to_string(), line)
+            })
+            .collect();
+        let tag_width = lines.iter().map(|(tag, _)| tag.len()).max().unwrap_or(0);
+        let left_width = lines
+            .iter()
+            .filter_map(|(_, line)| line.as_ref())
+            .map(|line| line.left.chars().count())
+            .max()
+            .unwrap_or(0);
+        let mut output: Vec<String> = lines
+            .into_iter()
+            .map(|(tag, line)| match line {
+                Some(line) => format!(
+                    "  {tag:<tag_width$}  {:>left_width$}[{}]{}",
+                    line.left, line.keyword, line.right
+                ),
+                None => format!("  {tag}"),
+            })
+            .collect();
+        if positions.len() < word.positions().len() {
+            output.push(format!(
+                "  …… 共 {} 处，只显示前 {} 处",
+                word.positions().len(),
+                positions.len()
+            ));
+        }
+        output
+    }
+}
+
+/// 将多个文件中的单词依次追加到单词计数映射中。
+fn append_files(
+    bstree: &mut BSTMap<String, WordStats>,
+    meta: &mut CacheMeta,
+    tokenizer: &dyn Tokenizer,
+    normalizer: Option<&dyn Normalizer>,
+    files: &[PathBuf],
+) -> Result<(), Error> {
+    for file in files {
+        let text = read_text(file)?;
+        let source = if file == Path::new("-") {
+            "<stdin>".to_string()
+        } else {
+            file.display().to_string()
+        };
+        append_text(bstree, meta, tokenizer, normalizer, &source, &text);
+    }
+    Ok(())
+}
+
+/// 加载缓存文件，自动识别格式。
+fn load(path: &Path) -> Result<(BSTMap<String, WordStats>, CacheMeta), Error> {
+    load_cache_file(path).map_err(|e| e.context(format!("无法加载缓存 {}", path.display())))
+}
+
+/// 按指定格式保存缓存文件，未指定格式时 `.bin` 扩展名保存为二进制格式，其余保存为紧凑格式。
+fn save(
+    path: &Path,
+    bstree: &BSTMap<String, WordStats>,
+    meta: &CacheMeta,
+    format: Option<FormatArg>,
+) -> Result<(), Error> {
+    let format = format.unwrap_or(match path.extension() {
+        Some(ext) if ext == "bin" => FormatArg::Binary,
+        _ => FormatArg::Compact,
+    });
+    let result = match format {
+        FormatArg::Binary => save_binary_cache(path, bstree, meta),
+        FormatArg::Compact => cache_to_json(bstree, meta, CacheFormat::Compact)
+            .and_then(|cache| write_str_to_file(path, &cache)),
+        FormatArg::Tree => cache_to_json(bstree, meta, CacheFormat::Tree)
+            .and_then(|cache| write_str_to_file(path, &cache)),
+    };
+    result.map_err(|e| e.context(format!("无法保存缓存 {}", path.display())))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    const TEXT: &str =
+        "The hostages were freed.\nThe hostage crisis ended and the hostages went home.";
+
+    /// 解析命令行参数并执行子命令，返回退出码和标准输出的内容。
+    fn run_args(args: &[&str]) -> (u8, String) {
+        let cli = Cli::try_parse_from(["bst_words"].iter().chain(args)).unwrap();
+        let mut out = Vec::new();
+        let result = execute(cli.command.unwrap(), cli.dict.as_deref(), &mut out);
+        (exit_status(&result), String::from_utf8(out).unwrap())
+    }
+
+    /// 写入测试用的文本，统计后保存为缓存，返回缓存路径。
+    fn make_cache(name: &str, extra: &[&str]) -> String {
+        let text = format!("target/test_cli_{name}.txt");
+        let cache = format!("target/test_cli_{name}.json");
+        fs::write(&text, TEXT).unwrap();
+        let mut args = vec!["count", text.as_str(), "-o", cache.as_str()];
+        args.extend(extra);
+        assert_eq!(run_args(&args), (0, String::new()));
+        cache
+    }
+
+    #[test]
+    fn test_count() {
+        fs::write("target/test_cli_count.txt", TEXT).unwrap();
+        let (status, out) = run_args(&["count", "target/test_cli_count.txt"]);
+        assert_eq!(status, 0);
+        assert_eq!(
+            out.lines().collect::<Vec<_>>(),
+            [
+                "and: 1",
+                "crisis: 1",
+                "ended: 1",
+                "freed: 1",
+                "home: 1",
+                "hostage: 1",
+                "hostages: 2",
+                "the: 3",
+                "went: 1",
+                "were: 1"
+            ]
+        );
+        // 输入文件不存在时退出码为 2
+        let (status, out) = run_args(&["count", "target/no_such_file.txt"]);
+        assert_eq!((status, out.as_str()), (2, ""));
+    }
+
+    #[test]
+    fn test_query() {
+        let cache = make_cache("query", &["--case-variants"]);
+        let (status, out) = run_args(&["query", &cache, "Hostages", "the"]);
+        assert_eq!(status, 0);
+        assert_eq!(out, "hostages: 2\nthe: 3 [The 2, the 1]\n");
+
+        // 有单词不在树中时输出相邻的单词和拼写建议，退出码为 1
+        let (status, out) = run_args(&["query", &cache, "hostges", "home"]);
+        assert_eq!(status, 1);
+        assert_eq!(
+            out,
+            "hostges: 不在bstree中.\n  字母序相邻的单词: hostages: 2 | the: 3\n  \
+             您是不是要找: hostages: 2, hostage: 1\nhome: 1\n"
+        );
+        let (status, out) = run_args(&["query", &cache, "hostges", "-d", "0"]);
+        assert_eq!(status, 1);
+        assert!(!out.contains("您是不是要找"), "{out}");
+
+        // 切分后没有可查询的单词或缓存不存在时退出码为 2
+        assert_eq!(run_args(&["query", &cache, "!!!"]), (2, String::new()));
+        assert_eq!(run_args(&["query", &cache, "2023"]), (2, String::new()));
+        let (status, _) = run_args(&["query", "target/no_such_cache.json", "the"]);
+        assert_eq!(status, 2);
+    }
+
+    #[test]
+    fn test_user_dict() {
+        fs::write("target/test_cli_dict_text.txt", "三名人质获释").unwrap();
+        fs::write("target/test_cli_dict.txt", "三名人质\n").unwrap();
+        fs::write("target/test_cli_dict_other.txt", "获释\n").unwrap();
+        let cache = "target/test_cli_dict.json";
+        let args = [
+            "count",
+            "target/test_cli_dict_text.txt",
+            "-t",
+            "cjk",
+            "--dict",
+            "target/test_cli_dict.txt",
+            "-o",
+            cache,
+        ];
+        assert_eq!(run_args(&args), (0, String::new()));
+        let query = [
+            "query",
+            cache,
+            "三名人质",
+            "--dict",
+            "target/test_cli_dict.txt",
+        ];
+        assert_eq!(run_args(&query), (0, "三名人质: 1\n".to_string()));
+        // 没有指定词典或指定了其他词典时退出码为 2
+        assert_eq!(run_args(&["query", cache, "三名人质"]).0, 2);
+        let query = [
+            "query",
+            cache,
+            "三名人质",
+            "--dict",
+            "target/test_cli_dict_other.txt",
+        ];
+        assert_eq!(run_args(&query).0, 2);
+    }
+
+    #[test]
+    fn test_append() {
+        let cache = make_cache("append", &[]);
+        fs::write("target/test_cli_append_more.txt", "More hostages.").unwrap();
+        let output = "target/test_cli_append_out.bin";
+        let args = [
+            "append",
+            &cache,
+            "target/test_cli_append_more.txt",
+            "-o",
+            output,
+        ];
+        assert_eq!(run_args(&args), (0, String::new()));
+        let (status, out) = run_args(&["query", output, "hostages", "more"]);
+        assert_eq!((status, out.as_str()), (0, "hostages: 3\nmore: 1\n"));
+        // 没有指定输出文件时原缓存不变
+        let (_, out) = run_args(&["query", &cache, "hostages"]);
+        assert_eq!(out, "hostages: 2\n");
+
+        let args = ["append", &cache, "target/no_such_file.txt"];
+        assert_eq!(run_args(&args).0, 2);
+    }
+
+    #[test]
+    fn test_kwic() {
+        let cache = make_cache("kwic", &["--positions"]);
+        let (status, out) = run_args(&["kwic", &cache, "crisis", "-w", "1"]);
+        assert_eq!(status, 0);
+        assert_eq!(out, "crisis: 1\n  #0:2:13  hostage [crisis] ended\n");
+        let (status, out) = run_args(&["kwic", &cache, "hostages", "-w", "1", "-l", "1"]);
+        assert_eq!(status, 0);
+        assert_eq!(
+            out,
+            "hostages: 2\n  #0:1:5  The [hostages] were\n  …… 共 2 处，只显示前 1 处\n"
+        );
+        let (status, out) = run_args(&["kwic", &cache, "nothing"]);
+        assert_eq!((status, out.as_str()), (1, "nothing: 不在bstree中.\n"));
+        assert_eq!(run_args(&["kwic", &cache, "..."]), (2, String::new()));
+
+        // 缓存没有记录出现位置时退出码为 2
+        let cache = make_cache("kwic_plain", &[]);
+        assert_eq!(run_args(&["kwic", &cache, "crisis"]).0, 2);
+    }
+
+    #[test]
+    fn test_range() {
+        let cache = make_cache("range", &[]);
+        let range = |args: &[&str]| run_args(&[&["range", cache.as_str()], args].concat());
+        assert_eq!(
+            range(&["-p", "Host"]),
+            (0, "hostage: 1\nhostages: 2\n".to_string())
+        );
+        assert_eq!(
+            range(&["--from", "h", "--to", "the"]),
+            (0, "home: 1\nhostage: 1\nhostages: 2\nthe: 3\n".to_string())
+        );
+        assert_eq!(
+            range(&["-w", "*e?"]),
+            (0, "ended: 1\nfreed: 1\nhostages: 2\n".to_string())
+        );
+        assert_eq!(
+            range(&["-r", "^w|^t"]),
+            (0, "the: 3\nwent: 1\nwere: 1\n".to_string())
+        );
+        assert_eq!(
+            range(&["-p", "x"]),
+            (1, "没有符合条件的单词.\n".to_string())
+        );
+        // 无效的正则表达式退出码为 2
+        assert_eq!(range(&["-r", "^host("]), (2, String::new()));
+    }
+
+    #[test]
+    fn test_stop() {
+        let cache = make_cache("stop", &[]);
+        let output = "target/test_cli_stop_out.json";
+        let (status, out) = run_args(&["stop", &cache, "-w", "The", "and", "-o", output]);
+        assert_eq!(status, 0);
+        assert_eq!(out, "停用词:the 删除成功\n停用词:and 删除成功\n");
+        let (status, out) = run_args(&["query", output, "the"]);
+        assert_eq!(status, 1);
+        assert!(out.starts_with("the: 不在bstree中.\n"), "{out}");
+
+        fs::write("target/test_cli_stop_words.txt", "were went nothing").unwrap();
+        let args = ["stop", output, "--file", "target/test_cli_stop_words.txt"];
+        let (status, out) = run_args(&args);
+        assert_eq!(status, 1);
+        assert_eq!(
+            out,
+            "停用词:were 删除成功\n停用词:went 删除成功\n停用词:nothing 不在bstree中.\n"
+        );
+        let (_, out) = run_args(&["traverse", output]);
+        assert_eq!(out.lines().count(), 6);
+    }
+
+    #[test]
+    fn test_traverse() {
+        let cache = make_cache("traverse", &[]);
+        let (status, out) = run_args(&["traverse", &cache, "-o", "reverse-in"]);
+        assert_eq!(status, 0);
+        let words: Vec<&str> = out.lines().collect();
+        assert_eq!(words.len(), 10);
+        assert_eq!(words.first(), Some(&"were: 1"));
+        assert_eq!(words.last(), Some(&"and: 1"));
+
+        // 紧凑格式加载为完全平衡的树，10 个单词共 4 层
+        let (status, out) = run_args(&["traverse", &cache, "-o", "level", "--levels"]);
+        assert_eq!(status, 0);
+        assert_eq!(
+            out,
+            "0: hostage: 1\n1: ended: 1 | went: 1\n2: crisis: 1 | home: 1 | the: 3 | were: 1\n\
+             3: and: 1 | freed: 1 | hostages: 2\n"
+        );
+    }
+
+    /// 模拟已被另一端关闭的管道。
+    struct ClosedPipe;
+
+    impl Write for ClosedPipe {
+        fn write(&mut self, _: &[u8]) -> std::io::Result<usize> {
+            Err(ErrorKind::BrokenPipe.into())
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_broken_pipe() {
+        let cache = make_cache("pipe", &[]);
+        let cli = Cli::try_parse_from(["bst_words", "traverse", &cache]).unwrap();
+        let result = execute(cli.command.unwrap(), None, &mut ClosedPipe);
+        assert!(result.is_err());
+        assert_eq!(exit_status(&result), 0);
+        let other = Err(std::io::Error::other("disk full").into());
+        assert_eq!(exit_status(&other), 2);
+    }
+
+    #[test]
+    fn test_convert() {
+        let cache = make_cache("convert", &["--case-variants"]);
+        for (output, format) in [
+            ("target/test_cli_convert.bin", None),
+            ("target/test_cli_convert_tree.json", Some("tree")),
+        ] {
+            let mut args = vec!["convert", cache.as_str(), output];
+            args.extend(format.iter().flat_map(|format| ["-f", format]));
+            assert_eq!(run_args(&args), (0, String::new()));
+            let (status, out) = run_args(&["query", output, "the", "hostages"]);
+            assert_eq!(status, 0);
+            assert_eq!(out, "the: 3 [The 2, the 1]\nhostages: 2\n");
+        }
+        let args = ["convert", "target/no_such_cache.json", "target/unused.json"];
+        assert_eq!(run_args(&args).0, 2);
+    }
+}
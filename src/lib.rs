@@ -2,6 +2,9 @@
 //!
 //! 该程序通过构建二叉搜索树（BST）来实现对输入文本中单词的计数和相关操作。支持从用户输入、文件读取、以及加载缓存三种方式读入文本，用户可以执行停用词过滤、遍历、查询、保存缓存和退出等操作。
 //!
+//! 不带子命令运行时进入交互式菜单；也可以使用 `count`、`append`、`query`、`kwic`、`range`、`stop`、`traverse`、`convert`
+//! 等子命令在脚本或管道中非交互地使用，详见 `bst_words --help`。
+//!
 //! ## 1. 读入数据
 //!
 //! - 用户在程序开始时被提示选择读入文本的方式：输入文本、输入路径、或加载缓存。
@@ -9,8 +12,14 @@
 //!
 //! ## 2. 构建二叉查找树 (BST)
 //!
-//! - 如果选择加载缓存，则程序会读取 JSON 文件，并将其反序列化为 `BSTree<Word>` 类型。
+//! - 如果选择加载缓存，则程序会读取 JSON 文件，并将其反序列化为 `BSTree<Word>` 类型。缓存以前序序列保存，任意高度的树都能还原，同时兼容旧版本的嵌套结点格式。
+//! - 也可以保存为二进制格式（varint 编码，逐个单词流式读写），适合很大的词表；加载时根据文件开头自动识别二进制和 JSON 格式。
+//! - 缓存带有文件头，记录版本、创建时间、文本来源及其校验和、分词设置、单词总数和 payload 的校验和，加载时会校验版本和校验和，不匹配时提示原因并让用户重新选择。
 //! - 如果选择输入文本或输入路径，则程序会根据用户提供的文本内容或文件路径构建一个 BST，其中存储了单词和它们的出现次数。
+//! - 构建前可选择分词方式：只保留 ASCII 字母，按 Unicode 单词边界（UAX #29）切分以保留各种文字（还可以额外做大小写折叠），只按空白切分，或按正则表达式匹配；缩写（如 "don't"）可以拆开、保留或展开，连字符复合词可以拆开或保留，弯撇号可以统一为直撇号，数字可以丢弃、保留或统一计为 `<NUM>`，还可以允许下划线等符号出现在单词中；中英文混合的文本可以使用中文词典分词，连续的汉字按内置词典或用户词典正向最大匹配切分为词语。分词方式记录在缓存中，停用词、查询和追加都使用同一个分词器。库的使用者也可以实现 `utils::Tokenizer` 提供自己的分词方式。
+//! - 分词之后还可以选择把单词的不同形式归并为同一个单词：使用内置的 Porter 英文词干提取（"killed"、"killing" 计为 "kill"），或使用词典文件做词形还原（"went" 计为 "go"）。归并方式记录在缓存中，每个单词同时记录归并前的原始形式及其出现次数，查询时输出如 `kill: 5 (killed 3, killing 2)`。
+//! - 还可以记录每个单词在原文中的各种大小写写法及其出现次数（如 "Israeli"、"ISRAELI"、"israeli"），便于识别专有名词和缩略词，查询时一并输出。
+//! - 还可以记录每个单词每次出现的位置（文档、行号、列号和字节偏移），查询时按位置重新读取原文，输出单词前后若干个词的上下文（KWIC 索引），命令行中使用 `kwic` 子命令。
 //!
 //! ## 3. 主要功能
 //!
@@ -18,27 +27,39 @@
 //!   - 用户提供停用词，程序从 BST 中删除对应的结点。
 //!
 //! - ### 遍历二叉查找树，输出每个单词及其出现次数
-//!   - 用户选择遍历方式（前序、中序、后序），程序输出 BST 中每个结点的数据。
+//!   - 用户选择遍历方式（前序、中序、后序、层序、逆中序），层序遍历可选择每一层单独输出一行，程序输出 BST 中每个结点的数据。
 //!
 //! - ### 输入查询词，搜索二叉查找树中的相应结点
-//!   - 用户提供查询词，程序从 BST 中查找对应结点并输出其出现次数。
+//!   - 用户提供查询词，程序从 BST 中查找对应结点并输出其出现次数，启用归并时同时输出归并到该单词的各个原始形式。
+//!   - 查询词不在 BST 中时，输出按字母序排在它前后的两个单词（前驱和后继）及其出现次数，并按编辑距离（允许交换相邻字符）列出相近的单词作为"您是不是要找"的建议，如把 "hostge" 提示为 "hostage"。
+//!
+//! - ### 按前缀、字母区间或模式列出单词及其出现次数
+//!   - 用户输入前缀（如 "inter"）或区间的起点和终点，程序沿 BST 定位区间的两端，按字母序输出区间内的单词及其出现次数，命令行中使用 `range` 子命令。
+//!   - 也可以输入通配符模式（如 "kill*"、"*ing"、"h?st?ge"）或正则表达式，模式有字面前缀时只检查以该前缀开头的区间，命令行中使用 `range --wildcard` 或 `range --regex`。
+//!
+//! - ### 追加文本或文件到当前二叉查找树
+//!   - 用户输入文本或文件路径，程序将其中的单词追加到当前 BST（例如已加载的缓存）中，累积多篇文档的计数。
 //!
 //! - ### 保存为缓存
-//!   - 用户输入文件名，程序将当前 BST 序列化为 JSON 格式并保存为文件。
+//!   - 用户选择缓存格式并输入文件名，程序将当前 BST 序列化为 JSON 格式并保存为文件。
+//!   - 紧凑格式为按单词排序的 `[单词, 次数]` 数组，加载时重建为平衡树；树形格式保留树的形状。加载时自动识别格式。
 //!
 //! - ### 退出程序
 //!   - 用户选择退出程序，程序正常结束。
 //!
 //! ## 4. 数据结构
 //!
-//! 使用二叉搜索树（BST）来存储单词及其出现次数。
+//! 使用二叉搜索树（BST）来存储单词及其出现次数。单词计数为以单词为键、统计信息为值的 `BSTMap<String, WordStats>`，
+//! 底层的 BST 只按键比较，从文本构建时使用 AVL 平衡，保证有序输入下插入、查找、删除仍为 O(log n)。
 //!
 //! ## 5. 主要模块
 //!
 //! - `bst`: 包含 `BSTree` 结构体和与二叉搜索树相关的操作。
-//! - `r#type`: 包含 `Order` 枚举类型和 `Word` 结构体。
+//! - `map`: 包含基于 `BSTree` 的键值映射 `BSTMap` 和键值对 `KeyValue`。
+//! - `r#type`: 包含 `Order`、`Balance` 枚举类型和 `Word`、`WordStats` 结构体。
 //! - `utils`: 包含与文本处理相关的工具函数，如过滤单词、文件读取和写入。
 
 pub mod bst;
+pub mod map;
 pub mod r#type;
 pub mod utils;
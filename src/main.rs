@@ -1,15 +1,44 @@
-// 引入bst库中的BSTree结构体，Order枚举类型，和Word结构体
-use bst_words::bst::BSTree;
-use bst_words::r#type::{Order, Word};
-// 引入bst库中的一些工具函数，比如过滤文本中的单词，从文件中读取文本，和将字符串写入文件
-use bst_words::utils::{filter_word, read_from_file, write_str_to_file};
-// 引入dialoguer库中的ColorfulTheme结构体，Input结构体，和Select结构体，它们用于创建彩色的主题，输入框，和选择框
-use dialoguer::{theme::ColorfulTheme, Input, Select};
-// 引入std库中的process模块，它用于处理程序的退出
-use std::process;
+// 引入bst库中的BSTMap结构体，Order枚举类型，和WordStats结构体
+use bst_words::map::BSTMap;
+use bst_words::r#type::{Order, WordStats};
+// 引入bst库中的一些工具函数，比如过滤文本中的单词，从文件中读取文本，将字符串写入文件，以及缓存的读写
+use bst_words::utils::{
+    append_file, append_text, cache_to_json, load_cache_file, normalizer_from_name, read_from_file,
+    save_binary_cache, tokenizer_from_name, write_str_to_file, AsciiTokenizer, CacheFormat,
+    CacheMeta, Contractions, Hyphens, Lemmatizer, Metric, Normalizer, Numbers, Pattern,
+    PorterStemmer, RegexTokenizer, Segmenter, Symbols, Tokenizer, UnicodeTokenizer,
+    WhitespaceTokenizer, WordRules, CACHE_VERSION,
+};
+// 引入dialoguer库中的ColorfulTheme结构体，Confirm结构体，Input结构体，MultiSelect结构体，和Select结构体，它们用于创建彩色的主题，确认框，输入框，多选框，和选择框
+use dialoguer::{theme::ColorfulTheme, Confirm, Input, MultiSelect, Select};
+// 引入clap库中的Parser trait，它用于解析命令行参数
+use clap::Parser;
+// 引入std库中的process模块，它用于设置程序的退出码
+use std::process::ExitCode;
 
-// 定义程序的主函数
-fn main() {
+// 非交互式的命令行子命令
+mod cli;
+
+// 查询时每次出现输出前后各多少个词的上下文
+const KWIC_WIDTH: usize = 5;
+// 查询时每个单词最多输出多少次出现的上下文
+const KWIC_LIMIT: usize = 10;
+// 查询的单词不在树中时，拼写建议允许的最大编辑距离
+const SUGGEST_DISTANCE: usize = 2;
+// 查询的单词不在树中时，最多给出多少个拼写建议
+const SUGGEST_LIMIT: usize = 5;
+
+// 定义程序的主函数，带子命令时执行相应的命令，否则进入交互式菜单
+fn main() -> ExitCode {
+    let cli = cli::Cli::parse();
+    match cli.command {
+        Some(command) => cli::run(command, cli.dict),
+        None => interactive(),
+    }
+}
+
+// 交互式菜单
+fn interactive() -> ExitCode {
     // 打印欢迎信息，使用ASCII艺术字表示程序的名称
     println!(
         r#"
@@ -25,9 +54,16 @@ fn main() {
     let theme = ColorfulTheme::default();
 
     // 定义一个字符串变量，用于存储文本内容
-    let mut context: String;
+    let mut context = String::new();
+    // 定义一个字符串变量，用于存储文本的来源，直接输入的文本记为<input>
+    let mut source = String::from("<input>");
+
+    // 定义一个BSTMap<String, WordStats>类型的变量，以单词为键存储它们的出现次数等统计信息，底层的BST使用AVL平衡，避免有序文本使树退化成链表
+    let mut bstree: BSTMap<String, WordStats> = BSTMap::new();
+    // 定义一个CacheMeta类型的变量，用于记录缓存文件头中的来源、分词设置和单词总数
+    let mut meta = CacheMeta::new(AsciiTokenizer::NAME);
 
-    // 创建一个布尔变量，用于标记文本是否是JSON格式
+    // 创建一个布尔变量，用于标记是否已经加载了缓存
     let mut is_json = false;
     // 使用一个无限循环，用于让用户选择读入文本的方式
     loop {
@@ -57,18 +93,50 @@ fn main() {
                     .with_prompt("输入纯文本文件路径")
                     .interact_text()
                     .expect("无法读取输入");
-                context = read_from_file(path).ok().unwrap();
+                context = read_from_file(&path).ok().unwrap();
+                source = path;
                 // 跳出循环
                 break;
             }
             2 => {
-                // 如果用户选择加载缓存，那么创建一个输入框，用于让用户输入JSON文件的路径，并调用read_from_file函数，从文件中读取文本内容，并将其赋值给context变量
+                // 如果用户选择加载缓存，那么创建一个输入框，用于让用户输入缓存文件的路径
                 let path: String = Input::with_theme(&theme)
-                    .with_prompt("输入json文件路径")
+                    .with_prompt("输入缓存文件路径(json或bin)")
                     .interact_text()
                     .expect("无法读取输入");
-                context = read_from_file(path).ok().unwrap();
-                // 将is_json变量设为true，表示文本是JSON格式
+                // 调用load_cache_file函数，根据文件开头自动识别二进制或JSON格式，校验缓存文件头的版本和校验和，并将其反序列化为BSTMap<String, WordStats>类型，如果加载失败，那么打印错误原因，并让用户重新选择
+                match load_cache_file(path) {
+                    Ok((tree, cache_meta)) => {
+                        bstree = tree;
+                        meta = cache_meta;
+                    }
+                    Err(e) => {
+                        println!("加载缓存失败: {e}");
+                        continue;
+                    }
+                }
+                // 打印缓存文件头中的信息，旧版本的缓存没有文件头，保存时会升级为当前版本
+                if meta.version == 1 {
+                    println!("该缓存为没有文件头的旧版本缓存，保存时将升级为版本{CACHE_VERSION}.");
+                } else {
+                    println!(
+                        "缓存版本: {}, 创建时间(Unix时间戳): {}, 分词设置: {}, 归并方式: {}, 记录大小写: {}, 记录位置: {}, 单词总数: {}",
+                        meta.version,
+                        meta.created,
+                        meta.tokenizer,
+                        meta.normalizer.as_deref().unwrap_or("无"),
+                        if meta.case_variants { "是" } else { "否" },
+                        if meta.positions { "是" } else { "否" },
+                        meta.total_tokens
+                    );
+                    for info in &meta.sources {
+                        println!("来源: {} ({}, {}个单词)", info.path, info.hash, info.tokens);
+                    }
+                    if meta.version < CACHE_VERSION {
+                        println!("保存时将升级为版本{CACHE_VERSION}.");
+                    }
+                }
+                // 将is_json变量设为true，表示已经加载了缓存
                 is_json = true;
                 // 跳出循环
                 break;
@@ -79,38 +147,89 @@ fn main() {
             }
         }
     }
-    // 定义一个BSTree<Word>类型的变量，用于存储单词和它们的出现次数
-    let mut bstree: BSTree<Word>;
-    // 如果文本是JSON格式，那么调用serde_json库中的from_str函数，将文本反序列化为BSTree<Word>类型，并赋值给bstree变量，如果反序列化失败，那么panic结束程序，并打印错误信息
-    if is_json {
-        bstree = serde_json::from_str(&context).unwrap_or_else(|e| {
-            panic!("加载缓存失败,请确保文件为正确json格式.\n{e}");
-        });
+    // 定义一个分词器，构建、追加、查询和停用词都使用同一个分词器，保证得到的单词一致
+    let tokenizer: Box<dyn Tokenizer> = if is_json {
+        // 如果加载了缓存，那么使用缓存记录的分词方式，旧版本的缓存没有记录分词方式，使用当时唯一的ASCII分词
+        if meta.tokenizer.split(';').next() == Some(Segmenter::NAME) {
+            // 中文分词需要用户重新指定统计时使用的用户词典，名称记录了词典内容，不一致时让用户重新输入
+            Box::new(choose_dictionary(&theme, Some(&meta.tokenizer)))
+        } else {
+            match tokenizer_from_name(&meta.tokenizer) {
+                Ok(tokenizer) => tokenizer,
+                Err(err) => {
+                    println!("{}，使用ASCII分词.", err);
+                    Box::new(AsciiTokenizer::new())
+                }
+            }
+        }
     } else {
-        // 如果文本不是JSON格式，那么创建一个空的BSTree<Word>类型的变量，并赋值给bstree变量
-        bstree = BSTree::new();
-        // 调用filter_word函数，将文本中的单词过滤出来，并返回一个迭代器
-        for word in filter_word(&mut context) {
-            // 对于每个单词，调用bstree的insert_fn方法，将单词作为Word类型的结点插入到BST中，如果BST中已经存在该单词，那么调用匿名函数，将该单词的出现次数加一
-            bstree.insert_fn(Word::new(word.to_string()), |node| {
-                node.data.add();
-            })
+        // 如果没有加载缓存，那么让用户选择分词方式，并记录在缓存文件头中
+        let tokenizer = choose_tokenizer(&theme);
+        meta = CacheMeta::new(tokenizer.name());
+        tokenizer
+    };
+    // 定义一个归并方式，构建、追加、查询和停用词都使用同一个归并方式，使单词的不同形式计为同一个单词
+    let normalizer: Option<Box<dyn Normalizer>> = if is_json {
+        // 如果加载了缓存，那么使用缓存记录的归并方式，如果词典无法读取，那么打印错误信息，并且不做归并
+        match meta.normalizer.as_deref().map(normalizer_from_name) {
+            Some(Ok(normalizer)) => Some(normalizer),
+            Some(Err(err)) => {
+                println!("{:#}，不做归并.", err);
+                None
+            }
+            None => None,
         }
+    } else {
+        // 如果没有加载缓存，那么让用户选择归并方式，并记录在缓存文件头中
+        let normalizer = choose_normalizer(&theme);
+        meta.normalizer = normalizer.as_ref().map(|n| n.name().to_string());
+        // 询问是否记录单词在原文中的大小写写法，用于识别专有名词和缩略词
+        meta.case_variants = Confirm::with_theme(&theme)
+            .with_prompt("是否记录单词在原文中的大小写写法(如Israeli、ISRAELI)?")
+            .default(false)
+            .interact()
+            .expect("无法读取输入");
+        // 询问是否记录单词每次出现的位置，查询时可以输出单词在原文中的上下文
+        meta.positions = Confirm::with_theme(&theme)
+            .with_prompt("是否记录单词每次出现的位置(查询时输出上下文)?")
+            .default(false)
+            .interact()
+            .expect("无法读取输入");
+        normalizer
+    };
+    // 如果没有加载缓存，那么调用append_text函数，将文本中的单词过滤出来并逐个插入到BST中，如果BST中已经存在该单词，那么将该单词的出现次数加一，同时在meta中记录文本来源
+    if !is_json {
+        append_text(
+            &mut bstree,
+            &mut meta,
+            &*tokenizer,
+            normalizer.as_deref(),
+            &source,
+            &context,
+        );
+    }
+    // 定义一个上下文索引，查询时按记录的位置输出单词的上下文，来源文件在需要时重新读取
+    let mut kwic = cli::KwicView::default();
+    // 直接输入的文本没有保存为文件，如果记录了位置，那么把它加入上下文索引
+    if !is_json && meta.positions && source == "<input>" {
+        kwic.add_text(0, context, &*tokenizer);
     }
 
     // 使用一个无限循环，用于让用户选择菜单中的一项操作
     loop {
-        // 定义一个字符串数组，用于存储五种操作的名称
+        // 定义一个字符串数组，用于存储七种操作的名称
         let options = [
             "输入停用词，删除二叉查找树中的相应结点",
             "遍历二叉查找树，输出每个单词及其出现次数",
             "输入查询词，搜索二叉查找树中的相应结点",
+            "按前缀、字母区间或模式列出单词及其出现次数",
+            "追加文本或文件到当前二叉查找树",
             "保存为缓存",
             "退出程序",
         ];
         // 创建一个选择框，用于让用户选择其中一种操作
         let option = Select::with_theme(&theme)
-            .with_prompt("请选择以下四项操作之一：")
+            .with_prompt("请选择以下操作之一：")
             .default(0)
             .items(&options[..])
             .interact()
@@ -121,18 +240,19 @@ fn main() {
             0 => {
                 // 停用词功能
                 // 创建一个输入框，用于让用户输入停用词，停用词是指那些在文本中没有实际意义的词，比如“的”、“了”等
-                let mut input: String = Input::with_theme(&theme)
+                let input: String = Input::with_theme(&theme)
                     .with_prompt("输入停用词，以空格分割.")
                     .interact_text()
                     .expect("无法读取输入");
-                // 调用filter_word函数，将输入的停用词过滤出来，并返回一个迭代器
-                for word in filter_word(&mut input) {
-                    // 对于每个停用词，调用bstree的delete方法，将其从BST中删除，如果删除成功，那么打印成功信息，如果删除失败，那么打印失败信息
-                    match bstree.delete(Word::new(word.to_string())) {
-                        Ok(_) => {
+                // 调用tokenizer的tokenize方法，按与构建时相同的分词方式将输入的停用词过滤出来
+                for word in tokenizer.tokenize(&input) {
+                    // 对于每个停用词，先按相同的归并方式归并，再调用bstree的remove方法，将其从BST中删除，如果删除成功，那么打印成功信息，如果删除失败，那么打印失败信息
+                    let stem = cli::normalize(normalizer.as_deref(), &word);
+                    match bstree.remove(stem.as_ref()) {
+                        Some(_) => {
                             println!("停用词:{word} 删除成功");
                         }
-                        Err(_) => {
+                        None => {
                             println!("停用词:{word} 不在bstree中.");
                         }
                     }
@@ -142,8 +262,14 @@ fn main() {
                 // 遍历二叉树
                 // 使用一个无限循环，用于让用户选择遍历二叉树的方式
                 loop {
-                    // 定义一个字符串数组，用于存储三种遍历方式的名称，分别是前序遍历，中序遍历，和后序遍历
-                    let choices = ["PreOrder", "InOrder", "PostOrder"];
+                    // 定义一个字符串数组，用于存储五种遍历方式的名称，分别是前序遍历，中序遍历，后序遍历，层序遍历，和逆中序遍历
+                    let choices = [
+                        "PreOrder",
+                        "InOrder",
+                        "PostOrder",
+                        "LevelOrder",
+                        "ReverseInOrder",
+                    ];
                     // 创建一个选择框，用于让用户选择其中一种方式
                     let selection = Select::with_theme(&theme)
                         .with_prompt("请选择遍历方式")
@@ -155,25 +281,46 @@ fn main() {
                     match selection {
                         0 => {
                             // 如果用户选择前序遍历，那么调用bstree的traverse方法，传入Order::Pre作为参数，表示按照前序遍历的顺序输出每个结点的数据
-                            bstree.traverse(Order::Pre);
+                            bstree.tree().traverse(Order::Pre);
                             // 跳出循环
                             break;
                         }
                         1 => {
                             // 如果用户选择中序遍历，那么调用bstree的traverse方法，传入Order::In作为参数，表示按照中序遍历的顺序输出每个结点的数据
-                            bstree.traverse(Order::In);
+                            bstree.tree().traverse(Order::In);
                             // 跳出循环
                             break;
                         }
                         2 => {
                             // 如果用户选择后序遍历，那么调用bstree的traverse方法，传入Order::Post作为参数，表示按照后序遍历的顺序输出每个结点的数据
-                            bstree.traverse(Order::Post);
+                            bstree.tree().traverse(Order::Post);
+                            // 跳出循环
+                            break;
+                        }
+                        3 => {
+                            // 如果用户选择层序遍历，那么询问是否按层分行输出，分行时每一层的结点单独打印一行，便于观察树的形状
+                            let by_level = Confirm::with_theme(&theme)
+                                .with_prompt("是否每一层单独输出一行?")
+                                .default(true)
+                                .interact()
+                                .expect("无法读取输入");
+                            if by_level {
+                                bstree.tree().traverse_levels();
+                            } else {
+                                bstree.tree().traverse(Order::Level);
+                            }
+                            // 跳出循环
+                            break;
+                        }
+                        4 => {
+                            // 如果用户选择逆中序遍历，那么调用bstree的traverse方法，传入Order::ReverseIn作为参数，表示按照从大到小的顺序输出每个结点的数据
+                            bstree.tree().traverse(Order::ReverseIn);
                             // 跳出循环
                             break;
                         }
                         _ => {
                             // 如果用户选择了其他的选项，那么打印错误信息，并继续循环
-                            println!("无效的选择，请输入(0-2)");
+                            println!("无效的选择，请输入(0-4)");
                         }
                     }
                 }
@@ -181,46 +328,421 @@ fn main() {
             2 => {
                 // 查询单词出现次数
                 // 创建一个输入框，用于让用户输入要查询的单词，可以输入多个单词，以空格分割
-                let mut input: String = Input::with_theme(&theme)
+                let input: String = Input::with_theme(&theme)
                     .with_prompt("请输入要查询的单词，以空格分割.")
                     .interact_text()
                     .expect("无法读取输入");
 
-                // 调用filter_word函数，将输入的单词过滤出来，并返回一个迭代器
-                for word in filter_word(&mut input) {
-                    // 对于每个单词，调用bstree的find方法，将其作为Word类型的结点在BST中查找，如果找到了，那么打印该单词及其出现次数，如果没找到，那么打印未找到的信息
-                    if let Some(node) = bstree.find(Word::new(word.to_string())) {
-                        println!("{}出现次数: {}", word, node.data.count());
+                // 调用tokenizer的tokenize方法，按与构建时相同的分词方式将输入的单词过滤出来
+                for word in tokenizer.tokenize(&input) {
+                    // 对于每个单词，先按相同的归并方式归并，再调用bstree的get方法，以单词为键在BST中查找，如果找到了，那么打印该单词及其出现次数，如果没找到，那么打印未找到的信息
+                    let stem = cli::normalize(normalizer.as_deref(), &word);
+                    if let Some(stats) = bstree.get(stem.as_ref()) {
+                        match stats.forms_summary(&stem) {
+                            // 如果启用了归并，那么同时打印归并到该词干的各个原始形式及其出现次数
+                            Some(forms) => {
+                                println!("{}出现次数: {} ({})", stem, stats.count(), forms)
+                            }
+                            None => println!("{}出现次数: {}", word, stats.count()),
+                        }
+                        // 如果记录了大小写写法，那么打印各种写法及其出现次数
+                        if let Some(variants) = stats.variants_summary(&stem) {
+                            println!("  大小写写法: {}", variants);
+                        }
+                        // 如果记录了出现位置，那么打印每次出现的位置及其前后的原文
+                        if !stats.positions().is_empty() {
+                            println!("  出现位置(前后{KWIC_WIDTH}个词):");
+                            let limit = Some(KWIC_LIMIT);
+                            for line in kwic.lines(&meta, &*tokenizer, stats, KWIC_WIDTH, limit) {
+                                println!("{}", line);
+                            }
+                        }
                     } else {
-                        println!("{}: 不在bstree中.", word)
+                        println!("{}: 不在bstree中.", word);
+                        // 输出按字母序排在它前后的两个单词，便于发现拼写错误或归并后的写法
+                        if let Some(line) = cli::neighbours_line(&bstree, &stem) {
+                            println!("{}", line);
+                        }
+                        // 输出编辑距离相近的单词，作为"您是不是要找"的拼写建议
+                        if let Some(line) = cli::suggestions_line(
+                            &bstree,
+                            &stem,
+                            SUGGEST_DISTANCE,
+                            Metric::Damerau,
+                            SUGGEST_LIMIT,
+                        ) {
+                            println!("{}", line);
+                        }
                     }
                 }
             }
             3 => {
+                // 按前缀、字母区间或模式列出单词
+                // 创建一个选择框，用于让用户选择按前缀、字母区间、通配符模式还是正则表达式列出
+                let choices = [
+                    "按前缀(如inter)",
+                    "按字母区间(如从a到c)",
+                    "按通配符模式(如kill*、*ing、h?st?ge)",
+                    "按正则表达式(如^kill(ed|ing)$)",
+                ];
+                let selection = Select::with_theme(&theme)
+                    .with_prompt("选择列出单词的方式")
+                    .default(0)
+                    .items(&choices[..])
+                    .interact()
+                    .expect("无法读取输入");
+                let range = match selection {
+                    0 => {
+                        let prefix: String = Input::with_theme(&theme)
+                            .with_prompt("输入前缀")
+                            .interact_text()
+                            .expect("无法读取输入");
+                        cli::WordRange::Prefix(prefix)
+                    }
+                    1 => {
+                        // 起点和终点都包含在区间内，留空表示不限
+                        let [from, to] = ["起点", "终点"].map(|end| {
+                            let input: String = Input::with_theme(&theme)
+                                .with_prompt(format!("输入区间的{end}(留空表示不限)"))
+                                .allow_empty(true)
+                                .interact_text()
+                                .expect("无法读取输入");
+                            Some(input).filter(|input| !input.trim().is_empty())
+                        });
+                        cli::WordRange::Between(from, to)
+                    }
+                    2 => {
+                        // *匹配任意多个字符，?匹配一个字符，模式需要匹配整个单词
+                        let pattern: String = Input::with_theme(&theme)
+                            .with_prompt("输入通配符模式(*匹配任意多个字符，?匹配一个字符)")
+                            .interact_text()
+                            .expect("无法读取输入");
+                        cli::WordRange::Wildcard(pattern)
+                    }
+                    _ => {
+                        // 正则表达式无效时打印错误信息，回到菜单
+                        let pattern: String = Input::with_theme(&theme)
+                            .with_prompt("输入正则表达式(以^开头时按其后的字面前缀缩小查找范围)")
+                            .interact_text()
+                            .expect("无法读取输入");
+                        match Pattern::regex(&pattern) {
+                            Ok(pattern) => cli::WordRange::Regex(pattern),
+                            Err(e) => {
+                                println!("无效的正则表达式: {e}");
+                                continue;
+                            }
+                        }
+                    }
+                };
+                // 调用range_words函数，按与构建时相同的分词方式转换前缀、端点或通配符模式，再沿BST定位区间的两端，按字母序输出区间内匹配的单词及其出现次数
+                let matched = cli::range_words(&bstree, &*tokenizer, &range);
+                for word in &matched {
+                    println!("{}", word);
+                }
+                match matched.len() {
+                    0 => println!("没有符合条件的单词."),
+                    count => println!("共{count}个单词."),
+                }
+            }
+            4 => {
+                // 追加文本到当前的BST，用于在已加载的缓存上累积多篇文档的计数
+                // 如果缓存没有记录可识别的分词设置，那么提示用户计数可能不一致
+                if meta.tokenizer != tokenizer.name() {
+                    println!(
+                        "注意: 缓存的分词设置为{}，将按{}追加，计数可能不一致.",
+                        meta.tokenizer,
+                        tokenizer.name()
+                    );
+                }
+                // 如果缓存记录的归并方式无法重新创建，那么提示用户追加的单词不会被归并
+                if meta.normalizer.as_deref() != normalizer.as_ref().map(|n| n.name()) {
+                    println!("注意: 追加的单词不会被归并，计数可能不一致.");
+                }
+                // 创建一个选择框，用于让用户选择追加的方式
+                let choices = ["输入文本", "输入路径"];
+                let selection = Select::with_theme(&theme)
+                    .with_prompt("选择追加英文语句方式")
+                    .default(0)
+                    .items(&choices[..])
+                    .interact()
+                    .expect("无法读取输入");
+                if selection == 0 {
+                    // 如果用户选择输入文本，那么调用append_text函数，将输入的文本中的单词追加到BST中
+                    let input: String = Input::with_theme(&theme)
+                        .with_prompt("输入文本内容")
+                        .interact_text()
+                        .expect("无法读取输入");
+                    let tokens = append_text(
+                        &mut bstree,
+                        &mut meta,
+                        &*tokenizer,
+                        normalizer.as_deref(),
+                        "<input>",
+                        &input,
+                    );
+                    println!("已追加{tokens}个单词.");
+                    // 直接输入的文本没有保存为文件，如果记录了位置，那么把它加入上下文索引
+                    if meta.positions {
+                        let doc = meta.sources.len() as u32 - 1;
+                        kwic.add_text(doc, input, &*tokenizer);
+                    }
+                } else {
+                    // 如果用户选择输入路径，那么调用append_file函数，读取文件并将其中的单词追加到BST中，如果读取失败，那么打印错误信息
+                    let path: String = Input::with_theme(&theme)
+                        .with_prompt("输入纯文本文件路径")
+                        .interact_text()
+                        .expect("无法读取输入");
+                    match append_file(
+                        &mut bstree,
+                        &mut meta,
+                        &*tokenizer,
+                        normalizer.as_deref(),
+                        &path,
+                    ) {
+                        Ok(tokens) => println!("已从{path}追加{tokens}个单词."),
+                        Err(e) => println!("读取{path}失败: {e}"),
+                    }
+                }
+            }
+            5 => {
                 // 保存为缓存
-                // 调用serde_json库中的to_string函数，将bstree序列化为JSON格式的字符串，并赋值给cache变量，如果序列化失败，那么panic结束程序，并打印错误信息
-                let cache = serde_json::to_string(&bstree).unwrap();
+                // 创建一个选择框，用于让用户选择缓存格式，紧凑格式体积小且加载后为平衡树，树形格式保留当前树的形状，二进制格式读写最快，适合很大的词表
+                let formats = [
+                    "紧凑格式(按单词排序的[单词, 次数]数组)",
+                    "树形格式(保留树的形状)",
+                    "二进制格式(体积最小，读写最快)",
+                ];
+                let format = Select::with_theme(&theme)
+                    .with_prompt("请选择缓存格式")
+                    .default(0)
+                    .items(&formats[..])
+                    .interact()
+                    .expect("无法读取输入");
                 // 创建一个输入框，用于让用户输入要保存的文件名，不包含扩展名
                 let mut input: String = Input::with_theme(&theme)
                     .with_prompt("请输入要保存为的文件名(不含拓展名).")
                     .interact_text()
                     .expect("无法读取输入");
-                // 如果输入的文件名不以.json结尾，那么在文件名后面加上.json
-                if !input.ends_with(".json") {
-                    input += ".json";
+                if format == 2 {
+                    // 如果输入的文件名不以.bin结尾，那么在文件名后面加上.bin
+                    if !input.ends_with(".bin") {
+                        input += ".bin";
+                    }
+                    // 调用save_binary_cache函数，将bstree连同文件头以二进制格式逐个单词写入到指定的文件中，如果写入失败，那么panic结束程序，并打印错误信息
+                    save_binary_cache(input, &bstree, &meta).unwrap();
+                } else {
+                    let format = if format == 0 {
+                        CacheFormat::Compact
+                    } else {
+                        CacheFormat::Tree
+                    };
+                    // 调用cache_to_json函数，将bstree按所选格式序列化，连同版本、来源、分词设置和校验和等文件头一起组成JSON字符串，并赋值给cache变量，如果序列化失败，那么panic结束程序，并打印错误信息
+                    let cache = cache_to_json(&bstree, &meta, format).unwrap();
+                    // 如果输入的文件名不以.json结尾，那么在文件名后面加上.json
+                    if !input.ends_with(".json") {
+                        input += ".json";
+                    }
+                    // 调用write_str_to_file函数，将cache变量中的字符串写入到指定的文件中，如果写入失败，那么panic结束程序，并打印错误信息
+                    write_str_to_file(input, &cache).unwrap();
                 }
-                // 调用write_str_to_file函数，将cache变量中的字符串写入到指定的文件中，如果写入失败，那么panic结束程序，并打印错误信息
-                write_str_to_file(input, &cache).unwrap();
             }
-            4 => {
+            6 => {
                 // 退出程序
-                // 调用process模块中的exit函数，传入0作为参数，表示正常退出程序
-                process::exit(0);
+                // 返回成功的退出码，表示正常退出程序
+                return ExitCode::SUCCESS;
             }
             _ => {
                 // 不应该发生，但如果发生了，提示用户重新输入
-                println!("无效的选择,请重新输入(0-3)");
+                println!("无效的选择,请重新输入(0-6)");
             }
         }
     }
 }
+
+// 创建一个选择框，让用户选择分词方式，返回相应的分词器
+fn choose_tokenizer(theme: &ColorfulTheme) -> Box<dyn Tokenizer> {
+    let choices = [
+        "ASCII(只保留英文字母)",
+        "Unicode(按UAX #29单词边界，保留各种文字)",
+        "Unicode大小写折叠(如ß与ss视为相同)",
+        "空白(只按空白切分，保留标点)",
+        "正则表达式(每个匹配是一个单词)",
+        "中文词典分词(正向最大匹配，英文按Unicode切分)",
+    ];
+    let selection = Select::with_theme(theme)
+        .with_prompt("选择分词方式")
+        .default(0)
+        .items(&choices[..])
+        .interact()
+        .expect("无法读取输入");
+    match selection {
+        0 => Box::new(
+            AsciiTokenizer::new().with_rules(choose_rules(theme, AsciiTokenizer::DEFAULT_RULES)),
+        ),
+        1 => Box::new(
+            UnicodeTokenizer::new()
+                .with_rules(choose_rules(theme, UnicodeTokenizer::DEFAULT_RULES)),
+        ),
+        2 => Box::new(
+            UnicodeTokenizer::folding()
+                .with_rules(choose_rules(theme, UnicodeTokenizer::DEFAULT_RULES)),
+        ),
+        3 => Box::new(WhitespaceTokenizer),
+        4 => loop {
+            // 如果正则表达式无效，那么打印错误信息，并让用户重新输入
+            let pattern: String = Input::with_theme(theme)
+                .with_prompt("输入正则表达式")
+                .interact_text()
+                .expect("无法读取输入");
+            match RegexTokenizer::new(&pattern) {
+                Ok(tokenizer) => break Box::new(tokenizer),
+                Err(err) => println!("无效的正则表达式: {}", err),
+            }
+        },
+        _ => Box::new(choose_dictionary(theme, None)),
+    }
+}
+
+// 让用户选择缩写、撇号和连字符的处理规则，不调整时返回默认规则
+fn choose_rules(theme: &ColorfulTheme, default: WordRules) -> WordRules {
+    let adjust = Confirm::with_theme(theme)
+        .with_prompt("是否调整缩写(如don't)、撇号、连字符(如three-hour)、数字和符号的处理方式?")
+        .default(false)
+        .interact()
+        .expect("无法读取输入");
+    if !adjust {
+        return default;
+    }
+    let contractions = [
+        Contractions::Split,
+        Contractions::Keep,
+        Contractions::Expand,
+    ];
+    let selection = Select::with_theme(theme)
+        .with_prompt("缩写的处理方式")
+        .default(
+            contractions
+                .iter()
+                .position(|c| *c == default.contractions)
+                .unwrap_or(0),
+        )
+        .items(&["拆开(don + t)", "保留(don't)", "展开(do + not)"])
+        .interact()
+        .expect("无法读取输入");
+    let contractions = contractions[selection];
+    let hyphens = Select::with_theme(theme)
+        .with_prompt("连字符复合词的处理方式")
+        .default(0)
+        .items(&["拆开(three + hour)", "保留(three-hour)"])
+        .interact()
+        .expect("无法读取输入");
+    let normalize_apostrophes = Confirm::with_theme(theme)
+        .with_prompt("是否将弯撇号’统一为'?")
+        .default(true)
+        .interact()
+        .expect("无法读取输入");
+    let numbers = [Numbers::Drop, Numbers::Keep, Numbers::Bucket];
+    let selection = Select::with_theme(theme)
+        .with_prompt("数字的处理方式")
+        .default(0)
+        .items(&["丢弃", "保留(如2023、x86)", "纯数字统一计为<NUM>"])
+        .interact()
+        .expect("无法读取输入");
+    let numbers = numbers[selection];
+    // 创建一个多选框，用于让用户选择允许出现在单词中的符号
+    let selections = MultiSelect::with_theme(theme)
+        .with_prompt("允许出现在单词中的符号(空格选择，回车确认)")
+        .items(&[
+            "下划线(x86_64)",
+            "点号(node.js)",
+            "@(admin@example)",
+            "斜杠(and/or)",
+            "末尾的+和#(c++、c#)",
+        ])
+        .interact()
+        .expect("无法读取输入");
+    let symbols = selections
+        .into_iter()
+        .fold(Symbols::NONE, |symbols, i| symbols | Symbols::CLASSES[i].0);
+    WordRules {
+        contractions,
+        hyphens: if hyphens == 0 {
+            Hyphens::Split
+        } else {
+            Hyphens::Keep
+        },
+        normalize_apostrophes,
+        numbers,
+        symbols,
+    }
+}
+
+// 创建一个输入框，让用户选择是否在内置词典的基础上追加用户词典，返回中文分词器
+// 如果指定了缓存记录的分词器名称，那么词典必须与统计时使用的一致
+fn choose_dictionary(theme: &ColorfulTheme, expected: Option<&str>) -> Segmenter {
+    loop {
+        let mut segmenter = Segmenter::bundled();
+        let path: String = Input::with_theme(theme)
+            .with_prompt("输入用户词典路径(每行一个词)，留空则只使用内置词典")
+            .allow_empty(true)
+            .interact_text()
+            .expect("无法读取输入");
+        if !path.is_empty() {
+            match segmenter.load_file(&path) {
+                Ok(count) => println!(
+                    "已从{}追加{}个词语，词典共{}个词语.",
+                    path,
+                    count,
+                    segmenter.len()
+                ),
+                Err(err) => {
+                    println!("无法读取词典: {}", err);
+                    continue;
+                }
+            }
+        }
+        match expected {
+            Some(name) if name != segmenter.name() => {
+                println!(
+                    "词典与统计时使用的不一致(缓存记录{}，当前{})，请重新输入.",
+                    name,
+                    segmenter.name()
+                );
+            }
+            _ => return segmenter,
+        }
+    }
+}
+
+// 创建一个选择框，让用户选择是否把单词的不同形式归并为同一个单词，返回相应的归并方式
+fn choose_normalizer(theme: &ColorfulTheme) -> Option<Box<dyn Normalizer>> {
+    let choices = [
+        "不归并",
+        "Porter词干提取(killed、killing计为kill)",
+        "词形还原(使用词典文件，如went计为go)",
+    ];
+    let selection = Select::with_theme(theme)
+        .with_prompt("选择单词形式的归并方式")
+        .default(0)
+        .items(&choices[..])
+        .interact()
+        .expect("无法读取输入");
+    match selection {
+        0 => None,
+        1 => Some(Box::new(PorterStemmer)),
+        _ => loop {
+            // 如果词典无法读取，那么打印错误信息，并让用户重新输入
+            let path: String = Input::with_theme(theme)
+                .with_prompt("输入词形还原词典路径(每行一个词元及其各种形式)")
+                .interact_text()
+                .expect("无法读取输入");
+            match Lemmatizer::from_file(&path) {
+                Ok(lemmatizer) => {
+                    println!("已从{}读入{}个词形.", path, lemmatizer.len());
+                    break Some(Box::new(lemmatizer));
+                }
+                Err(err) => println!("无法读取词典: {}", err),
+            }
+        },
+    }
+}
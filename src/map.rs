@@ -0,0 +1,651 @@
+//! # 二叉搜索树映射
+//!
+//! 该模块定义了以二叉搜索树存储键值对的映射 `BSTMap<K, V>`。树中的结点数据为 `KeyValue<K, V>`，
+//! 只按键比较大小，因此值（如单词的出现次数、原始形式）不再参与排序，可以随意修改。
+//!
+//! ## BSTMap 结构体实现的方法
+//!
+//! - `new() -> BSTMap<K, V>`: 创建一个使用 AVL 平衡的空映射；`with_balance(balance)` 指定平衡策略。
+//! - `from_sorted(entries: Vec<(K, V)>, balance: Balance)`: 由按键严格递增的键值对构建完全平衡的映射。
+//! - `insert(key, value) -> Option<V>`: 插入键值对，键已存在时替换并返回原来的值。
+//! - `get(&key) -> Option<&V>` / `get_mut(&key) -> Option<&mut V>`: 查找键对应的值。
+//! - `get_key_value(&key) -> Option<&KeyValue<K, V>>`: 查找键对应的键值对。
+//! - `contains_key(&key) -> bool`: 判断键是否存在。
+//! - `remove(&key) -> Option<V>`: 删除键值对，返回被删除的值。
+//! - `entry(key) -> Entry`: 获取键对应的条目，可以用 `or_insert`、`or_default`、`and_modify` 等方法就地修改或插入。
+//! - `len()` / `is_empty()` / `height()`: 键值对个数（O(1)）、是否为空和树高。
+//! - `rank(&key) -> usize` / `select(k) -> Option<(&K, &V)>` / `count_range(range) -> usize`: 顺序统计，
+//!   分别为小于 `key` 的键的个数、第 `k` 小的键值对和键落在区间中的键值对个数，见 `BSTree` 的同名方法。
+//! - `range(range) -> Range`: 按键从小到大遍历键落在区间中的键值对；键为 `String` 时，`prefix(prefix) -> Range` 遍历键以 `prefix` 开头的键值对。
+//! - `iter()` / `iter_mut()` / `keys()` / `values()`: 按键从小到大遍历的迭代器。
+//! - `tree() -> &BSTree<KeyValue<K, V>>`: 获取底层的二叉搜索树，用于按前序、层序等顺序遍历或保存树的形状。
+//!
+//! 查找、删除时的参数可以是键的借用形式，例如 `BSTMap<String, V>` 可以直接用 `&str` 查找。
+//!
+//! ## 示例
+//!
+//! ```rust
+//! use bst_words::map::BSTMap;
+//! let mut counts: BSTMap<String, u32> = BSTMap::new();
+//! for word in ["pear", "apple", "pear"] {
+//!     counts
+//!         .entry(word.to_string())
+//!         .and_modify(|count| *count += 1)
+//!         .or_insert(1);
+//! }
+//! assert_eq!(counts.get("pear"), Some(&2));
+//! assert!(counts.contains_key("apple"));
+//! assert_eq!(counts.remove("apple"), Some(1));
+//! let pairs: Vec<(&String, &u32)> = counts.iter().collect();
+//! assert_eq!(pairs, [(&"pear".to_string(), &2)]);
+//! ```
+// 引入bst模块中的BSTree结构体、迭代器和下降方向，BSTMap以BSTree存储键值对，条目记下查找时下降的路径
+use crate::bst::{self, BSTree, Dir};
+// 引入r#type模块中的Balance枚举类型，表示树的平衡策略
+use crate::r#type::Balance;
+// 引入serde库中的Deserialize和Serialize两个trait，使键值对可以序列化
+use serde::{Deserialize, Serialize};
+// 引入std库中的Borrow trait、Ordering枚举类型和RangeBounds trait，用于以借用形式的键查找、比较键的大小和表示键的区间
+use std::{borrow::Borrow, cmp::Ordering, ops::RangeBounds};
+
+/// 映射中的一个键值对，只按键比较大小和判断相等。
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct KeyValue<K, V> {
+    /// 键，决定键值对在树中的位置，不能修改
+    pub key: K,
+    /// 值
+    pub value: V,
+}
+
+impl<K: Ord, V> PartialEq for KeyValue<K, V> {
+    fn eq(&self, other: &Self) -> bool {
+        self.key == other.key
+    }
+}
+
+impl<K: Ord, V> Eq for KeyValue<K, V> {}
+
+impl<K: Ord, V> PartialOrd for KeyValue<K, V> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<K: Ord, V> Ord for KeyValue<K, V> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.key.cmp(&other.key)
+    }
+}
+
+/// 以二叉搜索树存储键值对的有序映射。
+#[derive(Debug, Clone)]
+pub struct BSTMap<K, V> {
+    tree: BSTree<KeyValue<K, V>>,
+}
+
+impl<K, V> Default for BSTMap<K, V> {
+    fn default() -> Self {
+        BSTMap::with_balance(Balance::Avl)
+    }
+}
+
+impl<K, V> BSTMap<K, V> {
+    /// 创建一个使用 AVL 平衡的空映射。
+    pub fn new() -> BSTMap<K, V> {
+        BSTMap::default()
+    }
+
+    /// 创建一个使用指定平衡策略的空映射。
+    pub fn with_balance(balance: Balance) -> BSTMap<K, V> {
+        BSTMap {
+            tree: BSTree {
+                root: None,
+                balance,
+            },
+        }
+    }
+
+    /// 由二叉搜索树创建映射，树中的键值对已按键排列。
+    pub fn from_tree(tree: BSTree<KeyValue<K, V>>) -> BSTMap<K, V> {
+        BSTMap { tree }
+    }
+
+    /// 获取底层的二叉搜索树，可用于按各种顺序遍历或保存树的形状。
+    pub fn tree(&self) -> &BSTree<KeyValue<K, V>> {
+        &self.tree
+    }
+
+    /// 取出底层的二叉搜索树。
+    pub fn into_tree(self) -> BSTree<KeyValue<K, V>> {
+        self.tree
+    }
+
+    /// 获取键值对的个数。
+    pub fn len(&self) -> usize {
+        self.tree.len()
+    }
+
+    /// 判断映射是否为空。
+    pub fn is_empty(&self) -> bool {
+        self.tree.is_empty()
+    }
+
+    /// 获取树的高度，空映射高度为 0。
+    pub fn height(&self) -> usize {
+        self.tree.root.as_ref().map_or(0, |n| n.height)
+    }
+
+    /// 获取按键从小到大的第 `k` 个键值对（从 0 开始），`k` 超出范围时返回 `None`。
+    pub fn select(&self, k: usize) -> Option<(&K, &V)> {
+        self.tree.select(k).map(|kv| (&kv.key, &kv.value))
+    }
+
+    /// 按键从小到大遍历键值对的双端迭代器。
+    pub fn iter(&self) -> Iter<'_, K, V> {
+        Iter(self.tree.iter())
+    }
+
+    /// 按键从小到大遍历键值对的迭代器，值可以修改。
+    pub fn iter_mut(&mut self) -> IterMut<'_, K, V> {
+        IterMut(self.tree.iter_mut())
+    }
+
+    /// 按从小到大的顺序遍历键的迭代器。
+    pub fn keys(&self) -> impl DoubleEndedIterator<Item = &K> {
+        self.iter().map(|(key, _)| key)
+    }
+
+    /// 按键从小到大的顺序遍历值的迭代器。
+    pub fn values(&self) -> impl DoubleEndedIterator<Item = &V> {
+        self.iter().map(|(_, value)| value)
+    }
+}
+
+impl<K: Ord, V> BSTMap<K, V> {
+    /// 由按键严格递增的键值对构建一个完全平衡的映射，键乱序或重复时返回错误。
+    pub fn from_sorted(entries: Vec<(K, V)>, balance: Balance) -> Result<BSTMap<K, V>, String> {
+        let entries = entries
+            .into_iter()
+            .map(|(key, value)| KeyValue { key, value })
+            .collect();
+        Ok(BSTMap {
+            tree: BSTree::from_sorted(entries, balance)?,
+        })
+    }
+
+    /// 插入键值对，键已存在时替换为新的值并返回原来的值。
+    pub fn insert(&mut self, key: K, value: V) -> Option<V> {
+        let mut old = None;
+        self.tree
+            .insert_with(KeyValue { key, value }, KeyValue::cmp, |node, new| {
+                old = Some(std::mem::replace(&mut node.data.value, new.value));
+            });
+        old
+    }
+
+    /// 查找键对应的值。
+    pub fn get<Q>(&self, key: &Q) -> Option<&V>
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        self.get_key_value(key).map(|kv| &kv.value)
+    }
+
+    /// 查找键对应的键值对，可用于同时输出键和值。
+    pub fn get_key_value<Q>(&self, key: &Q) -> Option<&KeyValue<K, V>>
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        self.tree
+            .search(|kv| key.cmp(kv.key.borrow()))
+            .map(|node| &node.data)
+    }
+
+    /// 查找键对应的值，返回可变引用。
+    pub fn get_mut<Q>(&mut self, key: &Q) -> Option<&mut V>
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        self.tree
+            .search_mut(|kv| key.cmp(kv.key.borrow()))
+            .map(|node| &mut node.data.value)
+    }
+
+    /// 判断键是否存在。
+    pub fn contains_key<Q>(&self, key: &Q) -> bool
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        self.get(key).is_some()
+    }
+
+    /// 删除键值对，返回被删除的值，键不存在时返回 `None`。
+    pub fn remove<Q>(&mut self, key: &Q) -> Option<V>
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        self.tree
+            .remove_with(|kv| key.cmp(kv.key.borrow()))
+            .map(|kv| kv.value)
+    }
+
+    /// 获取小于 `key` 的键的个数，即 `key` 按顺序的排名（从 0 开始），`key` 不必在映射中。
+    pub fn rank<Q>(&self, key: &Q) -> usize
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        self.tree
+            .count_below_with(|kv| key.cmp(kv.key.borrow()), false)
+    }
+
+    /// 获取键落在 `range` 中的键值对个数。
+    pub fn count_range<Q, R>(&self, range: R) -> usize
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+        R: RangeBounds<Q>,
+    {
+        self.tree
+            .count_range_with(&range, |key: &Q, kv| key.cmp(kv.key.borrow()))
+    }
+
+    /// 按键从小到大遍历键落在 `range` 中的键值对的双端迭代器。
+    pub fn range<Q, R>(&self, range: R) -> Range<'_, K, V>
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+        R: RangeBounds<Q>,
+    {
+        Range(
+            self.tree
+                .range_with(range, |key: &Q, kv| key.cmp(kv.key.borrow())),
+        )
+    }
+
+    /// 获取键对应的条目，用于就地修改已有的值或插入新的值。
+    ///
+    /// 只比较一次查找路径上的键，并记下下降的路径；之后读写和插入都沿路径定位，不再比较键。
+    pub fn entry(&mut self, key: K) -> Entry<'_, K, V> {
+        match self.tree.locate(|kv| key.cmp(&kv.key)) {
+            Ok(path) => Entry::Occupied(OccupiedEntry {
+                map: self,
+                key,
+                path,
+            }),
+            Err(path) => Entry::Vacant(VacantEntry {
+                map: self,
+                key,
+                path,
+            }),
+        }
+    }
+}
+
+/// 映射中的一个条目，由 `BSTMap::entry` 创建。
+pub enum Entry<'a, K, V> {
+    /// 键已存在
+    Occupied(OccupiedEntry<'a, K, V>),
+    /// 键不存在
+    Vacant(VacantEntry<'a, K, V>),
+}
+
+/// 已存在的条目。
+pub struct OccupiedEntry<'a, K, V> {
+    map: &'a mut BSTMap<K, V>,
+    key: K,
+    // 从根到键所在结点的路径
+    path: Vec<Dir>,
+}
+
+/// 不存在的条目。
+pub struct VacantEntry<'a, K, V> {
+    map: &'a mut BSTMap<K, V>,
+    key: K,
+    // 从根到应插入的空位的路径
+    path: Vec<Dir>,
+}
+
+impl<'a, K: Ord, V> Entry<'a, K, V> {
+    /// 获取条目的键。
+    pub fn key(&self) -> &K {
+        match self {
+            Entry::Occupied(entry) => entry.key(),
+            Entry::Vacant(entry) => entry.key(),
+        }
+    }
+
+    /// 键不存在时插入 `default`，返回值的可变引用。
+    pub fn or_insert(self, default: V) -> &'a mut V {
+        self.or_insert_with(|| default)
+    }
+
+    /// 键不存在时插入 `default()` 的结果，返回值的可变引用。
+    pub fn or_insert_with(self, default: impl FnOnce() -> V) -> &'a mut V {
+        match self {
+            Entry::Occupied(entry) => entry.into_mut(),
+            Entry::Vacant(entry) => entry.insert(default()),
+        }
+    }
+
+    /// 键不存在时插入值的默认值，返回值的可变引用。
+    pub fn or_default(self) -> &'a mut V
+    where
+        V: Default,
+    {
+        self.or_insert_with(V::default)
+    }
+
+    /// 键已存在时用 `f` 修改值，返回条目本身，可以继续调用 `or_insert`。
+    pub fn and_modify(mut self, f: impl FnOnce(&mut V)) -> Self {
+        if let Entry::Occupied(entry) = &mut self {
+            f(entry.get_mut());
+        }
+        self
+    }
+}
+
+impl<'a, K: Ord, V> OccupiedEntry<'a, K, V> {
+    /// 获取条目的键。
+    pub fn key(&self) -> &K {
+        &self.key
+    }
+
+    /// 获取条目的值。
+    pub fn get(&self) -> &V {
+        // 条目只在键存在时创建，且期间映射被独占借用，因此路径一定有效
+        &self
+            .map
+            .tree
+            .get_at(&self.path)
+            .expect("occupied entry")
+            .value
+    }
+
+    /// 获取条目的值的可变引用。
+    pub fn get_mut(&mut self) -> &mut V {
+        &mut self
+            .map
+            .tree
+            .get_at_mut(&self.path)
+            .expect("occupied entry")
+            .value
+    }
+
+    /// 转换为与映射生命周期相同的可变引用。
+    pub fn into_mut(self) -> &'a mut V {
+        &mut self
+            .map
+            .tree
+            .get_at_mut(&self.path)
+            .expect("occupied entry")
+            .value
+    }
+
+    /// 替换条目的值，返回原来的值。
+    pub fn insert(&mut self, value: V) -> V {
+        std::mem::replace(self.get_mut(), value)
+    }
+
+    /// 删除条目，返回它的值。
+    pub fn remove(self) -> V {
+        self.map.remove(&self.key).expect("occupied entry")
+    }
+}
+
+impl<'a, K: Ord, V> VacantEntry<'a, K, V> {
+    /// 获取条目的键。
+    pub fn key(&self) -> &K {
+        &self.key
+    }
+
+    /// 取回条目的键。
+    pub fn into_key(self) -> K {
+        self.key
+    }
+
+    /// 插入值，返回值的可变引用。
+    ///
+    /// AVL 旋转会移动结点，插入时随旋转更新新结点的路径，再沿路径取回值，不需要再比较键。
+    pub fn insert(self, value: V) -> &'a mut V {
+        let kv = KeyValue {
+            key: self.key,
+            value,
+        };
+        let path = self.map.tree.insert_at(&self.path, kv);
+        &mut self
+            .map
+            .tree
+            .get_at_mut(&path)
+            .expect("inserted entry")
+            .value
+    }
+}
+
+impl<V> BSTMap<String, V> {
+    /// 按键从小到大遍历键以 `prefix` 开头的键值对。
+    pub fn prefix(&self, prefix: &str) -> Range<'_, String, V> {
+        Range(self.tree.prefix(prefix))
+    }
+}
+
+/// 以字符串为键的键值对可以用 `&str` 借用，因此底层的树也可以直接用 `&str` 查找和按前缀遍历。
+impl<V> Borrow<str> for KeyValue<String, V> {
+    fn borrow(&self) -> &str {
+        &self.key
+    }
+}
+
+/// 按键从小到大遍历区间内键值对的双端迭代器，由 `BSTMap::range` 和 `BSTMap::prefix` 创建。
+pub struct Range<'a, K, V>(bst::Range<'a, KeyValue<K, V>>);
+
+impl<'a, K, V> Iterator for Range<'a, K, V> {
+    type Item = (&'a K, &'a V);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.0.next().map(|kv| (&kv.key, &kv.value))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.0.size_hint()
+    }
+}
+
+impl<K, V> DoubleEndedIterator for Range<'_, K, V> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.0.next_back().map(|kv| (&kv.key, &kv.value))
+    }
+}
+
+impl<K, V> ExactSizeIterator for Range<'_, K, V> {}
+
+/// 按键从小到大遍历键值对的双端迭代器，由 `BSTMap::iter` 创建。
+pub struct Iter<'a, K, V>(bst::Iter<'a, KeyValue<K, V>>);
+
+impl<'a, K, V> Iterator for Iter<'a, K, V> {
+    type Item = (&'a K, &'a V);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.0.next().map(|kv| (&kv.key, &kv.value))
+    }
+}
+
+impl<K, V> DoubleEndedIterator for Iter<'_, K, V> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.0.next_back().map(|kv| (&kv.key, &kv.value))
+    }
+}
+
+/// 按键从小到大遍历键值对的迭代器，值可以修改，由 `BSTMap::iter_mut` 创建。
+pub struct IterMut<'a, K, V>(bst::IterMut<'a, KeyValue<K, V>>);
+
+impl<'a, K, V> Iterator for IterMut<'a, K, V> {
+    type Item = (&'a K, &'a mut V);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.0.next().map(|kv| (&kv.key, &mut kv.value))
+    }
+}
+
+impl<'a, K, V> IntoIterator for &'a BSTMap<K, V> {
+    type Item = (&'a K, &'a V);
+    type IntoIter = Iter<'a, K, V>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<K, V> IntoIterator for BSTMap<K, V> {
+    type Item = (K, V);
+    type IntoIter = std::iter::Map<bst::IntoIter<KeyValue<K, V>>, fn(KeyValue<K, V>) -> (K, V)>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.tree.into_iter().map(|kv| (kv.key, kv.value))
+    }
+}
+
+impl<K: Ord, V> FromIterator<(K, V)> for BSTMap<K, V> {
+    /// 逐个插入键值对，重复的键保留最后一次出现的值。
+    fn from_iter<I: IntoIterator<Item = (K, V)>>(iter: I) -> Self {
+        let mut map = BSTMap::new();
+        for (key, value) in iter {
+            map.insert(key, value);
+        }
+        map
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::ops::Bound;
+
+    #[test]
+    fn test_map() {
+        let mut map: BSTMap<String, u32> = BSTMap::new();
+        assert!(map.is_empty());
+        for (i, key) in ["d", "b", "a", "c", "e", "f", "g"].into_iter().enumerate() {
+            assert_eq!(map.insert(key.to_string(), i as u32), None);
+        }
+        assert_eq!(map.len(), 7);
+        assert_eq!(map.height(), 3);
+        assert_eq!(map.insert("a".to_string(), 10), Some(2));
+        assert_eq!(map.get("a"), Some(&10));
+        assert_eq!(map.get("z"), None);
+        *map.get_mut("b").unwrap() += 5;
+        assert_eq!(map.get("b"), Some(&6));
+        assert!(map.contains_key("g") && !map.contains_key("h"));
+
+        assert_eq!(map.remove("d"), Some(0));
+        assert_eq!(map.remove("d"), None);
+        let keys: Vec<&str> = map.keys().map(String::as_str).collect();
+        assert_eq!(keys, ["a", "b", "c", "e", "f", "g"]);
+        assert_eq!(map.len(), 6);
+        assert_eq!(map.rank("d"), 3);
+        assert_eq!(map.rank("e"), 3);
+        assert_eq!(map.select(3), Some((&"e".to_string(), &4)));
+        assert_eq!(map.select(6), None);
+        assert_eq!(
+            map.count_range::<str, _>((Bound::Included("b"), Bound::Excluded("f"))),
+            3
+        );
+        assert_eq!(map.count_range("c".to_string()..), 4);
+        let keys: Vec<&String> = map
+            .range::<str, _>((Bound::Excluded("b"), Bound::Unbounded))
+            .map(|(k, _)| k)
+            .collect();
+        assert_eq!(keys, ["c", "e", "f", "g"]);
+        let values: Vec<&u32> = map
+            .range("b".to_string()..="c".to_string())
+            .map(|(_, v)| v)
+            .collect();
+        assert_eq!(values, [&6, &3]);
+        map.insert("ga".to_string(), 1);
+        assert_eq!(
+            map.prefix("g").map(|(k, _)| k.as_str()).collect::<Vec<_>>(),
+            ["g", "ga"]
+        );
+        assert_eq!(map.remove("ga"), Some(1));
+        assert_eq!(map.values().next_back(), Some(&6));
+        for (_, value) in map.iter_mut() {
+            *value *= 2;
+        }
+        assert_eq!(map.get("a"), Some(&20));
+
+        let sorted = BSTMap::from_sorted(vec![(1, 'a'), (2, 'b'), (3, 'c')], Balance::Avl).unwrap();
+        assert_eq!(sorted.height(), 2);
+        assert!(BSTMap::from_sorted(vec![(2, 'b'), (1, 'a')], Balance::Avl).is_err());
+        let collected: BSTMap<i32, char> = [(3, 'c'), (1, 'a'), (3, 'z')].into_iter().collect();
+        assert_eq!(
+            collected.into_iter().collect::<Vec<_>>(),
+            [(1, 'a'), (3, 'z')]
+        );
+    }
+
+    #[test]
+    fn test_entry() {
+        let mut map: BSTMap<String, Vec<usize>> = BSTMap::new();
+        let text = "the cat saw the dog and the cat";
+        for (i, word) in text.split(' ').enumerate() {
+            map.entry(word.to_string()).or_default().push(i);
+        }
+        assert_eq!(map.get("the"), Some(&vec![0, 3, 6]));
+        assert_eq!(map.get("dog"), Some(&vec![4]));
+
+        let mut counts: BSTMap<&str, u32> = BSTMap::new();
+        for word in text.split(' ') {
+            counts.entry(word).and_modify(|c| *c += 1).or_insert(1);
+        }
+        assert_eq!(counts.get("the"), Some(&3));
+        assert_eq!(counts.entry("cat").key(), &"cat");
+
+        match counts.entry("saw") {
+            Entry::Occupied(mut entry) => {
+                assert_eq!(entry.insert(7), 1);
+                assert_eq!(*entry.get(), 7);
+                assert_eq!(entry.remove(), 7);
+            }
+            Entry::Vacant(_) => unreachable!(),
+        }
+        match counts.entry("bird") {
+            Entry::Vacant(entry) => *entry.insert(1) += 1,
+            Entry::Occupied(_) => unreachable!(),
+        }
+        assert_eq!(counts.get("bird"), Some(&2));
+        assert!(!counts.contains_key("saw"));
+
+        // 键不需要实现 Clone；AVL 旋转移动结点后，沿更新后的路径仍能取回刚插入的值
+        struct Key(u32);
+        impl PartialEq for Key {
+            fn eq(&self, other: &Self) -> bool {
+                self.0 == other.0
+            }
+        }
+        impl Eq for Key {}
+        impl PartialOrd for Key {
+            fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+                Some(self.cmp(other))
+            }
+        }
+        impl Ord for Key {
+            fn cmp(&self, other: &Self) -> Ordering {
+                self.0.cmp(&other.0)
+            }
+        }
+        let mut map: BSTMap<Key, u32> = BSTMap::with_balance(Balance::Avl);
+        for i in (0..200).chain((0..200).rev()).chain([50, 150]) {
+            *map.entry(Key(i % 100 * 7 % 100)).or_insert(i) += 1000;
+        }
+        assert_eq!(map.len(), 100);
+        let keys: Vec<u32> = map.keys().map(|key| key.0).collect();
+        assert_eq!(keys, (0..100).collect::<Vec<_>>());
+        assert_eq!(map.get(&Key(0)), Some(&4000));
+        assert_eq!(map.get(&Key(50)), Some(&6050));
+    }
+}
@@ -9,7 +9,7 @@
 //! ### 使用示例
 //!
 //! ```rust
-//! use crate::word_order::{Word, Order};
+//! use bst_words::r#type::{Word, Order};
 //! let word1 = Word::new("apple".to_string());
 //! let word2 = Word::new("apples".to_string());
 //! assert_eq!(word1.cmp(&word2), std::cmp::Ordering::Less);
@@ -19,15 +19,60 @@
 //!
 //! ## 遍历顺序枚举 Order
 //!
-//! 用于表示二叉树遍历的顺序，包括前序、中序和后序遍历。
+//! 用于表示二叉树遍历的顺序，包括前序、中序、后序、层序和逆中序遍历。
 //!
 //! ### 使用示例
 //!
 //! ```rust
-//! use crate::word_order::{Word, Order};
+//! use bst_words::r#type::{Word, Order};
 //! let order = Order::Pre;
 //! ```
 //!
+//! ## 平衡策略枚举 Balance
+//!
+//! 用于表示二叉搜索树的平衡策略，`Balance::None` 不做平衡，`Balance::Avl` 使用 AVL 旋转保持平衡。
+//!
+//! ### 使用示例
+//!
+//! ```rust
+//! use bst_words::bst::BSTree;
+//! use bst_words::r#type::Balance;
+//! let mut bstree = BSTree::with_balance(Balance::Avl);
+//! bstree.insert_fn(1, |_| {});
+//! ```
+//!
+//! ## 出现位置 Position
+//!
+//! 启用位置记录时，单词每次出现都会记录一个 `Position`，包括文档编号（缓存来源列表中的下标）、
+//! 从 1 开始的行号和列号（按字符计），以及在文档中的字节偏移和字节长度，`span()` 返回单词在原文中的字节范围。
+//!
+//! ```rust
+//! use bst_words::r#type::Position;
+//! let position = Position { doc: 0, line: 2, column: 5, offset: 17, len: 7 };
+//! assert_eq!(position.span(), 17..24);
+//! assert_eq!(position.to_string(), "#0:2:5");
+//! ```
+//!
+//! ## 单词统计信息 WordStats
+//!
+//! `WordStats` 保存单词的出现次数、原始形式、大小写写法和出现位置，不包含单词本身。单词计数使用
+//! `BSTMap<String, WordStats>`，以单词为键，统计信息为值；`KeyValue<String, WordStats>` 按 `value: count (forms)` 的格式输出。
+//! `Word` 为单词和统计信息的组合，`Word::from_parts` / `into_parts` 以及与 `KeyValue` 之间的 `From` 转换可以相互转换，
+//! 下面 `Word` 的统计方法都委托给 `WordStats` 中的同名方法（`forms_summary` 等需要额外传入单词）。
+//!
+//! ```rust
+//! use bst_words::map::BSTMap;
+//! use bst_words::r#type::WordStats;
+//! let mut words: BSTMap<String, WordStats> = BSTMap::new();
+//! for form in ["killed", "killing", "killed"] {
+//!     let stats = words.entry("kill".to_string()).or_default();
+//!     stats.add();
+//!     stats.add_form(form);
+//! }
+//! let kill = words.get_key_value("kill").unwrap();
+//! assert_eq!(kill.to_string(), "kill: 3 (killed 2, killing 1)");
+//! ```
+//!
 //! ## Word 结构体实现的 trait
 //!
 //! - `PartialOrd`: 部分比较，用于定义单词的大小关系。
@@ -35,12 +80,22 @@
 //! - `Ord`: 完全比较，用于排序。
 //! - `Display`: 格式化输出，用于打印到屏幕或写入到文件。
 //! - `Clone`: 克隆自身。
+//! - `Borrow<str>`: 以单词的值借用，`Ord` 与 `str` 的比较一致，因此 `BSTree<Word>` 可以直接用 `&str` 查找和删除。
 //!
 //! ## Word 结构体的方法
 //!
 //! - `new(value: String) -> Word`: 创建一个新的 Word 实例。
+//! - `with_count(value: String, count: u32) -> Word`: 创建一个指定出现次数的 Word 实例。
 //! - `add()`: 将单词的出现次数加一。
 //! - `count() -> u32`: 获取单词的出现次数。
+//! - `add_form(form: &str)`: 记录一次归并到该单词的原始形式（启用词干提取或词形还原时）。
+//! - `forms() -> &BTreeMap<String, u32>`: 获取归并到该单词的原始形式及其出现次数。
+//! - `add_variant(variant: &str)`: 记录一次单词在原文中的大小写写法（启用大小写记录时），如 "Israeli"、"ISRAELI"。
+//! - `variants() -> &BTreeMap<String, u32>`: 获取单词的各种大小写写法及其出现次数。
+//! - `preferred_variant() -> Option<&str>`: 获取出现次数最多的大小写写法，可用于识别专有名词和缩略词。
+//! - `add_position(position: Position)`: 记录一次出现位置（启用位置记录时）。
+//! - `positions() -> &[Position]`: 获取单词每次出现的位置，包括文档编号、行号、列号和字节偏移。
+//! - `merge(other: Word)`: 合并另一个相同单词的出现次数、原始形式、大小写写法和出现位置。
 //!
 //! ## 使用注意事项
 //!
@@ -50,7 +105,7 @@
 //! ## 示例
 //!
 //! ```rust
-//! use crate::word_order::{Word, Order};
+//! use bst_words::r#type::{Word, Order};
 //!
 //! // 创建两个单词
 //! let word1 = Word::new("apple".to_string());
@@ -66,49 +121,248 @@
 //! assert!(word3 == word4)
 //! ```
 //!
+//! 在单词树中查找时不需要构造 `Word`：
+//!
+//! ```rust
+//! use bst_words::bst::BSTree;
+//! use bst_words::r#type::Word;
+//! let mut bstree = BSTree::new();
+//! bstree.insert_fn(Word::new("apple".to_string()), |n| n.data.add());
+//! assert_eq!(bstree.find("apple").map(|n| n.data.count()), Some(1));
+//! bstree.delete("apple").unwrap();
+//! assert!(bstree.find("apple").is_none());
+//! ```
+//!
 //! 注意：以上示例中的单词值仅供参考，实际使用时应根据需求替换。
+// 引入map模块中的KeyValue结构体，单词计数映射中的键值对按单词的格式输出
+use crate::map::KeyValue;
 // 引入serde库中的Deserialize和Serialize两个trait，它们用于实现JSON的反序列化和序列化功能
 use serde::{Deserialize, Serialize};
-// 引入std库中的cmp模块，它用于实现比较大小的功能
-use std::{cmp::Ordering, fmt::Display};
+// 引入std库中的borrow模块和cmp模块，它们用于以借用形式查找和实现比较大小的功能，以及BTreeMap类型，它用于按顺序记录单词的原始形式
+use std::{borrow::Borrow, cmp::Ordering, collections::BTreeMap, fmt::Display, ops::Range};
 
-/// 表示二叉树的遍历顺序，包括前序、中序和后序遍历。
-#[derive(Clone)]
+/// 表示二叉树的遍历顺序，包括前序、中序、后序、层序和逆中序遍历。
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum Order {
     Pre,
     In,
     Post,
+    /// 层序遍历（广度优先），逐层从左到右访问
+    Level,
+    /// 逆中序遍历，即从大到小访问
+    ReverseIn,
 }
 
-/// 表示一个单词，包括单词的值和出现次数。
-#[derive(Serialize, Deserialize, Eq, Debug, Clone)]
-pub struct Word {
-    /// 单词的值
-    pub value: String,
+/// 表示二叉搜索树的平衡策略。
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Balance {
+    /// 不做平衡，按插入顺序生长（有序输入会退化成链表）
+    #[default]
+    None,
+    /// AVL 平衡，插入和删除后通过旋转保持左右子树高度差不超过 1
+    Avl,
+}
+
+/// 表示单词的一次出现位置。
+///
+/// 缓存中序列化为 `[文档编号, 行号, 列号, 字节偏移, 字节长度]` 数组，以减小体积。
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+#[serde(from = "(u32, u32, u32, u64, u32)", into = "(u32, u32, u32, u64, u32)")]
+pub struct Position {
+    /// 文档编号，即该文档在缓存来源列表中的下标
+    pub doc: u32,
+    /// 行号，从 1 开始
+    pub line: u32,
+    /// 列号，从 1 开始，按字符计
+    pub column: u32,
+    /// 单词在文档中的字节偏移
+    pub offset: u64,
+    /// 单词在文档中的字节长度
+    pub len: u32,
+}
+
+impl Position {
+    /// 返回单词在原文中的字节范围。
+    pub fn span(&self) -> Range<usize> {
+        let start = self.offset as usize;
+        start..start + self.len as usize
+    }
+}
+
+impl From<(u32, u32, u32, u64, u32)> for Position {
+    fn from((doc, line, column, offset, len): (u32, u32, u32, u64, u32)) -> Position {
+        Position {
+            doc,
+            line,
+            column,
+            offset,
+            len,
+        }
+    }
+}
+
+impl From<Position> for (u32, u32, u32, u64, u32) {
+    fn from(p: Position) -> Self {
+        (p.doc, p.line, p.column, p.offset, p.len)
+    }
+}
+
+impl Display for Position {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "#{}:{}:{}", self.doc, self.line, self.column)
+    }
+}
+
+/// 单词的统计信息，包括出现次数、原始形式、大小写写法和出现位置，不参与单词的比较。
+///
+/// 单词计数使用 `BSTMap<String, WordStats>`，以单词为键、统计信息为值。
+#[derive(Serialize, Deserialize, Default, Debug, Clone, PartialEq, Eq)]
+pub struct WordStats {
     /// 单词的出现次数
     count: u32,
+    /// 归并到该单词的原始形式及其出现次数，只在启用词干提取或词形还原时记录
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    forms: BTreeMap<String, u32>,
+    /// 单词在原文中的各种大小写写法及其出现次数，只在启用大小写记录时记录
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    variants: BTreeMap<String, u32>,
+    /// 单词每次出现的位置，按记录顺序排列，只在启用位置记录时记录
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    positions: Vec<Position>,
 }
 
-impl PartialOrd for Word {
-    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
-        let self_bytes = self.value.as_bytes();
-        let other_bytes = other.value.as_bytes();
+impl WordStats {
+    /// 创建一个指定出现次数的统计信息，`WordStats::default()` 的出现次数为 0。
+    pub fn with_count(count: u32) -> WordStats {
+        WordStats {
+            count,
+            ..WordStats::default()
+        }
+    }
 
-        for (i, j) in self_bytes.iter().zip(other_bytes.iter()) {
-            if i < j {
-                return Some(Ordering::Less);
-            } else if i > j {
-                return Some(Ordering::Greater);
-            }
+    /// 设置原始形式，用于从缓存加载。
+    pub fn with_forms(mut self, forms: BTreeMap<String, u32>) -> WordStats {
+        self.forms = forms;
+        self
+    }
+
+    /// 设置大小写写法，用于从缓存加载。
+    pub fn with_variants(mut self, variants: BTreeMap<String, u32>) -> WordStats {
+        self.variants = variants;
+        self
+    }
+
+    /// 设置出现位置，用于从缓存加载。
+    pub fn with_positions(mut self, positions: Vec<Position>) -> WordStats {
+        self.positions = positions;
+        self
+    }
+
+    /// 将出现次数加一，达到 `u32::MAX` 后不再增加。
+    pub fn add(&mut self) {
+        self.count = self.count.saturating_add(1);
+    }
+
+    /// 获取出现次数。
+    pub fn count(&self) -> u32 {
+        self.count
+    }
+
+    /// 记录一次归并到该单词的原始形式，不改变出现次数，达到 `u32::MAX` 后不再增加。
+    pub fn add_form(&mut self, form: &str) {
+        bump(&mut self.forms, form);
+    }
+
+    /// 获取归并到该单词的原始形式及其出现次数，未启用归并时为空。
+    pub fn forms(&self) -> &BTreeMap<String, u32> {
+        &self.forms
+    }
+
+    /// 记录一次单词在原文中的大小写写法，不改变出现次数。
+    pub fn add_variant(&mut self, variant: &str) {
+        bump(&mut self.variants, variant);
+    }
+
+    /// 获取单词在原文中的各种大小写写法及其出现次数，未启用大小写记录时为空。
+    pub fn variants(&self) -> &BTreeMap<String, u32> {
+        &self.variants
+    }
+
+    /// 获取出现次数最多的大小写写法，次数相同时取字典序最小的写法，没有记录时返回 `None`。
+    pub fn preferred_variant(&self) -> Option<&str> {
+        by_count(&self.variants)
+            .first()
+            .map(|(variant, _)| *variant)
+    }
+
+    /// 记录一次出现位置，不改变出现次数。
+    pub fn add_position(&mut self, position: Position) {
+        self.positions.push(position);
+    }
+
+    /// 获取单词每次出现的位置，未启用位置记录时为空。
+    pub fn positions(&self) -> &[Position] {
+        &self.positions
+    }
+
+    /// 合并同一个单词的另一份统计信息，各项次数相加时超过 `u32::MAX` 的部分被截断，
+    /// 因此合并很大或被篡改的缓存也不会溢出。
+    pub fn merge(&mut self, other: WordStats) {
+        self.positions.extend(other.positions);
+        self.count = self.count.saturating_add(other.count);
+        for (form, count) in other.forms {
+            let total = self.forms.entry(form).or_insert(0);
+            *total = total.saturating_add(count);
         }
+        for (variant, count) in other.variants {
+            let total = self.variants.entry(variant).or_insert(0);
+            *total = total.saturating_add(count);
+        }
+    }
 
-        if self_bytes.len() < other_bytes.len() {
-            Some(Ordering::Less)
-        } else if self_bytes.len() > other_bytes.len() {
-            Some(Ordering::Greater)
-        } else {
-            Some(Ordering::Equal)
+    /// 按出现次数从多到少列出原始形式，如 `killed 3, killing 2`；
+    /// 没有记录原始形式，或唯一的原始形式就是单词 `value` 本身时返回 `None`。
+    pub fn forms_summary(&self, value: &str) -> Option<String> {
+        summary(&self.forms, value)
+    }
+
+    /// 按出现次数从多到少列出大小写写法，如 `Israeli 5, israeli 2, ISRAELI 1`；
+    /// 没有记录大小写写法，或唯一的写法就是单词 `value` 本身时返回 `None`。
+    pub fn variants_summary(&self, value: &str) -> Option<String> {
+        summary(&self.variants, value)
+    }
+
+    /// 按 `value: count (forms)` 的格式输出单词及其统计信息。
+    fn write(&self, f: &mut std::fmt::Formatter<'_>, value: &str) -> std::fmt::Result {
+        write!(f, "{}: {}", value, self.count)?;
+        if let Some(forms) = self.forms_summary(value) {
+            write!(f, " ({forms})")?;
         }
+        Ok(())
+    }
+}
+
+impl Display for KeyValue<String, WordStats> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.value.write(f, &self.key)
+    }
+}
+
+/// 表示一个单词，包括单词的值和统计信息，只按单词的值比较。
+///
+/// 序列化时统计信息与单词的值位于同一层，如 `{"value": "kill", "count": 2, "forms": {..}}`。
+#[derive(Serialize, Deserialize, Eq, Debug, Clone)]
+pub struct Word {
+    /// 单词的值
+    pub value: String,
+    /// 单词的统计信息
+    #[serde(flatten)]
+    stats: WordStats,
+}
+
+impl PartialOrd for Word {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
     }
 }
 
@@ -141,29 +395,181 @@ impl Ord for Word {
     }
 }
 
+/// 按逐字节的字典序比较与 `str` 的 `Ord` 一致，因此可以用 `&str` 在单词树中查找。
+impl Borrow<str> for Word {
+    fn borrow(&self) -> &str {
+        &self.value
+    }
+}
+
 impl Display for Word {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "{}: {}", self.value, self.count)
+        self.stats.write(f, &self.value)
+    }
+}
+
+impl From<KeyValue<String, WordStats>> for Word {
+    fn from(kv: KeyValue<String, WordStats>) -> Word {
+        Word::from_parts(kv.key, kv.value)
+    }
+}
+
+impl From<Word> for KeyValue<String, WordStats> {
+    fn from(word: Word) -> Self {
+        KeyValue {
+            key: word.value,
+            value: word.stats,
+        }
     }
 }
 
 impl Word {
     /// 创建一个新的 Word 实例。
     pub fn new(value: String) -> Word {
-        Word { value, count: 1 }
+        Word::with_count(value, 1)
+    }
+
+    /// 创建一个指定出现次数的 Word 实例。
+    pub fn with_count(value: String, count: u32) -> Word {
+        Word::from_parts(value, WordStats::with_count(count))
+    }
+
+    /// 创建一个指定出现次数和原始形式的 Word 实例，用于从缓存加载。
+    pub fn with_forms(value: String, count: u32, forms: BTreeMap<String, u32>) -> Word {
+        Word::from_parts(value, WordStats::with_count(count).with_forms(forms))
+    }
+
+    /// 创建一个指定出现次数、原始形式和大小写写法的 Word 实例，用于从缓存加载。
+    pub fn with_variants(
+        value: String,
+        count: u32,
+        forms: BTreeMap<String, u32>,
+        variants: BTreeMap<String, u32>,
+    ) -> Word {
+        let stats = WordStats::with_count(count)
+            .with_forms(forms)
+            .with_variants(variants);
+        Word::from_parts(value, stats)
+    }
+
+    /// 由单词的值和统计信息创建 Word 实例。
+    pub fn from_parts(value: String, stats: WordStats) -> Word {
+        Word { value, stats }
+    }
+
+    /// 拆分为单词的值和统计信息。
+    pub fn into_parts(self) -> (String, WordStats) {
+        (self.value, self.stats)
+    }
+
+    /// 设置单词的出现位置，用于从缓存加载。
+    pub fn with_positions(mut self, positions: Vec<Position>) -> Word {
+        self.stats.positions = positions;
+        self
+    }
+
+    /// 获取单词的统计信息。
+    pub fn stats(&self) -> &WordStats {
+        &self.stats
+    }
+
+    /// 获取单词的统计信息的可变引用。
+    pub fn stats_mut(&mut self) -> &mut WordStats {
+        &mut self.stats
     }
 
     /// 将单词的出现次数加一。
     pub fn add(&mut self) {
-        self.count += 1;
+        self.stats.add();
     }
 
     /// 获取单词的出现次数。
     pub fn count(&self) -> u32 {
-        self.count
+        self.stats.count()
+    }
+
+    /// 记录一次归并到该单词的原始形式，不改变出现次数。
+    pub fn add_form(&mut self, form: &str) {
+        self.stats.add_form(form);
+    }
+
+    /// 获取归并到该单词的原始形式及其出现次数，未启用归并时为空。
+    pub fn forms(&self) -> &BTreeMap<String, u32> {
+        self.stats.forms()
+    }
+
+    /// 记录一次单词在原文中的大小写写法，不改变出现次数。
+    pub fn add_variant(&mut self, variant: &str) {
+        self.stats.add_variant(variant);
+    }
+
+    /// 获取单词在原文中的各种大小写写法及其出现次数，未启用大小写记录时为空。
+    pub fn variants(&self) -> &BTreeMap<String, u32> {
+        self.stats.variants()
+    }
+
+    /// 获取出现次数最多的大小写写法，次数相同时取字典序最小的写法，没有记录时返回 `None`。
+    pub fn preferred_variant(&self) -> Option<&str> {
+        self.stats.preferred_variant()
+    }
+
+    /// 记录一次出现位置，不改变出现次数。
+    pub fn add_position(&mut self, position: Position) {
+        self.stats.add_position(position);
+    }
+
+    /// 获取单词每次出现的位置，未启用位置记录时为空。
+    pub fn positions(&self) -> &[Position] {
+        self.stats.positions()
+    }
+
+    /// 合并另一个相同单词的出现次数、原始形式、大小写写法和出现位置。
+    pub fn merge(&mut self, other: Word) {
+        self.stats.merge(other.stats);
+    }
+
+    /// 按出现次数从多到少列出原始形式，如 `killed 3, killing 2`；
+    /// 没有记录原始形式，或唯一的原始形式就是单词本身时返回 `None`。
+    pub fn forms_summary(&self) -> Option<String> {
+        self.stats.forms_summary(&self.value)
+    }
+
+    /// 按出现次数从多到少列出大小写写法，如 `Israeli 5, israeli 2, ISRAELI 1`；
+    /// 没有记录大小写写法，或唯一的写法就是单词本身时返回 `None`。
+    pub fn variants_summary(&self) -> Option<String> {
+        self.stats.variants_summary(&self.value)
     }
 }
 
+/// 将 `map` 中 `key` 对应的次数加一，达到 `u32::MAX` 后不再增加。
+fn bump(map: &mut BTreeMap<String, u32>, key: &str) {
+    match map.get_mut(key) {
+        Some(count) => *count = count.saturating_add(1),
+        None => {
+            map.insert(key.to_string(), 1);
+        }
+    }
+}
+
+/// 按次数从多到少排列，次数相同时按字典序排列。
+fn by_count(map: &BTreeMap<String, u32>) -> Vec<(&str, u32)> {
+    let mut entries: Vec<(&str, u32)> = map.iter().map(|(k, v)| (k.as_str(), *v)).collect();
+    entries.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(b.0)));
+    entries
+}
+
+/// 将 `map` 列为 `key count, ..`，为空或只有 `value` 本身时返回 `None`。
+fn summary(map: &BTreeMap<String, u32>, value: &str) -> Option<String> {
+    if map.is_empty() || (map.len() == 1 && map.contains_key(value)) {
+        return None;
+    }
+    let entries: Vec<String> = by_count(map)
+        .into_iter()
+        .map(|(key, count)| format!("{key} {count}"))
+        .collect();
+    Some(entries.join(", "))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -181,4 +587,111 @@ mod tests {
         word4.add();
         assert!(word3 == word4)
     }
+
+    #[test]
+    fn test_saturating_counts() {
+        let forms = BTreeMap::from([("killed".to_string(), u32::MAX - 1)]);
+        let mut word = Word::with_forms("kill".to_string(), u32::MAX - 1, forms.clone());
+        word.add();
+        word.add();
+        word.add_form("killed");
+        word.add_form("killed");
+        assert_eq!(word.count(), u32::MAX);
+        assert_eq!(word.forms()["killed"], u32::MAX);
+
+        let variants = BTreeMap::from([("Kill".to_string(), u32::MAX)]);
+        let mut word = Word::with_variants("kill".to_string(), 1, BTreeMap::new(), variants);
+        word.add_variant("Kill");
+        assert_eq!(word.variants()["Kill"], u32::MAX);
+
+        let mut word = Word::with_count("kill".to_string(), u32::MAX - 5);
+        let variants = BTreeMap::from([("Kill".to_string(), 10)]);
+        word.merge(Word::with_variants(
+            "kill".to_string(),
+            10,
+            forms.clone(),
+            variants.clone(),
+        ));
+        word.merge(Word::with_variants(
+            "kill".to_string(),
+            u32::MAX,
+            forms.clone(),
+            variants,
+        ));
+        assert_eq!(word.count(), u32::MAX);
+        assert_eq!(word.forms()["killed"], u32::MAX);
+        assert_eq!(word.variants()["Kill"], 20);
+
+        let mut stats = WordStats::with_count(u32::MAX - 5).with_forms(forms.clone());
+        let variants = BTreeMap::from([("Kill".to_string(), 10)]);
+        stats.merge(
+            WordStats::with_count(10)
+                .with_forms(forms)
+                .with_variants(variants.clone()),
+        );
+        stats.merge(WordStats::with_count(u32::MAX).with_variants(variants));
+        assert_eq!(stats.count(), u32::MAX);
+        assert_eq!(stats.forms()["killed"], u32::MAX);
+        assert_eq!(stats.variants()["Kill"], 20);
+    }
+
+    #[test]
+    fn test_borrow() {
+        // 以 &str 比较的结果与 Word 的比较一致
+        let values = ["", "a", "apple", "apples", "Apple", "以色列", "以色"];
+        for a in values {
+            for b in values {
+                let (wa, wb) = (Word::new(a.to_string()), Word::new(b.to_string()));
+                let (sa, sb): (&str, &str) = (wa.borrow(), wb.borrow());
+                assert_eq!(wa.cmp(&wb), sa.cmp(sb), "{a} {b}");
+            }
+        }
+    }
+
+    #[test]
+    fn test_forms() {
+        let mut word = Word::with_count("kill".to_string(), 5);
+        assert_eq!(word.to_string(), "kill: 5");
+        for form in ["killed", "killing", "killed", "killing", "killed"] {
+            word.add_form(form);
+        }
+        assert_eq!(word.to_string(), "kill: 5 (killed 3, killing 2)");
+
+        let mut other = Word::new("kill".to_string());
+        other.add_form("kill");
+        assert_eq!(other.forms_summary(), None);
+        word.merge(other);
+        assert_eq!(word.count(), 6);
+        assert_eq!(word.to_string(), "kill: 6 (killed 3, killing 2, kill 1)");
+    }
+
+    #[test]
+    fn test_variants() {
+        let mut word = Word::with_count("israeli".to_string(), 8);
+        assert_eq!(word.preferred_variant(), None);
+        for variant in [
+            "Israeli", "israeli", "ISRAELI", "Israeli", "israeli", "Israeli",
+        ] {
+            word.add_variant(variant);
+        }
+        assert_eq!(word.preferred_variant(), Some("Israeli"));
+        assert_eq!(
+            word.variants_summary().as_deref(),
+            Some("Israeli 3, israeli 2, ISRAELI 1")
+        );
+        // 大小写写法不影响比较和显示
+        assert_eq!(word, Word::new("israeli".to_string()));
+        assert_eq!(word.to_string(), "israeli: 8");
+
+        let mut lower = Word::new("the".to_string());
+        lower.add_variant("the");
+        assert_eq!(lower.variants_summary(), None);
+        lower.merge(Word::with_variants(
+            "the".to_string(),
+            1,
+            BTreeMap::new(),
+            BTreeMap::from([("The".to_string(), 1)]),
+        ));
+        assert_eq!(lower.variants_summary().as_deref(), Some("The 1, the 1"));
+    }
 }
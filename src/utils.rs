@@ -4,26 +4,20 @@
 //!
 //! ## 示例
 //!
-//! ```rust
-//! use crate::file_operations::{write_str_to_file, read_from_file, filter_word};
+//! ```rust,no_run
+//! use bst_words::utils::{write_str_to_file, read_from_file, filter_word};
 //!
-//! #[test]
-//! fn test_write() {
-//!     write_str_to_file("data.txt", "Hello, canxin!").unwrap();
-//! }
+//! // 写入文件
+//! write_str_to_file("hello.txt", "Hello, canxin!").unwrap();
 //!
-//! #[test]
-//! fn test_read() {
-//!     let data = read_from_file("data.txt").unwrap();
-//!     println!("{}", data);
-//! }
+//! // 读取文件
+//! let data = read_from_file("data.txt").unwrap();
+//! println!("{}", data);
 //!
-//! #[test]
-//! fn test_filter() {
-//!     let mut context = "This is a sample text with some repeated words. Some words may appear more than once. Let's test the BST program.".to_string();
-//!     for (index, word) in filter_word(&mut context).enumerate() {
-//!         println!("{}:{}", index, word);
-//!     }
+//! // 过滤单词
+//! let mut context = "This is a sample text with some repeated words. Some words may appear more than once. Let's test the BST program.".to_string();
+//! for (index, word) in filter_word(&mut context).enumerate() {
+//!     println!("{}:{}", index, word);
 //! }
 //! ```
 //!
@@ -31,7 +25,29 @@
 //!
 //! - `write_str_to_file`: 将字符串写入指定文件。
 //! - `read_from_file`: 从指定文件中读取字符串。
-//! - `filter_word`: 从字符串中过滤出单词。
+//! - `filter_word`: 从字符串中过滤出单词，会把字符串转为小写，新代码请使用 `AsciiTokenizer`。
+//! - `Tokenizer`: 分词器 trait，不修改输入，返回借用或拥有的单词；语料、停用词和查询词都应通过同一个分词器切分。
+//! - `Token`: `Tokenizer::tokens` 返回的单词及其在原文中的字节范围，用于记录单词的原始大小写。
+//! - `AsciiTokenizer` / `UnicodeTokenizer` / `WhitespaceTokenizer` / `RegexTokenizer`: 内置的分词器。
+//! - `WordRules`: 缩写（拆开、保留或展开）、弯撇号统一、连字符复合词、数字（丢弃、保留或计为 `<NUM>`）和符号类别的处理规则，用于 ASCII 和 Unicode 分词器。
+//! - `Segmenter`: 基于词典正向最大匹配的中文分词器，可使用内置词典或用户词典。
+//! - `tokenizer_from_name`: 根据缓存文件头中记录的名称重新创建分词器。
+//! - `Normalizer` / `PorterStemmer` / `Lemmatizer`: 可选的词干提取和词形还原，把单词的不同形式归并为同一个单词，`normalizer_from_name` 根据名称重新创建。
+//! - `filter_word_unicode`: 按 Unicode 单词边界（UAX #29）从字符串中过滤出单词，保留非 ASCII 的单词。
+//! - `fold_case`: 按 Unicode 的 CaseFolding.txt 做完全大小写折叠，用于不区分大小写地比较单词。
+//! - `append_text` / `append_file`: 将文本或文件中的单词按指定分词方式和归并方式追加到已有的单词树中，并记录来源。
+//! - `Concordance` / `source_text`: 根据单词记录的出现位置重新读取原文，生成带前后若干个词的 KWIC 索引行。
+//! - `fuzzy_search` / `edit_distance` / `Metric`: 按 Levenshtein 或 Damerau 编辑距离查找相近的单词，用于查询词拼错时给出建议。
+//! - `Pattern` / `pattern_search`: 按通配符（`*`、`?`）或正则表达式查找单词，模式有字面前缀时只检查以该前缀开头的区间。
+//! - `tree_to_json`: 将二叉搜索树序列化为 JSON 字符串。
+//! - `tree_from_json`: 从 JSON 字符串加载二叉搜索树，兼容旧版本的嵌套格式。
+//! - `words_to_json`: 将单词树按 `CacheFormat` 指定的树形或紧凑格式序列化。
+//! - `words_from_json`: 从 JSON 字符串加载单词树，自动识别缓存格式。
+//! - `cache_to_json`: 将单词树连同版本、来源、分词设置和校验和等文件头一起序列化。
+//! - `cache_from_json`: 加载带文件头的缓存并校验版本和校验和，无文件头的旧缓存会被迁移。
+//! - `write_binary_cache` / `read_binary_cache`: 以二进制格式流式写入、读取单词树和文件头。
+//! - `save_binary_cache`: 将单词树以二进制格式保存到文件。
+//! - `load_cache_file`: 从文件加载缓存，自动识别二进制和 JSON 格式。
 //!
 //! ## 使用注意事项
 //!
@@ -39,9 +55,10 @@
 //!
 //! ## 示例
 //!
-//! ```rust
+//! ```rust,no_run
+//! # use bst_words::utils::{write_str_to_file, read_from_file, filter_word};
 //! // 写入文件示例
-//! write_str_to_file("data.txt", "Hello, canxin!").unwrap();
+//! write_str_to_file("hello.txt", "Hello, canxin!").unwrap();
 //!
 //! // 读取文件示例
 //! let data = read_from_file("data.txt").unwrap();
@@ -74,6 +91,51 @@
 //! 使用本模块时，请根据实际场景谨慎处理文件路径和内容。
 //!
 //! 若有疑问或需要帮助，请参考 Rust 官方文档或向相关社区寻求支持。
+// 二进制缓存的流式读写
+mod binary;
+// 缓存的序列化、格式识别和文件头校验
+mod cache;
+// Unicode 完全大小写折叠
+mod casefold;
+// 根据出现位置生成上下文索引行
+mod concordance;
+// 基于词典的中文分词
+mod cjk;
+// 按编辑距离模糊查询
+mod fuzzy;
+// 按通配符或正则表达式查询
+mod pattern;
+// 缩写、撇号和连字符的处理规则
+mod rules;
+// 词干提取和词形还原
+mod stem;
+// 可选的分词方式
+mod tokenize;
+
+pub use binary::{
+    load_cache_file, read_binary_cache, save_binary_cache, write_binary_cache, BINARY_MAGIC,
+};
+pub use cache::{
+    cache_from_json, cache_to_json, checksum, detect_format, tree_from_json, tree_to_json,
+    words_from_json, words_to_json, CacheFormat, CacheMeta, SourceInfo, CACHE_VERSION,
+};
+pub use casefold::fold_case;
+pub use cjk::{is_cjk, Segmenter};
+pub use concordance::{source_text, Concordance, KwicLine};
+pub use fuzzy::{edit_distance, fuzzy_search, FuzzyMatch, Metric};
+pub use pattern::{pattern_search, Pattern};
+pub use rules::{
+    expand_contraction, is_apostrophe, is_hyphen, is_number, normalize_apostrophes, Contractions,
+    Hyphens, Numbers, Symbols, WordRules, NUM_TOKEN,
+};
+pub use stem::{normalizer_from_name, porter_stem, Lemmatizer, Normalizer, PorterStemmer};
+pub use tokenize::{
+    filter_word_unicode, tokenizer_from_name, AsciiTokenizer, RegexTokenizer, Token, Tokenizer,
+    UnicodeTokenizer, WhitespaceTokenizer,
+};
+// 引入map模块中的BSTMap结构体和r#type模块中的WordStats结构体
+use crate::map::BSTMap;
+use crate::r#type::{Position, WordStats};
 // 引入anyhow库中的Error类型，它用于表示任何可能的错误
 use anyhow::Error;
 // 引入std库中的fs模块，它用于处理文件系统的操作，比如创建目录，打开文件等
@@ -123,7 +185,7 @@ pub fn read_from_file<P: AsRef<Path>>(path: P) -> Result<String, Error> {
     reader
         .get_mut()
         .read_to_string(&mut buf)
-        .unwrap_or_else(|err| {
+        .unwrap_or_else(|_| {
             buf = "{}".to_string();
             2
         });
@@ -132,6 +194,8 @@ pub fn read_from_file<P: AsRef<Path>>(path: P) -> Result<String, Error> {
 
 /// 从字符串中过滤出单词。
 ///
+/// 该函数会把 `context` 转为小写，新代码请使用不修改输入的 `AsciiTokenizer`。
+///
 /// # 参数
 ///
 /// * `context`: 可变引用的字符串，将对其进行单词过滤。
@@ -146,13 +210,111 @@ pub fn filter_word(context: &mut String) -> impl Iterator<Item = &str> {
         .filter(|s| !s.is_empty())
 }
 
+/// 将文本中的单词追加到已有的单词计数映射中，并在元数据中记录该文本来源。
+///
+/// 文本先按 `tokenizer` 切分出单词，指定了 `normalizer` 时再归并为词干或词元，然后逐个通过 `entry` 取得统计信息，
+/// 新单词以空的统计信息插入，每次出现的次数加一，因此可以在加载的缓存上不断累积多篇文档的计数。
+/// 启用归并时，每个单词还会记录归并前的原始形式及其出现次数；`meta.case_variants` 为 `true` 时，
+/// 每个单词还会记录它在原文中的大小写写法（如 "Israeli"、"ISRAELI"）及其出现次数；`meta.positions` 为 `true` 时，
+/// 每个单词还会记录每次出现的位置，文档编号为该文本在 `meta.sources` 中的下标。
+///
+/// # 参数
+///
+/// * `words`: 要追加到的单词计数映射。
+/// * `meta`: 缓存的元数据，会记录来源、文本校验和和读入的单词数。
+/// * `tokenizer`: 分词器，其名称应与 `meta.tokenizer` 一致，否则计数可能不一致。
+/// * `normalizer`: 归并方式，其名称应与 `meta.normalizer` 一致，`None` 表示不归并。
+/// * `source`: 文本的来源，通常为文件路径，直接输入的文本为 `<input>`。
+/// * `text`: 文本内容。
+///
+/// # 返回
+///
+/// 返回读入的单词数（包括重复出现的单词）。
+pub fn append_text(
+    words: &mut BSTMap<String, WordStats>,
+    meta: &mut CacheMeta,
+    tokenizer: &dyn Tokenizer,
+    normalizer: Option<&dyn Normalizer>,
+    source: &str,
+    text: &str,
+) -> u64 {
+    let mut tokens = 0;
+    let doc = meta.sources.len() as u32;
+    let mut cursor = meta.positions.then(|| concordance::LineCursor::new(text));
+    for token in tokenizer.tokens(text) {
+        let word = &token.word;
+        // 展开的缩写和<NUM>在原文中没有对应的写法，不记录大小写
+        let variant = (meta.case_variants && token.verbatim).then(|| &text[token.span.clone()]);
+        let position = cursor.as_mut().map(|cursor| {
+            let (line, column) = cursor.locate(token.span.start);
+            Position {
+                doc,
+                line,
+                column,
+                offset: token.span.start as u64,
+                len: token.span.len() as u32,
+            }
+        });
+        let value = match normalizer {
+            Some(normalizer) => normalizer.normalize(word).into_owned(),
+            None => word.to_string(),
+        };
+        let stats = words.entry(value).or_default();
+        stats.add();
+        if normalizer.is_some() {
+            stats.add_form(word);
+        }
+        if let Some(variant) = variant {
+            stats.add_variant(variant);
+        }
+        if let Some(position) = position {
+            stats.add_position(position);
+        }
+        tokens += 1;
+    }
+    meta.add_source(source, checksum(text.as_bytes()), tokens);
+    tokens
+}
+
+/// 读取文本文件，并将其中的单词追加到已有的单词计数映射中，见 `append_text`。
+///
+/// # 参数
+///
+/// * `words`: 要追加到的单词计数映射。
+/// * `meta`: 缓存的元数据。
+/// * `tokenizer`: 分词器。
+/// * `normalizer`: 归并方式，`None` 表示不归并。
+/// * `path`: 泛型类型 `P`，必须实现 `AsRef<Path>` trait，表示文件路径。
+///
+/// # 返回
+///
+/// 返回 `Result<u64, Error>` 类型。如果成功，返回读入的单词数；否则返回 `Error`，表示错误原因。
+pub fn append_file<P: AsRef<Path>>(
+    words: &mut BSTMap<String, WordStats>,
+    meta: &mut CacheMeta,
+    tokenizer: &dyn Tokenizer,
+    normalizer: Option<&dyn Normalizer>,
+    path: P,
+) -> Result<u64, Error> {
+    let path = path.as_ref();
+    let text = fs::read_to_string(path)?;
+    Ok(append_text(
+        words,
+        meta,
+        tokenizer,
+        normalizer,
+        &path.to_string_lossy(),
+        &text,
+    ))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
     #[test]
     fn test_write() {
-        write_str_to_file("data.txt", "Hello, canxin!").unwrap();
+        write_str_to_file("target/test_write.txt", "Hello, canxin!").unwrap();
     }
 
     #[test]
@@ -161,6 +323,166 @@ mod tests {
         println!("{}", data);
     }
 
+    #[test]
+    fn test_append() {
+        let mut bstree = BSTMap::new();
+        let mut meta = CacheMeta::new("ascii-lowercase");
+        assert_eq!(
+            append_text(
+                &mut bstree,
+                &mut meta,
+                &AsciiTokenizer::new(),
+                None,
+                "<input>",
+                "Apple pear, apple."
+            ),
+            3
+        );
+        let json = cache_to_json(&bstree, &meta, CacheFormat::Compact).unwrap();
+
+        // 加载缓存后继续追加另一篇文档
+        let (mut loaded, mut loaded_meta) = cache_from_json(&json).unwrap();
+        let tokens = append_file(
+            &mut loaded,
+            &mut loaded_meta,
+            &AsciiTokenizer::new(),
+            None,
+            "data.txt",
+        )
+        .unwrap();
+        assert!(tokens > 0);
+        let count = |t: &BSTMap<String, WordStats>, w: &str| t.get(w).map(|s| s.count());
+        let text = read_from_file("data.txt").unwrap();
+        let in_data = AsciiTokenizer::new()
+            .tokenize(&text)
+            .iter()
+            .filter(|w| *w == "apple")
+            .count() as u32;
+        assert_eq!(count(&loaded, "apple"), Some(2 + in_data));
+        assert_eq!(count(&loaded, "pear"), Some(1));
+        assert!(count(&loaded, "hostages").is_some());
+        assert_eq!(loaded_meta.sources.len(), 2);
+        assert_eq!(loaded_meta.sources[1].path, "data.txt");
+        assert_eq!(loaded_meta.total_tokens, 3 + tokens);
+        assert!(append_file(
+            &mut loaded,
+            &mut loaded_meta,
+            &AsciiTokenizer::new(),
+            None,
+            "missing.txt"
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn test_append_stemmed() {
+        let mut bstree = BSTMap::new();
+        let mut meta = CacheMeta::new(AsciiTokenizer::NAME);
+        meta.normalizer = Some(PorterStemmer::NAME.to_string());
+        let text =
+            "Killed, killing, killed. The IDF killed 3 hostages; a hostage was killing time.";
+        append_text(
+            &mut bstree,
+            &mut meta,
+            &AsciiTokenizer::new(),
+            Some(&PorterStemmer),
+            "<input>",
+            text,
+        );
+        let find = |w: &str| {
+            bstree
+                .tree()
+                .iter()
+                .find(|kv| kv.key == w)
+                .map(|kv| kv.to_string())
+        };
+        assert_eq!(
+            find("kill").as_deref(),
+            Some("kill: 5 (killed 3, killing 2)")
+        );
+        assert_eq!(
+            find("hostag").as_deref(),
+            Some("hostag: 2 (hostage 1, hostages 1)")
+        );
+        assert_eq!(find("the").as_deref(), Some("the: 1"));
+        assert!(find("killed").is_none());
+    }
+
+    #[test]
+    fn test_append_variants() {
+        let mut bstree = BSTMap::new();
+        let mut meta = CacheMeta::new(AsciiTokenizer::NAME);
+        meta.case_variants = true;
+        let text =
+            "The IDF said Israeli troops; ISRAELI media and israeli officials. Idf, the IDF.";
+        append_text(
+            &mut bstree,
+            &mut meta,
+            &AsciiTokenizer::new(),
+            None,
+            "<input>",
+            text,
+        );
+        let word = |w: &str| bstree.get(w).unwrap().clone();
+        let israeli = word("israeli");
+        assert_eq!(israeli.count(), 3);
+        assert_eq!(
+            israeli.variants_summary("israeli").as_deref(),
+            Some("ISRAELI 1, Israeli 1, israeli 1")
+        );
+        assert_eq!(word("idf").preferred_variant(), Some("IDF"));
+        assert_eq!(
+            word("the").variants_summary("the").as_deref(),
+            Some("The 1, the 1")
+        );
+        assert_eq!(word("said").variants_summary("said"), None);
+
+        // 与词干提取同时使用时，大小写写法记录在词干上
+        let mut stemmed = BSTMap::new();
+        append_text(
+            &mut stemmed,
+            &mut meta,
+            &AsciiTokenizer::new(),
+            Some(&PorterStemmer),
+            "<input>",
+            "Killed killing KILLS",
+        );
+        let kill = stemmed.get("kill").unwrap();
+        assert_eq!(
+            kill.variants_summary("kill").as_deref(),
+            Some("KILLS 1, Killed 1, killing 1")
+        );
+    }
+
+    #[test]
+    fn test_append_positions() {
+        let mut bstree = BSTMap::new();
+        let mut meta = CacheMeta::new(AsciiTokenizer::NAME);
+        meta.positions = true;
+        let tokenizer = AsciiTokenizer::new();
+        append_text(
+            &mut bstree,
+            &mut meta,
+            &tokenizer,
+            None,
+            "<input>",
+            "The IDF said\nthe IDF.",
+        );
+        append_text(&mut bstree, &mut meta, &tokenizer, None, "<input>", "IDF");
+        let idf = bstree.get("idf").unwrap();
+        let found: Vec<String> = idf.positions().iter().map(|p| p.to_string()).collect();
+        assert_eq!(found, ["#0:1:5", "#0:2:5", "#1:1:1"]);
+        assert_eq!(idf.positions()[1].span(), 17..20);
+        assert!(idf.variants().is_empty());
+
+        // 未启用位置记录时不记录
+        meta.positions = false;
+        append_text(&mut bstree, &mut meta, &tokenizer, None, "<input>", "IDF");
+        let idf = bstree.get("idf").unwrap();
+        assert_eq!(idf.count(), 4);
+        assert_eq!(idf.positions().len(), 3);
+    }
+
     #[test]
     fn test_filter() {
         let mut context =
@@ -0,0 +1,501 @@
+//! # 二进制缓存模块
+//!
+//! 该模块提供单词树的二进制缓存格式，适用于包含大量不同单词的语料，读写时逐个单词流式处理，
+//! 不会在内存中生成完整的字符串。
+//!
+//! ## 文件结构
+//!
+//! 整数均使用 LEB128 变长编码（varint），字符串为 varint 长度加 UTF-8 字节：
+//!
+//! | 字段 | 内容 |
+//! | --- | --- |
+//! | 魔数 | `BSTW` 4 个字节 |
+//! | 版本号 | varint，等于 `CACHE_VERSION` |
+//! | 创建时间 | varint，Unix 时间戳（秒） |
+//! | 来源 | varint 个数，每个来源为路径、校验和两个字符串和一个 varint 单词数 |
+//! | 分词设置 | 字符串 |
+//! | 归并方式 | 字符串，未启用词干提取或词形还原时为空字符串 |
+//! | 大小写记录 | varint，1 表示记录了单词的大小写写法，0 表示未记录 |
+//! | 位置记录 | varint，1 表示记录了单词的出现位置，0 表示未记录 |
+//! | 单词总数 | varint |
+//! | 单词 | varint 个数，按字典序排列，每个单词为字符串、varint 出现次数、原始形式、大小写写法和出现位置，原始形式和大小写写法均为 varint 个数加若干个字符串和 varint 次数，出现位置为 varint 个数加若干组文档编号、行号、列号、字节偏移和字节长度 5 个 varint |
+//! | 校验和 | 8 个字节（小端），为魔数之后、校验和之前全部字节的 FNV-1a 64 位哈希 |
+//!
+//! 加载时单词按顺序重建为 AVL 平衡树。版本 2 的二进制缓存没有归并方式和原始形式，
+//! 版本 3 没有大小写记录和大小写写法，版本 4 没有位置记录和出现位置，仍可加载。
+// 引入cache模块中的缓存元数据和校验和计算
+use super::cache::{
+    now, CacheMeta, Fnv64, SourceInfo, CACHE_VERSION, FORMS_VERSION, HEADER_VERSION,
+    POSITIONS_VERSION, VARIANTS_VERSION,
+};
+// 引入map模块中的BSTMap结构体，以及r#type模块中的Balance枚举类型和WordStats结构体
+use crate::map::BSTMap;
+use crate::r#type::{Balance, Position, WordStats};
+// 引入anyhow库中的Error类型和bail宏，它们用于表示和返回错误
+use anyhow::{bail, Error};
+// 引入std库中的fs模块和io模块，它们用于文件的创建和读写
+use std::collections::BTreeMap;
+use std::fs::{self, File};
+use std::io::{BufReader, BufWriter, ErrorKind, Read, Write};
+use std::path::Path;
+
+/// 二进制缓存文件开头的魔数。
+pub const BINARY_MAGIC: &[u8; 4] = b"BSTW";
+
+/// 在写入的同时计算校验和的包装。
+struct HashWriter<W> {
+    inner: W,
+    hasher: Fnv64,
+}
+
+impl<W: Write> HashWriter<W> {
+    fn write_bytes(&mut self, bytes: &[u8]) -> Result<(), Error> {
+        self.hasher.update(bytes);
+        self.inner.write_all(bytes)?;
+        Ok(())
+    }
+
+    fn write_varint(&mut self, mut value: u64) -> Result<(), Error> {
+        let mut buf = [0u8; 10];
+        let mut len = 0;
+        loop {
+            let byte = (value & 0x7f) as u8;
+            value >>= 7;
+            if value == 0 {
+                buf[len] = byte;
+                len += 1;
+                break;
+            }
+            buf[len] = byte | 0x80;
+            len += 1;
+        }
+        self.write_bytes(&buf[..len])
+    }
+
+    fn write_str(&mut self, s: &str) -> Result<(), Error> {
+        self.write_varint(s.len() as u64)?;
+        self.write_bytes(s.as_bytes())
+    }
+
+    fn write_counts(&mut self, counts: &BTreeMap<String, u32>) -> Result<(), Error> {
+        self.write_varint(counts.len() as u64)?;
+        for (key, count) in counts {
+            self.write_str(key)?;
+            self.write_varint(u64::from(*count))?;
+        }
+        Ok(())
+    }
+
+    fn write_positions(&mut self, positions: &[Position]) -> Result<(), Error> {
+        self.write_varint(positions.len() as u64)?;
+        for position in positions {
+            self.write_varint(u64::from(position.doc))?;
+            self.write_varint(u64::from(position.line))?;
+            self.write_varint(u64::from(position.column))?;
+            self.write_varint(position.offset)?;
+            self.write_varint(u64::from(position.len))?;
+        }
+        Ok(())
+    }
+}
+
+/// 在读取的同时计算校验和的包装。
+struct HashReader<R> {
+    inner: R,
+    hasher: Fnv64,
+}
+
+impl<R: Read> HashReader<R> {
+    fn read_bytes(&mut self, buf: &mut [u8]) -> Result<(), Error> {
+        self.inner.read_exact(buf).map_err(truncated)?;
+        self.hasher.update(buf);
+        Ok(())
+    }
+
+    fn read_varint(&mut self) -> Result<u64, Error> {
+        let mut value = 0u64;
+        for shift in (0..64).step_by(7) {
+            let mut byte = [0u8];
+            self.read_bytes(&mut byte)?;
+            // 第 10 个字节只能提供最高的 1 位
+            if shift == 63 && byte[0] > 1 {
+                bail!("二进制缓存中的整数超出 64 位，文件可能已损坏");
+            }
+            value |= u64::from(byte[0] & 0x7f) << shift;
+            if byte[0] & 0x80 == 0 {
+                return Ok(value);
+            }
+        }
+        bail!("二进制缓存中的整数过长，文件可能已损坏")
+    }
+
+    fn read_u32(&mut self) -> Result<u32, Error> {
+        let value = self.read_varint()?;
+        u32::try_from(value).map_err(|_| Error::msg(format!("二进制缓存中的数值 {value} 超出范围")))
+    }
+
+    fn read_string(&mut self) -> Result<String, Error> {
+        let len = self.read_varint()?;
+        // 逐步读入而不是按长度预先分配，避免损坏的长度字段导致分配巨大的内存
+        let mut bytes = Vec::new();
+        let read = (&mut self.inner).take(len).read_to_end(&mut bytes)?;
+        if read as u64 != len {
+            return Err(truncated(ErrorKind::UnexpectedEof.into()));
+        }
+        self.hasher.update(&bytes);
+        Ok(String::from_utf8(bytes)?)
+    }
+
+    fn read_counts(&mut self) -> Result<BTreeMap<String, u32>, Error> {
+        let mut counts = BTreeMap::new();
+        for _ in 0..self.read_varint()? {
+            let key = self.read_string()?;
+            counts.insert(key, self.read_u32()?);
+        }
+        Ok(counts)
+    }
+
+    fn read_positions(&mut self) -> Result<Vec<Position>, Error> {
+        // 与字符串一样不按记录的个数预先分配
+        let mut positions = Vec::new();
+        for _ in 0..self.read_varint()? {
+            positions.push(Position {
+                doc: self.read_u32()?,
+                line: self.read_u32()?,
+                column: self.read_u32()?,
+                offset: self.read_varint()?,
+                len: self.read_u32()?,
+            });
+        }
+        Ok(positions)
+    }
+}
+
+/// 将读到文件末尾的错误转换为更容易理解的提示。
+fn truncated(e: std::io::Error) -> Error {
+    if e.kind() == ErrorKind::UnexpectedEof {
+        Error::msg("二进制缓存不完整，文件可能被截断")
+    } else {
+        e.into()
+    }
+}
+
+/// 将单词计数映射连同文件头以二进制格式写入 `writer`。
+///
+/// # 参数
+///
+/// * `writer`: 写入的目标，例如文件或内存缓冲区。
+/// * `words`: 要保存的单词计数映射。
+/// * `meta`: 缓存的元数据，版本号总是写为 `CACHE_VERSION`，创建时间为保存时的时间。
+///
+/// # 返回
+///
+/// 返回 `Result<(), Error>` 类型。如果成功，返回空元组；否则返回 `Error`，表示错误原因。
+pub fn write_binary_cache<W: Write>(
+    mut writer: W,
+    words: &BSTMap<String, WordStats>,
+    meta: &CacheMeta,
+) -> Result<(), Error> {
+    writer.write_all(BINARY_MAGIC)?;
+    let mut writer = HashWriter {
+        inner: writer,
+        hasher: Fnv64::new(),
+    };
+    writer.write_varint(u64::from(CACHE_VERSION))?;
+    writer.write_varint(now())?;
+    writer.write_varint(meta.sources.len() as u64)?;
+    for source in &meta.sources {
+        writer.write_str(&source.path)?;
+        writer.write_str(&source.hash)?;
+        writer.write_varint(source.tokens)?;
+    }
+    writer.write_str(&meta.tokenizer)?;
+    writer.write_str(meta.normalizer.as_deref().unwrap_or_default())?;
+    writer.write_varint(u64::from(meta.case_variants))?;
+    writer.write_varint(u64::from(meta.positions))?;
+    writer.write_varint(meta.total_tokens)?;
+    writer.write_varint(words.len() as u64)?;
+    for (value, word) in words {
+        writer.write_str(value)?;
+        writer.write_varint(u64::from(word.count()))?;
+        writer.write_counts(word.forms())?;
+        writer.write_counts(word.variants())?;
+        writer.write_positions(word.positions())?;
+    }
+    let hash = writer.hasher.finish();
+    writer.inner.write_all(&hash.to_le_bytes())?;
+    writer.inner.flush()?;
+    Ok(())
+}
+
+/// 从 `reader` 读取二进制缓存，校验魔数、版本号和校验和后返回单词计数映射和元数据。
+///
+/// # 参数
+///
+/// * `reader`: 读取的来源，例如文件或内存缓冲区。
+///
+/// # 返回
+///
+/// 返回 `Result<(BSTMap<String, WordStats>, CacheMeta), Error>` 类型。如果成功，返回单词计数映射和元数据；否则返回 `Error`，表示错误原因。
+pub fn read_binary_cache<R: Read>(
+    mut reader: R,
+) -> Result<(BSTMap<String, WordStats>, CacheMeta), Error> {
+    let mut magic = [0u8; 4];
+    reader.read_exact(&mut magic).map_err(truncated)?;
+    if &magic != BINARY_MAGIC {
+        bail!("不是二进制缓存文件");
+    }
+    let mut reader = HashReader {
+        inner: reader,
+        hasher: Fnv64::new(),
+    };
+    let version = reader.read_u32()?;
+    if !(HEADER_VERSION..=CACHE_VERSION).contains(&version) {
+        bail!("二进制缓存版本为 {version}，程序支持的版本为 {HEADER_VERSION} 到 {CACHE_VERSION}");
+    }
+    // 版本 2 没有记录归并方式和原始形式，版本 3 没有记录大小写写法，版本 4 没有记录出现位置
+    let has_forms = version >= FORMS_VERSION;
+    let has_variants = version >= VARIANTS_VERSION;
+    let has_positions = version >= POSITIONS_VERSION;
+    let created = reader.read_varint()?;
+    let mut sources = Vec::new();
+    for _ in 0..reader.read_varint()? {
+        sources.push(SourceInfo {
+            path: reader.read_string()?,
+            hash: reader.read_string()?,
+            tokens: reader.read_varint()?,
+        });
+    }
+    let tokenizer = reader.read_string()?;
+    let normalizer = if has_forms {
+        Some(reader.read_string()?).filter(|name| !name.is_empty())
+    } else {
+        None
+    };
+    let case_variants = has_variants && reader.read_varint()? != 0;
+    let positions = has_positions && reader.read_varint()? != 0;
+    let total_tokens = reader.read_varint()?;
+    let mut words = Vec::new();
+    for _ in 0..reader.read_varint()? {
+        let value = reader.read_string()?;
+        let count = reader.read_u32()?;
+        let forms = if has_forms {
+            reader.read_counts()?
+        } else {
+            BTreeMap::new()
+        };
+        let variants = if has_variants {
+            reader.read_counts()?
+        } else {
+            BTreeMap::new()
+        };
+        let stats = WordStats::with_count(count)
+            .with_forms(forms)
+            .with_variants(variants);
+        words.push(if has_positions {
+            (value, stats.with_positions(reader.read_positions()?))
+        } else {
+            (value, stats)
+        });
+    }
+    let expected = reader.hasher.finish();
+    let mut hash = [0u8; 8];
+    reader.inner.read_exact(&mut hash).map_err(truncated)?;
+    let actual = u64::from_le_bytes(hash);
+    if actual != expected {
+        bail!("二进制缓存校验失败: 记录的校验和为 {actual:016x}，实际为 {expected:016x}，文件可能已损坏");
+    }
+    let words = BSTMap::from_sorted(words, Balance::Avl).map_err(Error::msg)?;
+    let meta = CacheMeta {
+        version,
+        created,
+        sources,
+        tokenizer,
+        normalizer,
+        case_variants,
+        positions,
+        total_tokens,
+    };
+    Ok((words, meta))
+}
+
+/// 将单词计数映射以二进制格式保存到指定文件。
+///
+/// # 参数
+///
+/// * `path`: 泛型类型 `P`，必须实现 `AsRef<Path>` trait，表示文件路径。
+/// * `words`: 要保存的单词计数映射。
+/// * `meta`: 缓存的元数据。
+///
+/// # 返回
+///
+/// 返回 `Result<(), Error>` 类型。如果成功，返回空元组；否则返回 `Error`，表示错误原因。
+pub fn save_binary_cache<P: AsRef<Path>>(
+    path: P,
+    words: &BSTMap<String, WordStats>,
+    meta: &CacheMeta,
+) -> Result<(), Error> {
+    let path = path.as_ref();
+    let parent = path.parent().unwrap_or_else(|| Path::new("."));
+    fs::create_dir_all(parent)?;
+    write_binary_cache(BufWriter::new(File::create(path)?), words, meta)
+}
+
+/// 从指定文件加载缓存，根据文件开头的魔数自动选择二进制或 JSON 格式。
+///
+/// # 参数
+///
+/// * `path`: 泛型类型 `P`，必须实现 `AsRef<Path>` trait，表示文件路径。
+///
+/// # 返回
+///
+/// 返回 `Result<(BSTMap<String, WordStats>, CacheMeta), Error>` 类型。如果成功，返回单词计数映射和元数据；否则返回 `Error`，表示错误原因。
+pub fn load_cache_file<P: AsRef<Path>>(
+    path: P,
+) -> Result<(BSTMap<String, WordStats>, CacheMeta), Error> {
+    let mut reader = BufReader::new(File::open(path)?);
+    let mut magic = [0u8; 4];
+    let mut read = 0;
+    while read < magic.len() {
+        match reader.read(&mut magic[read..])? {
+            0 => break,
+            n => read += n,
+        }
+    }
+    if &magic[..read] == BINARY_MAGIC {
+        return read_binary_cache(BINARY_MAGIC.chain(reader));
+    }
+    let mut json = magic[..read].to_vec();
+    reader.read_to_end(&mut json)?;
+    super::cache_from_json(&String::from_utf8(json)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::{cache_from_json, cache_to_json, checksum, read_from_file, CacheFormat};
+
+    #[test]
+    fn test_binary_round_trip() {
+        let (bstree, _) = cache_from_json(&read_from_file("words.json").unwrap()).unwrap();
+        let mut meta = CacheMeta::new("ascii-lowercase");
+        meta.add_source("data.txt", checksum(b"data"), 1234);
+
+        let mut binary = Vec::new();
+        write_binary_cache(&mut binary, &bstree, &meta).unwrap();
+        let json = cache_to_json(&bstree, &meta, CacheFormat::Compact).unwrap();
+        assert!(binary.len() < json.len());
+
+        let (from_binary, binary_meta) = read_binary_cache(binary.as_slice()).unwrap();
+        let (from_json, json_meta) = cache_from_json(&json).unwrap();
+        assert!(from_binary.iter().eq(from_json.iter()));
+        assert!(from_binary.values().eq(from_json.values()));
+        assert_eq!(from_binary.height(), from_json.height());
+        assert_eq!(binary_meta.sources, json_meta.sources);
+        assert_eq!(binary_meta.tokenizer, json_meta.tokenizer);
+        assert_eq!(binary_meta.total_tokens, json_meta.total_tokens);
+
+        // 改动任意一个字节或截断文件都会被发现
+        let mut corrupted = binary.clone();
+        let mid = corrupted.len() / 2;
+        corrupted[mid] ^= 0x01;
+        assert!(read_binary_cache(corrupted.as_slice()).is_err());
+        let err = read_binary_cache(&binary[..binary.len() - 3]).unwrap_err();
+        assert!(err.to_string().contains("截断"), "{err}");
+        assert!(read_binary_cache(&b"{}"[..]).is_err());
+
+        // 文件读写，并且能自动识别二进制和 JSON 格式
+        save_binary_cache("target/test_cache.bin", &bstree, &meta).unwrap();
+        let (from_file, _) = load_cache_file("target/test_cache.bin").unwrap();
+        assert!(from_file.iter().eq(bstree.iter()));
+        let (from_json_file, _) = load_cache_file("words.json").unwrap();
+        assert!(from_json_file.iter().eq(bstree.iter()));
+    }
+
+    #[test]
+    fn test_varint() {
+        let read = |bytes: &[u8]| {
+            let mut reader = HashReader {
+                inner: bytes,
+                hasher: Fnv64::new(),
+            };
+            reader.read_varint()
+        };
+        for value in [0, 1, 127, 128, 300, u64::from(u32::MAX), u64::MAX] {
+            let mut bytes = Vec::new();
+            let mut writer = HashWriter {
+                inner: &mut bytes,
+                hasher: Fnv64::new(),
+            };
+            writer.write_varint(value).unwrap();
+            assert_eq!(read(&bytes).unwrap(), value);
+        }
+        // 第 10 个字节超出 64 位的部分不能被丢弃
+        let mut overlong = [0xff; 10];
+        overlong[9] = 0x01;
+        assert_eq!(read(&overlong).unwrap(), u64::MAX);
+        overlong[9] = 0x02;
+        assert!(read(&overlong).is_err());
+        overlong[9] = 0x7f;
+        assert!(read(&overlong).is_err());
+        assert!(read(&[0xff; 11]).is_err());
+    }
+
+    #[test]
+    fn test_binary_forms() {
+        let mut bstree: BSTMap<String, WordStats> = BSTMap::new();
+        for form in ["killed", "killing", "killed"] {
+            let kill = bstree.entry("kill".to_string()).or_default();
+            kill.add();
+            kill.add_form(form);
+        }
+        let word = bstree.entry("idf".to_string()).or_default();
+        word.add();
+        word.add_variant("IDF");
+        let position = Position {
+            doc: 1,
+            line: 300,
+            column: 12,
+            offset: 70000,
+            len: 3,
+        };
+        word.add_position(position);
+        let mut meta = CacheMeta::new("ascii-lowercase");
+        meta.normalizer = Some("porter".to_string());
+        meta.case_variants = true;
+        meta.positions = true;
+        let mut binary = Vec::new();
+        write_binary_cache(&mut binary, &bstree, &meta).unwrap();
+        let (loaded, loaded_meta) = read_binary_cache(binary.as_slice()).unwrap();
+        assert_eq!(loaded_meta.normalizer.as_deref(), Some("porter"));
+        assert!(loaded_meta.case_variants);
+        assert!(loaded_meta.positions);
+        let idf = loaded.get("idf").unwrap();
+        assert_eq!(idf.variants_summary("idf").as_deref(), Some("IDF 1"));
+        assert_eq!(idf.positions(), [position]);
+        let kill = loaded.tree().iter().nth(1).unwrap();
+        assert_eq!(kill.to_string(), "kill: 3 (killed 2, killing 1)");
+        assert!(kill.value.positions().is_empty());
+
+        // 版本 2 的二进制缓存没有归并方式和原始形式
+        let mut v2 = BINARY_MAGIC.to_vec();
+        let mut writer = HashWriter {
+            inner: &mut v2,
+            hasher: Fnv64::new(),
+        };
+        for value in [2, 0, 0] {
+            writer.write_varint(value).unwrap();
+        }
+        writer.write_str("ascii-lowercase").unwrap();
+        writer.write_varint(1).unwrap();
+        writer.write_varint(1).unwrap();
+        writer.write_str("apple").unwrap();
+        writer.write_varint(1).unwrap();
+        let hash = writer.hasher.finish();
+        v2.extend_from_slice(&hash.to_le_bytes());
+        let (loaded, loaded_meta) = read_binary_cache(v2.as_slice()).unwrap();
+        assert_eq!(loaded_meta.version, 2);
+        assert_eq!(loaded_meta.normalizer, None);
+        assert_eq!(loaded.tree().iter().next().unwrap().to_string(), "apple: 1");
+    }
+}
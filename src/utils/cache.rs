@@ -0,0 +1,691 @@
+//! # 缓存模块
+//!
+//! 该模块负责单词树缓存的序列化和加载，包括树形格式、紧凑格式，以及记录版本和校验和的缓存文件头。
+//!
+//! ## 缓存文件头
+//!
+//! `cache_to_json` 写出的缓存文件结构如下，其中 `payload` 为树形或紧凑格式的单词树，
+//! `checksum` 为 `payload` 原始文本的 FNV-1a 64 位校验和：
+//!
+//! ```json
+//! {
+//!   "version": 5,
+//!   "created": 1702800000,
+//!   "sources": [{"path": "data.txt", "hash": "fnv1a64:…", "tokens": 1234}],
+//!   "tokenizer": "ascii",
+//!   "normalizer": "porter",
+//!   "case_variants": true,
+//!   "positions": true,
+//!   "total_tokens": 1234,
+//!   "format": "compact",
+//!   "checksum": "fnv1a64:…",
+//!   "payload": [["a", 12], …]
+//! }
+//! ```
+//!
+//! `normalizer` 为词干提取或词形还原的方式，未启用时省略。启用时紧凑格式的单词带有第三个元素，
+//! 记录归并到该单词的原始形式，如 `["kill", 5, {"killed": 3, "killing": 2}]`。
+//! `case_variants` 表示记录了单词在原文中的大小写写法，未启用时省略；启用时紧凑格式的单词带有第四个元素，
+//! 如 `["israeli", 8, {}, {"Israeli": 5, "israeli": 2, "ISRAELI": 1}]`。
+//! `positions` 表示记录了单词每次出现的位置，未启用时省略；启用时紧凑格式的单词带有第五个元素，
+//! 每个位置为 `[文档编号, 行号, 列号, 字节偏移, 字节长度]`，如 `["idf", 2, {}, {}, [[0, 1, 5, 4, 3], [1, 3, 1, 80, 3]]]`，
+//! 文档编号为 `sources` 中的下标。
+//!
+//! 没有文件头的旧缓存（版本 1）和版本 2 到 4 的缓存仍可由 `cache_from_json` 加载，重新保存后即升级为当前版本。
+// 引入bst模块中的BSTree结构体，map模块中的BSTMap和KeyValue结构体，以及r#type模块中的Balance枚举类型和Word、WordStats结构体
+use crate::bst::BSTree;
+use crate::map::{BSTMap, KeyValue};
+use crate::r#type::{Balance, Position, Word, WordStats};
+// 引入anyhow库中的Error类型和bail宏，它们用于表示和返回错误
+use anyhow::{bail, Error};
+// 引入serde库中的反序列化和序列化trait，用于缓存的读写
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+// 引入serde_json库中的RawValue类型，它用于原样保存payload的文本以便计算校验和
+use serde_json::value::RawValue;
+// 引入std库中的BTreeMap类型，它用于读写单词的原始形式
+use std::collections::BTreeMap;
+// 引入std库中的time模块，它用于获取缓存的创建时间
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// 当前缓存文件头的版本号。
+pub const CACHE_VERSION: u32 = 5;
+
+/// 没有文件头的旧缓存视为的版本号。
+const LEGACY_VERSION: u32 = 1;
+
+/// 最早带有文件头的版本号，版本 2 没有记录归并方式和原始形式。
+pub(crate) const HEADER_VERSION: u32 = 2;
+
+/// 开始记录归并方式和原始形式的版本号。
+pub(crate) const FORMS_VERSION: u32 = 3;
+
+/// 开始记录大小写写法的版本号。
+pub(crate) const VARIANTS_VERSION: u32 = 4;
+
+/// 开始记录出现位置的版本号。
+pub(crate) const POSITIONS_VERSION: u32 = 5;
+
+/// 将二叉搜索树序列化为 JSON 字符串（前序序列格式，不受树高限制）。
+///
+/// # 参数
+///
+/// * `bstree`: 要序列化的二叉搜索树。
+///
+/// # 返回
+///
+/// 返回 `Result<String, Error>` 类型。如果成功，返回 JSON 字符串；否则返回 `Error`，表示错误原因。
+pub fn tree_to_json<T: Serialize>(bstree: &BSTree<T>) -> Result<String, Error> {
+    Ok(serde_json::to_string(bstree)?)
+}
+
+/// 从 JSON 字符串加载二叉搜索树，同时支持前序序列格式和旧版本的嵌套结点格式。
+///
+/// 旧版本的嵌套格式每一层结点都是一层 JSON 对象，因此解析时关闭了 serde_json 的 128 层递归限制，
+/// 并在栈空间不足时自动扩展栈，使很深的旧缓存也能正常加载。
+///
+/// # 参数
+///
+/// * `json`: JSON 字符串。
+///
+/// # 返回
+///
+/// 返回 `Result<BSTree<T>, Error>` 类型。如果成功，返回二叉搜索树；否则返回 `Error`，表示错误原因。
+pub fn tree_from_json<T: DeserializeOwned + Ord>(json: &str) -> Result<BSTree<T>, Error> {
+    Ok(from_json_unbounded(json)?)
+}
+
+/// 关闭递归限制并按需扩展栈来解析 JSON，用于可能嵌套很深的旧缓存。
+fn from_json_unbounded<T: DeserializeOwned>(json: &str) -> serde_json::Result<T> {
+    let mut deserializer = serde_json::Deserializer::from_str(json);
+    deserializer.disable_recursion_limit();
+    let deserializer = serde_stacker::Deserializer::new(&mut deserializer);
+    T::deserialize(deserializer)
+}
+
+/// 表示单词缓存文件的格式。
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum CacheFormat {
+    /// 树形格式：`{"balance": .., "preorder": [..]}`，保留树的形状
+    Tree,
+    /// 紧凑格式：按单词排序的 `[["word", count], ..]` 数组，加载时重建为平衡树
+    Compact,
+}
+
+/// 紧凑格式中的一个单词，记录了原始形式时带有第三个元素，记录了大小写写法时带有第四个元素，
+/// 记录了出现位置时带有第五个元素。
+#[derive(Serialize, Deserialize)]
+#[serde(untagged)]
+enum CompactEntry<S> {
+    Plain(S, u32),
+    Forms(S, u32, BTreeMap<String, u32>),
+    Variants(S, u32, BTreeMap<String, u32>, BTreeMap<String, u32>),
+    Positions(
+        S,
+        u32,
+        BTreeMap<String, u32>,
+        BTreeMap<String, u32>,
+        Vec<Position>,
+    ),
+}
+
+/// 按树形格式序列化单词计数映射的包装，每个结点借用键和统计信息，按 `Word` 的格式输出，不需要复制整棵树。
+struct TreeWords<'a>(&'a BSTMap<String, WordStats>);
+
+/// 借用键和统计信息的单词，序列化结果与 `Word` 相同。
+#[derive(Serialize)]
+struct WordRef<'a> {
+    value: &'a str,
+    #[serde(flatten)]
+    stats: &'a WordStats,
+}
+
+impl Serialize for TreeWords<'_> {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.0.tree().serialize_with(serializer, |kv| WordRef {
+            value: &kv.key,
+            stats: &kv.value,
+        })
+    }
+}
+
+/// 将单词计数映射按指定格式序列化为 JSON 字符串。
+///
+/// 树形格式中的每个结点按 `Word` 的格式输出，与之前的版本兼容。
+///
+/// # 参数
+///
+/// * `words`: 要序列化的单词计数映射。
+/// * `format`: 缓存格式。
+///
+/// # 返回
+///
+/// 返回 `Result<String, Error>` 类型。如果成功，返回 JSON 字符串；否则返回 `Error`，表示错误原因。
+pub fn words_to_json(
+    words: &BSTMap<String, WordStats>,
+    format: CacheFormat,
+) -> Result<String, Error> {
+    match format {
+        CacheFormat::Tree => Ok(serde_json::to_string(&TreeWords(words))?),
+        CacheFormat::Compact => {
+            let entries: Vec<CompactEntry<&str>> = words
+                .iter()
+                .map(|(value, stats)| {
+                    let value = value.as_str();
+                    if !stats.positions().is_empty() {
+                        CompactEntry::Positions(
+                            value,
+                            stats.count(),
+                            stats.forms().clone(),
+                            stats.variants().clone(),
+                            stats.positions().to_vec(),
+                        )
+                    } else if !stats.variants().is_empty() {
+                        CompactEntry::Variants(
+                            value,
+                            stats.count(),
+                            stats.forms().clone(),
+                            stats.variants().clone(),
+                        )
+                    } else if !stats.forms().is_empty() {
+                        CompactEntry::Forms(value, stats.count(), stats.forms().clone())
+                    } else {
+                        CompactEntry::Plain(value, stats.count())
+                    }
+                })
+                .collect();
+            Ok(serde_json::to_string(&entries)?)
+        }
+    }
+}
+
+/// 从 JSON 字符串加载单词计数映射，根据内容自动识别格式。
+///
+/// 以 `[` 开头的视为紧凑格式，单词会按字典序排序、合并重复项（次数相加时截断到 `u32::MAX`）后构建为 AVL 平衡树；
+/// 以 `{` 开头的视为树形格式（包括旧版本的嵌套结点格式），交给 `tree_from_json` 处理，并保留树的形状。
+///
+/// # 参数
+///
+/// * `json`: JSON 字符串。
+///
+/// # 返回
+///
+/// 返回 `Result<BSTMap<String, WordStats>, Error>` 类型。如果成功，返回单词计数映射；否则返回 `Error`，表示错误原因。
+pub fn words_from_json(json: &str) -> Result<BSTMap<String, WordStats>, Error> {
+    match detect_format(json) {
+        Some(CacheFormat::Compact) => {
+            let entries: Vec<CompactEntry<String>> = serde_json::from_str(json)?;
+            let mut parsed: Vec<(String, WordStats)> = entries
+                .into_iter()
+                .map(|entry| match entry {
+                    CompactEntry::Plain(value, count) => (value, WordStats::with_count(count)),
+                    CompactEntry::Forms(value, count, forms) => {
+                        (value, WordStats::with_count(count).with_forms(forms))
+                    }
+                    CompactEntry::Variants(value, count, forms, variants) => (
+                        value,
+                        WordStats::with_count(count)
+                            .with_forms(forms)
+                            .with_variants(variants),
+                    ),
+                    CompactEntry::Positions(value, count, forms, variants, positions) => (
+                        value,
+                        WordStats::with_count(count)
+                            .with_forms(forms)
+                            .with_variants(variants)
+                            .with_positions(positions),
+                    ),
+                })
+                .collect();
+            parsed.sort_by(|a, b| a.0.cmp(&b.0));
+            let mut words: Vec<(String, WordStats)> = Vec::with_capacity(parsed.len());
+            for (value, stats) in parsed {
+                match words.last_mut() {
+                    Some(last) if last.0 == value => last.1.merge(stats),
+                    _ => words.push((value, stats)),
+                }
+            }
+            BSTMap::from_sorted(words, Balance::Avl).map_err(Error::msg)
+        }
+        Some(CacheFormat::Tree) => {
+            let tree: BSTree<Word> = tree_from_json(json)?;
+            Ok(BSTMap::from_tree(tree.map_into(KeyValue::from)))
+        }
+        None => Err(Error::msg("无法识别的缓存格式：内容既不是数组也不是对象")),
+    }
+}
+
+/// 根据第一个非空白字符判断缓存的格式。
+pub fn detect_format(json: &str) -> Option<CacheFormat> {
+    match json.trim_start().chars().next() {
+        Some('[') => Some(CacheFormat::Compact),
+        Some('{') => Some(CacheFormat::Tree),
+        _ => None,
+    }
+}
+
+/// 表示构建单词树所用的一个文本来源。
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct SourceInfo {
+    /// 文件路径，直接输入的文本为 `<input>`
+    pub path: String,
+    /// 文本内容的校验和
+    pub hash: String,
+    /// 从该来源读入的单词数
+    pub tokens: u64,
+}
+
+/// 表示缓存文件头中的元数据。
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CacheMeta {
+    /// 缓存格式的版本号，旧的无文件头缓存为 1
+    pub version: u32,
+    /// 创建时间（Unix 时间戳，秒）
+    pub created: u64,
+    /// 构建单词树所用的文本来源
+    pub sources: Vec<SourceInfo>,
+    /// 分词设置
+    pub tokenizer: String,
+    /// 词干提取或词形还原的方式，未启用时为 `None`
+    pub normalizer: Option<String>,
+    /// 是否记录单词在原文中的大小写写法
+    pub case_variants: bool,
+    /// 是否记录单词每次出现的位置
+    pub positions: bool,
+    /// 读入的单词总数（包括重复出现的单词）
+    pub total_tokens: u64,
+}
+
+impl CacheMeta {
+    /// 创建一个当前版本、没有任何来源的元数据实例。
+    pub fn new(tokenizer: &str) -> CacheMeta {
+        CacheMeta {
+            version: CACHE_VERSION,
+            created: now(),
+            sources: Vec::new(),
+            tokenizer: tokenizer.to_string(),
+            normalizer: None,
+            case_variants: false,
+            positions: false,
+            total_tokens: 0,
+        }
+    }
+
+    /// 记录一个文本来源、其内容的校验和（见 `checksum`）及其读入的单词数。
+    pub fn add_source(&mut self, path: &str, hash: String, tokens: u64) {
+        self.sources.push(SourceInfo {
+            path: path.to_string(),
+            hash,
+            tokens,
+        });
+        self.total_tokens += tokens;
+    }
+}
+
+/// 缓存文件的结构，`P` 为 payload 的类型。
+#[derive(Serialize, Deserialize)]
+struct Envelope<P> {
+    version: u32,
+    created: u64,
+    sources: Vec<SourceInfo>,
+    tokenizer: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    normalizer: Option<String>,
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    case_variants: bool,
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    positions: bool,
+    total_tokens: u64,
+    format: CacheFormat,
+    checksum: String,
+    payload: P,
+}
+
+/// 只读取版本号，用于区分带文件头的缓存和旧缓存。
+#[derive(Deserialize)]
+struct Probe {
+    version: Option<u32>,
+}
+
+/// 获取当前的 Unix 时间戳（秒）。
+pub(crate) fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |d| d.as_secs())
+}
+
+/// FNV-1a 64 位哈希，可以分多次输入数据，用于流式计算校验和。
+pub(crate) struct Fnv64(u64);
+
+impl Fnv64 {
+    pub(crate) fn new() -> Fnv64 {
+        Fnv64(0xcbf2_9ce4_8422_2325)
+    }
+
+    pub(crate) fn update(&mut self, data: &[u8]) {
+        for byte in data {
+            self.0 ^= u64::from(*byte);
+            self.0 = self.0.wrapping_mul(0x0100_0000_01b3);
+        }
+    }
+
+    pub(crate) fn finish(&self) -> u64 {
+        self.0
+    }
+}
+
+/// 计算数据的 FNV-1a 64 位校验和，返回形如 `fnv1a64:0123456789abcdef` 的字符串。
+pub fn checksum(data: &[u8]) -> String {
+    let mut hasher = Fnv64::new();
+    hasher.update(data);
+    format!("fnv1a64:{:016x}", hasher.finish())
+}
+
+/// 将单词树连同文件头一起序列化为 JSON 字符串。
+///
+/// 文件头中的版本号总是写为 `CACHE_VERSION`，创建时间为保存时的时间。
+///
+/// # 参数
+///
+/// * `words`: 要序列化的单词计数映射。
+/// * `meta`: 缓存的元数据。
+/// * `format`: payload 的格式。
+///
+/// # 返回
+///
+/// 返回 `Result<String, Error>` 类型。如果成功，返回 JSON 字符串；否则返回 `Error`，表示错误原因。
+pub fn cache_to_json(
+    words: &BSTMap<String, WordStats>,
+    meta: &CacheMeta,
+    format: CacheFormat,
+) -> Result<String, Error> {
+    let payload = RawValue::from_string(words_to_json(words, format)?)?;
+    let envelope = Envelope {
+        version: CACHE_VERSION,
+        created: now(),
+        sources: meta.sources.clone(),
+        tokenizer: meta.tokenizer.clone(),
+        normalizer: meta.normalizer.clone(),
+        case_variants: meta.case_variants,
+        positions: meta.positions,
+        total_tokens: meta.total_tokens,
+        format,
+        checksum: checksum(payload.get().as_bytes()),
+        payload: &payload,
+    };
+    Ok(serde_json::to_string(&envelope)?)
+}
+
+/// 加载缓存，校验文件头后返回单词计数映射和元数据。
+///
+/// 带文件头的缓存会检查版本号和 payload 的校验和，版本 2 到 4 的缓存结构相同，只是没有归并方式、原始形式、大小写写法或出现位置；不匹配时返回说明原因的错误；
+/// 没有文件头的旧缓存（树形、紧凑或嵌套结点格式）会被直接加载，元数据的版本号为 1，
+/// 单词总数取各单词出现次数之和。
+///
+/// # 参数
+///
+/// * `json`: 缓存文件的内容。
+///
+/// # 返回
+///
+/// 返回 `Result<(BSTMap<String, WordStats>, CacheMeta), Error>` 类型。如果成功，返回单词计数映射和元数据；否则返回 `Error`，表示错误原因。
+pub fn cache_from_json(json: &str) -> Result<(BSTMap<String, WordStats>, CacheMeta), Error> {
+    let version = match detect_format(json) {
+        Some(CacheFormat::Tree) => {
+            from_json_unbounded::<Probe>(json)
+                .map_err(|e| Error::msg(format!("缓存文件不完整或不是合法的 JSON: {e}")))?
+                .version
+        }
+        _ => None,
+    };
+    let Some(version) = version else {
+        let words = words_from_json(json)?;
+        let meta = CacheMeta {
+            version: LEGACY_VERSION,
+            created: 0,
+            sources: Vec::new(),
+            tokenizer: "unknown".to_string(),
+            normalizer: None,
+            case_variants: false,
+            positions: false,
+            total_tokens: words.values().map(|w| u64::from(w.count())).sum(),
+        };
+        return Ok((words, meta));
+    };
+    if version > CACHE_VERSION {
+        bail!("缓存版本为 {version}，高于程序支持的版本 {CACHE_VERSION}，请升级程序后再加载");
+    }
+    if version < HEADER_VERSION {
+        bail!("缓存版本 {version} 不应带有文件头，文件可能被手工修改");
+    }
+    let envelope: Envelope<Box<RawValue>> =
+        serde_json::from_str(json).map_err(|e| Error::msg(format!("缓存文件头格式错误: {e}")))?;
+    let actual = checksum(envelope.payload.get().as_bytes());
+    if actual != envelope.checksum {
+        bail!(
+            "缓存校验失败: 文件头记录的校验和为 {}，实际为 {actual}，文件可能被截断或手工修改",
+            envelope.checksum
+        );
+    }
+    let payload = envelope.payload.get();
+    if detect_format(payload) != Some(envelope.format) {
+        bail!("缓存文件头声明的格式与 payload 不符");
+    }
+    let words = words_from_json(payload)?;
+    let meta = CacheMeta {
+        version,
+        created: envelope.created,
+        sources: envelope.sources,
+        tokenizer: envelope.tokenizer,
+        normalizer: envelope.normalizer,
+        case_variants: envelope.case_variants,
+        positions: envelope.positions,
+        total_tokens: envelope.total_tokens,
+    };
+    Ok((words, meta))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::read_from_file;
+
+    #[test]
+    fn test_tree_json() {
+        // words.json 为旧版本的嵌套格式缓存
+        let legacy: BSTree<Word> = tree_from_json(&read_from_file("words.json").unwrap()).unwrap();
+        assert!(legacy.iter().is_sorted());
+        let json = tree_to_json(&legacy).unwrap();
+        let loaded: BSTree<Word> = tree_from_json(&json).unwrap();
+        assert!(loaded.pre_order().eq(legacy.pre_order()));
+
+        // 手工拼出深度超过递归限制的旧格式，仍能加载
+        let depth = 5000;
+        let mut nested = "null".to_string();
+        for i in 0..depth {
+            nested = format!(r#"{{"data":{i},"left":{nested},"right":null}}"#);
+        }
+        let deep: BSTree<i32> = tree_from_json(&format!(r#"{{"root":{nested}}}"#)).unwrap();
+        assert_eq!(deep.height(), depth);
+        assert!(deep.iter().copied().eq(0..depth as i32));
+    }
+
+    #[test]
+    fn test_compact_json() {
+        let legacy = words_from_json(&read_from_file("words.json").unwrap()).unwrap();
+        let compact = words_to_json(&legacy, CacheFormat::Compact).unwrap();
+        let tree = words_to_json(&legacy, CacheFormat::Tree).unwrap();
+        assert!(compact.starts_with(r#"[["a","#));
+        assert!(compact.len() < tree.len());
+
+        let loaded = words_from_json(&compact).unwrap();
+        assert_eq!(loaded.tree().balance, Balance::Avl);
+        assert!(loaded.height() < legacy.height());
+        let counts = |t: &BSTMap<String, WordStats>| -> Vec<(String, u32)> {
+            t.iter().map(|(k, w)| (k.clone(), w.count())).collect()
+        };
+        assert_eq!(counts(&loaded), counts(&legacy));
+        assert_eq!(counts(&words_from_json(&tree).unwrap()), counts(&legacy));
+
+        // 手工编辑过的紧凑缓存：乱序和重复项会被排序、合并
+        let edited = words_from_json(r#" [["pear", 2], ["apple", 1], ["pear", 3]]"#).unwrap();
+        assert_eq!(
+            counts(&edited),
+            [("apple".to_string(), 1), ("pear".to_string(), 5)]
+        );
+        // 重复单词的次数相加时截断到 u32::MAX，不会溢出
+        let max = u32::MAX;
+        let edited = words_from_json(&format!(r#"[["pear",{max}],["pear",2]]"#)).unwrap();
+        assert_eq!(counts(&edited), [("pear".to_string(), max)]);
+        assert!(words_from_json("\"words\"").is_err());
+    }
+
+    #[test]
+    fn test_cache_envelope() {
+        let mut words: BSTMap<String, WordStats> = BSTMap::new();
+        for word in ["pear", "apple", "pear"] {
+            words.entry(word.to_string()).or_default().add();
+        }
+        let mut meta = CacheMeta::new("ascii");
+        meta.add_source("fruit.txt", checksum(b"pear apple pear"), 3);
+
+        for format in [CacheFormat::Compact, CacheFormat::Tree] {
+            let json = cache_to_json(&words, &meta, format).unwrap();
+            let (loaded, loaded_meta) = cache_from_json(&json).unwrap();
+            assert!(loaded.iter().eq(words.iter()));
+            assert_eq!(loaded.get("pear").unwrap().count(), 2);
+            assert_eq!(loaded_meta.version, CACHE_VERSION);
+            assert_eq!(loaded_meta.sources, meta.sources);
+            assert_eq!(loaded_meta.tokenizer, "ascii");
+            assert_eq!(loaded_meta.total_tokens, 3);
+        }
+
+        // 手工修改次数、截断文件、未来版本都会被拒绝
+        let json = cache_to_json(&words, &meta, CacheFormat::Compact).unwrap();
+        let edited = json.replace(r#"["pear",2]"#, r#"["pear",20]"#);
+        let err = cache_from_json(&edited).unwrap_err().to_string();
+        assert!(err.contains("校验失败"), "{err}");
+        assert!(cache_from_json(&json[..json.len() - 10]).is_err());
+        let current = format!(r#""version":{CACHE_VERSION}"#);
+        let future = json.replace(&current, &format!(r#""version":{}"#, CACHE_VERSION + 1));
+        let err = cache_from_json(&future).unwrap_err().to_string();
+        assert!(err.contains("高于程序支持的版本"), "{err}");
+
+        // 没有文件头的旧缓存按版本 1 加载
+        let (legacy, legacy_meta) =
+            cache_from_json(&read_from_file("words.json").unwrap()).unwrap();
+        assert_eq!(legacy_meta.version, 1);
+        assert_eq!(
+            legacy_meta.total_tokens,
+            legacy.values().map(|w| u64::from(w.count())).sum::<u64>()
+        );
+        let (_, compact_meta) = cache_from_json(r#"[["apple",1]]"#).unwrap();
+        assert_eq!(compact_meta.total_tokens, 1);
+
+        // 版本 2 的缓存没有归并方式，仍可加载
+        let v2 = json.replace(&current, r#""version":2"#);
+        let (_, v2_meta) = cache_from_json(&v2).unwrap();
+        assert_eq!(v2_meta.version, 2);
+        assert_eq!(v2_meta.normalizer, None);
+    }
+
+    #[test]
+    fn test_cache_forms() {
+        let mut words: BSTMap<String, WordStats> = BSTMap::new();
+        for (stem, form) in [("kill", "killed"), ("kill", "killing"), ("pear", "pear")] {
+            let stats = words.entry(stem.to_string()).or_default();
+            stats.add();
+            stats.add_form(form);
+        }
+        let mut meta = CacheMeta::new("ascii-lowercase");
+        meta.normalizer = Some("porter".to_string());
+
+        let compact = words_to_json(&words, CacheFormat::Compact).unwrap();
+        assert_eq!(
+            compact,
+            r#"[["kill",2,{"killed":1,"killing":1}],["pear",1,{"pear":1}]]"#
+        );
+        for format in [CacheFormat::Compact, CacheFormat::Tree] {
+            let json = cache_to_json(&words, &meta, format).unwrap();
+            let (loaded, loaded_meta) = cache_from_json(&json).unwrap();
+            assert_eq!(loaded_meta.normalizer.as_deref(), Some("porter"));
+            let kill = loaded.get("kill").unwrap();
+            assert_eq!(kill.count(), 2);
+            assert_eq!(kill.forms_summary("kill").unwrap(), "killed 1, killing 1");
+        }
+
+        // 记录了大小写写法时紧凑格式带有第四个元素
+        let idf = words.entry("idf".to_string()).or_default();
+        idf.add();
+        idf.add_variant("IDF");
+        meta.case_variants = true;
+        let compact = words_to_json(&words, CacheFormat::Compact).unwrap();
+        assert!(compact.contains(r#"["idf",1,{},{"IDF":1}]"#), "{compact}");
+        // 树形格式借用键和统计信息输出，结果与按 Word 输出相同
+        let owned = words.tree().map(|kv| Word::from(kv.clone()));
+        assert_eq!(
+            words_to_json(&words, CacheFormat::Tree).unwrap(),
+            tree_to_json(&owned).unwrap()
+        );
+        for format in [CacheFormat::Compact, CacheFormat::Tree] {
+            let json = cache_to_json(&words, &meta, format).unwrap();
+            let (loaded, loaded_meta) = cache_from_json(&json).unwrap();
+            assert!(loaded_meta.case_variants);
+            let idf = loaded.get("idf").unwrap();
+            assert_eq!(idf.preferred_variant(), Some("IDF"));
+            let kill = loaded.get("kill").unwrap();
+            assert!(kill.variants().is_empty());
+        }
+
+        // 紧凑格式中重复的单词会合并原始形式
+        let edited =
+            words_from_json(r#"[["kill",1,{"killed":1}],["kill",2,{"killed":1,"kills":1}]]"#)
+                .unwrap();
+        let kill = edited.tree().iter().next().unwrap();
+        assert_eq!(kill.to_string(), "kill: 3 (killed 2, kills 1)");
+
+        // 重复单词的次数相加时截断到 u32::MAX，不会溢出
+        let max = u32::MAX;
+        let edited = words_from_json(&format!(
+            r#"[["kill",{max},{{"killed":{max}}}],["kill",2,{{"killed":2}}]]"#
+        ))
+        .unwrap();
+        let kill = edited.get("kill").unwrap();
+        assert_eq!(kill.count(), max);
+        assert_eq!(kill.forms()["killed"], max);
+    }
+
+    #[test]
+    fn test_cache_positions() {
+        let at = |doc, line, column, offset| Position {
+            doc,
+            line,
+            column,
+            offset,
+            len: 3,
+        };
+        let mut words: BSTMap<String, WordStats> = BSTMap::new();
+        let idf = words.entry("idf".to_string()).or_default();
+        idf.add();
+        idf.add_position(at(0, 1, 5, 4));
+        let mut meta = CacheMeta::new("ascii-lowercase");
+        meta.positions = true;
+
+        let compact = words_to_json(&words, CacheFormat::Compact).unwrap();
+        assert_eq!(compact, r#"[["idf",1,{},{},[[0,1,5,4,3]]]]"#);
+        for format in [CacheFormat::Compact, CacheFormat::Tree] {
+            let json = cache_to_json(&words, &meta, format).unwrap();
+            let (loaded, loaded_meta) = cache_from_json(&json).unwrap();
+            assert!(loaded_meta.positions);
+            let idf = loaded.get("idf").unwrap();
+            assert_eq!(idf.positions(), [at(0, 1, 5, 4)]);
+        }
+
+        // 紧凑格式中重复的单词会合并出现位置
+        let edited =
+            words_from_json(r#"[["idf",1,{},{},[[0,1,5,4,3]]],["idf",1,{},{},[[1,2,1,9,3]]]]"#)
+                .unwrap();
+        let idf = edited.get("idf").unwrap();
+        assert_eq!(idf.positions(), [at(0, 1, 5, 4), at(1, 2, 1, 9)]);
+    }
+}
@@ -0,0 +1,382 @@
+//! # 中文分词模块
+//!
+//! 该模块基于词典的正向最大匹配实现中文（CJK）分词，用于统计中英文交替出现的文本（如 `data.txt`）中的中文词语。
+//!
+//! - `Segmenter::bundled`: 使用内置词典创建分词器，内置词典收录常用词语和 `data.txt` 中出现的专有名词。
+//! - `Segmenter::from_file`: 使用用户提供的词典文件创建分词器；`Segmenter::load_file` 可以在内置词典的基础上追加用户词典。
+//! - `Segmenter::segment`: 对一段连续的汉字做正向最大匹配，词典中没有的字按单字切分。
+//! - `Segmenter` 实现了 `Tokenizer`，对中英文混合的文本分词，汉字按词典切分，其余文字按 Unicode 单词边界切分并转为小写。
+//!
+//! 词典文件每行一个词，行中空白之后的内容（如词频、词性）会被忽略，以 `#` 开头的行为注释，因此也可以直接使用 jieba 格式的词典。
+//! 词典与内置词典不同时，分词器的名称带有 `;dict=` 加词典内容的哈希值，加载缓存时可以检查用户词典是否与统计时一致。
+//!
+//! ## 示例
+//!
+//! ```rust
+//! use bst_words::utils::{Segmenter, Tokenizer};
+//! let segmenter = Segmenter::bundled();
+//! assert_eq!(
+//!     segmenter.tokenize("以色列国防军官员周六表示 (IDF)"),
+//!     ["以色列国防军", "官员", "周六", "表示", "idf"]
+//! );
+//! ```
+// 引入标准库中的Cow类型，用于表示借用或拥有的词语
+use std::borrow::Cow;
+// 引入标准库中的HashSet类型，用于存储词典中的词语
+use std::collections::HashSet;
+// 引入标准库中的Range类型，用于表示连续汉字在原文中的字节范围
+use std::ops::Range;
+
+// 引入anyhow库中的Error类型，用于表示读取词典时的错误
+use anyhow::Error;
+// 引入unicode-segmentation库中的UnicodeSegmentation trait，用于切分汉字以外的文字
+use unicode_segmentation::UnicodeSegmentation;
+
+// 引入tokenize模块中的Tokenizer trait、Token结构体和lowercase、words函数，Segmenter作为中文分词器实现Tokenizer
+use super::tokenize::{lowercase, words, Token, Tokenizer};
+// 引入cache模块中的Fnv64哈希，用于在名称中记录词典的内容
+use super::cache::Fnv64;
+// 引入utils模块中的read_from_file函数，用于读取词典文件
+use super::read_from_file;
+
+/// 内置词典的内容，在编译时嵌入程序。
+const BUNDLED_DICT: &str = include_str!("cjk_dict.txt");
+
+/// 判断一个字符是否需要按词典切分，包括中日韩统一表意文字及其扩展区、兼容表意文字、平假名和 "々"、"〇" 等符号。
+pub fn is_cjk(c: char) -> bool {
+    matches!(c,
+        '\u{3005}'..='\u{3007}'
+        | '\u{3040}'..='\u{309F}'
+        | '\u{3400}'..='\u{4DBF}'
+        | '\u{4E00}'..='\u{9FFF}'
+        | '\u{F900}'..='\u{FAFF}'
+        | '\u{20000}'..='\u{2FA1F}'
+        | '\u{30000}'..='\u{3134F}')
+}
+
+/// 基于词典正向最大匹配的中文分词器。
+#[derive(Clone, Debug)]
+pub struct Segmenter {
+    /// 词典中的词语
+    words: HashSet<String>,
+    /// 词典中最长的词语包含的字符数，用于限制匹配的长度
+    max_chars: usize,
+    /// 词典是否与内置词典相同
+    bundled: bool,
+    /// 分词器的名称，词典与内置词典不同时包含词典内容的哈希值
+    name: String,
+}
+
+impl Default for Segmenter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Segmenter {
+    /// 使用内置词典时分词器的名称，记录在缓存文件头中。
+    pub const NAME: &'static str = "cjk-maxmatch";
+
+    /// 名称中词典哈希值的前缀，位于 `NAME` 之后。
+    pub const DICT_PREFIX: &'static str = ";dict=";
+
+    /// 创建一个空词典的分词器，所有汉字都按单字切分。
+    pub fn new() -> Self {
+        let mut segmenter = Segmenter {
+            words: HashSet::new(),
+            max_chars: 0,
+            bundled: false,
+            name: String::new(),
+        };
+        segmenter.update_name();
+        segmenter
+    }
+
+    /// 创建一个使用内置词典的分词器。
+    pub fn bundled() -> Self {
+        let mut segmenter = Self::new();
+        segmenter.load_dict(BUNDLED_DICT);
+        segmenter.bundled = true;
+        segmenter.update_name();
+        segmenter
+    }
+
+    /// 创建一个只使用指定词典文件的分词器。
+    ///
+    /// # 参数
+    ///
+    /// * `path`: 词典文件的路径。
+    ///
+    /// # 返回
+    ///
+    /// 如果读取成功，返回分词器，否则返回错误。
+    pub fn from_file(path: &str) -> Result<Self, Error> {
+        let mut segmenter = Self::new();
+        segmenter.load_file(path)?;
+        Ok(segmenter)
+    }
+
+    /// 将词典文件中的词语追加到当前词典。
+    ///
+    /// # 参数
+    ///
+    /// * `path`: 词典文件的路径。
+    ///
+    /// # 返回
+    ///
+    /// 如果读取成功，返回追加的词语数，否则返回错误。
+    pub fn load_file(&mut self, path: &str) -> Result<usize, Error> {
+        let dict = read_from_file(path)?;
+        Ok(self.load_dict(&dict))
+    }
+
+    /// 将词典文本中的词语追加到当前词典。
+    ///
+    /// 每行的第一个字段为词语，其后的内容会被忽略，空行和以 `#` 开头的行会被跳过。
+    ///
+    /// # 返回
+    ///
+    /// 返回新追加的词语数。
+    pub fn load_dict(&mut self, dict: &str) -> usize {
+        let before = self.words.len();
+        for line in dict.lines() {
+            let line = line.trim();
+            if line.starts_with('#') {
+                continue;
+            }
+            if let Some(word) = line.split_whitespace().next() {
+                self.add(word);
+            }
+        }
+        let added = self.words.len() - before;
+        if added > 0 {
+            self.bundled = false;
+            self.update_name();
+        }
+        added
+    }
+
+    /// 向词典中插入一个词语。
+    pub fn insert(&mut self, word: &str) {
+        if self.add(word) {
+            self.bundled = false;
+            self.update_name();
+        }
+    }
+
+    /// 向词典中插入一个词语，返回它是否是新的词语。
+    fn add(&mut self, word: &str) -> bool {
+        self.max_chars = self.max_chars.max(word.chars().count());
+        self.words.insert(word.to_string())
+    }
+
+    /// 根据词典更新分词器的名称，词典与内置词典不同时加上按字典序计算的哈希值。
+    fn update_name(&mut self) {
+        if self.bundled {
+            self.name = Self::NAME.to_string();
+            return;
+        }
+        let mut words: Vec<&str> = self.words.iter().map(String::as_str).collect();
+        words.sort_unstable();
+        let mut hasher = Fnv64::new();
+        for word in words {
+            hasher.update(word.as_bytes());
+            hasher.update(b"\n");
+        }
+        self.name = format!(
+            "{}{}{:016x}",
+            Self::NAME,
+            Self::DICT_PREFIX,
+            hasher.finish()
+        );
+    }
+
+    /// 判断词典中是否包含一个词语。
+    pub fn contains(&self, word: &str) -> bool {
+        self.words.contains(word)
+    }
+
+    /// 词典中的词语数。
+    pub fn len(&self) -> usize {
+        self.words.len()
+    }
+
+    /// 判断词典是否为空。
+    pub fn is_empty(&self) -> bool {
+        self.words.is_empty()
+    }
+
+    /// 对一段连续的汉字做正向最大匹配。
+    ///
+    /// 从左到右，每次取词典中能匹配的最长词语，没有匹配的词语时取一个字。
+    ///
+    /// # 参数
+    ///
+    /// * `run`: 要切分的文本，通常是一段不含标点的连续汉字。
+    ///
+    /// # 返回
+    ///
+    /// 返回切分出的词语，它们是 `run` 的切片。
+    pub fn segment<'a>(&self, run: &'a str) -> Vec<&'a str> {
+        // 记录每个字符的起始字节位置，末尾加上文本长度，方便按字符数取切片
+        let bounds: Vec<usize> = run
+            .char_indices()
+            .map(|(i, _)| i)
+            .chain([run.len()])
+            .collect();
+        let chars = bounds.len() - 1;
+        let mut words = Vec::new();
+        let mut i = 0;
+        while i < chars {
+            // 从最长的可能长度开始尝试，直到只剩一个字
+            let mut len = self.max_chars.min(chars - i).max(1);
+            while len > 1 && !self.contains(&run[bounds[i]..bounds[i + len]]) {
+                len -= 1;
+            }
+            words.push(&run[bounds[i]..bounds[i + len]]);
+            i += len;
+        }
+        words
+    }
+}
+
+impl Tokenizer for Segmenter {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// 对中英文混合的文本分词。
+    ///
+    /// 文本先按 Unicode 单词边界（UAX #29）切分，连续的汉字合并后用 `segment` 切分，
+    /// 其余至少包含一个字母的单词转为小写，标点、空白和纯数字会被丢弃。
+    fn tokenize<'a>(&self, text: &'a str) -> Vec<Cow<'a, str>> {
+        words(self.tokens(text))
+    }
+
+    fn tokens<'a>(&self, text: &'a str) -> Vec<Token<'a>> {
+        let segment = |run: Range<usize>| {
+            self.segment(&text[run])
+                .into_iter()
+                .map(|word| Token::within(text, word, Cow::Borrowed(word), true))
+        };
+        let mut tokens = Vec::new();
+        // 当前连续汉字的起止字节位置
+        let mut run = 0..0;
+        for (start, piece) in text.split_word_bound_indices() {
+            if piece.chars().all(is_cjk) {
+                if run.is_empty() {
+                    run = start..start;
+                }
+                run.end = start + piece.len();
+                continue;
+            }
+            // 遇到汉字以外的文字时，切分之前累积的连续汉字
+            tokens.extend(segment(run.clone()));
+            run = 0..0;
+            if piece.chars().any(char::is_alphabetic) {
+                tokens.push(Token::within(text, piece, lowercase(piece), true));
+            }
+        }
+        tokens.extend(segment(run));
+        tokens
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_segment() {
+        let mut segmenter = Segmenter::new();
+        // 空词典时按单字切分
+        assert_eq!(segmenter.segment("人质"), ["人", "质"]);
+        segmenter.load_dict("# 注释\n以色列 100 ns\n以色列国防军\n国防\n\n官员");
+        assert_eq!(segmenter.len(), 4);
+        // 取最长的匹配，词典中没有的字按单字切分
+        assert_eq!(
+            segmenter.segment("以色列国防军官员称"),
+            ["以色列国防军", "官员", "称"]
+        );
+        assert_eq!(segmenter.segment("以色列国防"), ["以色列", "国防"]);
+        assert!(segmenter.segment("").is_empty());
+    }
+
+    #[test]
+    fn test_cjk_tokenize() {
+        let segmenter = Segmenter::bundled();
+        // data.txt 中英文和中文交替出现，两种语言都能统计
+        assert_eq!(
+            segmenter.tokenize("What we know about the killing of 3 Israeli hostages by the IDF"),
+            [
+                "what", "we", "know", "about", "the", "killing", "of", "israeli", "hostages", "by",
+                "the", "idf"
+            ]
+        );
+        assert_eq!(
+            segmenter.tokenize("我们对以色列国防军杀害 3 名人质的了解"),
+            [
+                "我们",
+                "对",
+                "以色列国防军",
+                "杀害",
+                "名",
+                "人质",
+                "的",
+                "了解"
+            ]
+        );
+        assert_eq!(
+            segmenter
+                .tokenize("作者：Tamar Michaelis、Richard Allen Greene 和 Joshua Berlinger，CNN"),
+            [
+                "作者",
+                "tamar",
+                "michaelis",
+                "richard",
+                "allen",
+                "greene",
+                "和",
+                "joshua",
+                "berlinger",
+                "cnn"
+            ]
+        );
+        // 标点会切断连续的汉字
+        assert_eq!(
+            segmenter.tokenize("人质是谁？三名人质"),
+            ["人质", "是", "谁", "三", "名", "人质"]
+        );
+    }
+
+    #[test]
+    fn test_load_file() {
+        let path = "target/test_cjk_dict.txt";
+        std::fs::write(path, "三名\n名人\n").unwrap();
+        let user = Segmenter::from_file(path).unwrap();
+        assert_eq!(user.segment("三名人质"), ["三名", "人", "质"]);
+        // 在内置词典的基础上追加用户词典
+        let mut segmenter = Segmenter::bundled();
+        assert_eq!(segmenter.load_file(path).unwrap(), 2);
+        assert_eq!(segmenter.segment("三名人质"), ["三名", "人质"]);
+        assert!(Segmenter::from_file("target/no_such_dict.txt").is_err());
+
+        // 名称记录词典的内容，与加载顺序和词典文件的路径无关
+        assert_eq!(Segmenter::bundled().name(), Segmenter::NAME);
+        let name = segmenter.name().to_string();
+        assert!(name.starts_with("cjk-maxmatch;dict="), "{name}");
+        assert_ne!(user.name(), name);
+        let mut other = Segmenter::new();
+        other.load_dict("名人\n三名");
+        other.load_dict(BUNDLED_DICT);
+        assert_eq!(other.name(), name);
+        other.insert("人质");
+        assert_eq!(other.name(), name);
+        other.insert("三名人质");
+        assert_ne!(other.name(), name);
+        // 追加的词语都已在内置词典中时名称不变
+        let mut segmenter = Segmenter::bundled();
+        assert_eq!(segmenter.load_dict("人质\n以色列"), 0);
+        assert_eq!(segmenter.name(), Segmenter::NAME);
+    }
+}
@@ -0,0 +1,236 @@
+//! # 上下文索引模块
+//!
+//! 该模块根据单词记录的出现位置生成 KWIC（Keyword in Context，上下文关键词）索引行，即单词本身连同前后若干个词的原文。
+//!
+//! - `source_text`: 按文档编号重新读取缓存中记录的来源文件，并用记录的校验和确认文件没有被修改。
+//! - `Concordance::new`: 保存原文并用构建缓存时的分词器切分，记录每个词的字节范围，同一篇文档只需切分一次。
+//! - `Concordance::line`: 按出现位置的字节范围截取单词及其前后各 `width` 个词，返回 `KwicLine`。
+//! - `KwicLine`: 一条索引行，输出时原文中连续的空白（包括换行）合并为一个空格，单词用方括号标出。
+//!
+//! 启用位置记录时，`append_text` 还会用本模块中的 `LineCursor` 把单词的字节偏移换算为行号和列号。
+//!
+//! ## 示例
+//!
+//! ```rust
+//! use bst_words::utils::{AsciiTokenizer, Concordance};
+//! let text = "The IDF said\nIsraeli troops entered the area.".to_string();
+//! let concordance = Concordance::new(text, &AsciiTokenizer::new());
+//! let line = concordance.line(13..20, 2).unwrap();
+//! assert_eq!(line.keyword, "Israeli");
+//! assert_eq!(line.to_string(), "IDF said [Israeli] troops entered");
+//! ```
+// 引入标准库中的Display trait，用于输出索引行
+use std::fmt::Display;
+// 引入标准库中的fs模块，用于重新读取来源文件
+use std::fs;
+// 引入标准库中的Range类型，用于表示词语在原文中的字节范围
+use std::ops::Range;
+
+// 引入anyhow库中的Error类型和bail宏，用于表示和返回读取来源时的错误
+use anyhow::{bail, Error};
+
+// 引入cache模块中的缓存元数据和校验和计算，用于找到并校验来源文件
+use super::cache::{checksum, CacheMeta};
+// 引入tokenize模块中的Tokenizer trait，用于切分原文
+use super::tokenize::Tokenizer;
+
+/// 按文档编号读取缓存中记录的来源文件。
+///
+/// 直接输入的文本（`<input>`）和标准输入（`<stdin>`）没有保存原文，无法读取；
+/// 文件内容与记录的校验和不一致时，记录的位置已经不再有效，也会返回错误。
+///
+/// # 参数
+///
+/// * `meta`: 缓存的元数据。
+/// * `doc`: 文档编号，即来源在 `meta.sources` 中的下标。
+///
+/// # 返回
+///
+/// 返回 `Result<String, Error>` 类型。如果成功，返回文件内容；否则返回 `Error`，表示错误原因。
+pub fn source_text(meta: &CacheMeta, doc: u32) -> Result<String, Error> {
+    let Some(source) = meta.sources.get(doc as usize) else {
+        bail!("缓存中没有编号为 {doc} 的文档");
+    };
+    if source.path.starts_with('<') {
+        bail!("文档 {doc} 来自 {}，没有保存原文", source.path);
+    }
+    let text = fs::read_to_string(&source.path)
+        .map_err(|e| Error::msg(format!("无法读取文档 {doc}（{}）: {e}", source.path)))?;
+    if checksum(text.as_bytes()) != source.hash {
+        bail!(
+            "文档 {doc}（{}）在建立缓存后被修改，记录的位置已不再有效",
+            source.path
+        );
+    }
+    Ok(text)
+}
+
+/// 一篇文档的上下文索引，保存了原文和其中每个词的字节范围。
+pub struct Concordance {
+    text: String,
+    spans: Vec<Range<usize>>,
+}
+
+impl Concordance {
+    /// 用分词器切分原文，建立上下文索引。分词器应与构建缓存时使用的一致，这样上下文中的词与计数的词相同。
+    pub fn new(text: String, tokenizer: &dyn Tokenizer) -> Concordance {
+        let mut spans: Vec<Range<usize>> = tokenizer
+            .tokens(&text)
+            .into_iter()
+            .map(|token| token.span)
+            .filter(|span| !span.is_empty())
+            .collect();
+        // 展开的缩写等多个词共用同一个范围，只保留一个
+        spans.sort_by_key(|span| (span.start, span.end));
+        spans.dedup();
+        Concordance { text, spans }
+    }
+
+    /// 截取字节范围 `span` 处的单词及其前后各 `width` 个词。
+    ///
+    /// # 参数
+    ///
+    /// * `span`: 单词在原文中的字节范围，通常为 `Position::span()`。
+    /// * `width`: 前后各截取的词数，为 0 时只返回单词本身。
+    ///
+    /// # 返回
+    ///
+    /// 返回 `Option<KwicLine>` 类型。范围超出原文或不在字符边界上时返回 `None`。
+    pub fn line(&self, span: Range<usize>, width: usize) -> Option<KwicLine<'_>> {
+        let keyword = self.text.get(span.clone())?;
+        let before = self.spans.partition_point(|s| s.end <= span.start);
+        let start = if width == 0 || before == 0 {
+            span.start
+        } else {
+            self.spans[before.saturating_sub(width)].start
+        };
+        let after = self.spans.partition_point(|s| s.start < span.end);
+        let last = (after + width).min(self.spans.len());
+        let end = if last > after {
+            self.spans[last - 1].end
+        } else {
+            span.end
+        };
+        Some(KwicLine {
+            left: collapse(&self.text[start..span.start]),
+            keyword,
+            right: collapse(&self.text[span.end..end]),
+        })
+    }
+}
+
+/// 一条 KWIC 索引行。
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct KwicLine<'a> {
+    /// 单词之前的原文，连续的空白已合并为一个空格
+    pub left: String,
+    /// 单词在原文中的写法
+    pub keyword: &'a str,
+    /// 单词之后的原文，连续的空白已合并为一个空格
+    pub right: String,
+}
+
+impl Display for KwicLine<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}[{}]{}", self.left, self.keyword, self.right)
+    }
+}
+
+/// 把连续的空白（包括换行）合并为一个空格，使索引行保持在一行内。
+fn collapse(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    let mut space = false;
+    for c in s.chars() {
+        if c.is_whitespace() {
+            space = true;
+        } else {
+            if space {
+                out.push(' ');
+                space = false;
+            }
+            out.push(c);
+        }
+    }
+    if space {
+        out.push(' ');
+    }
+    out
+}
+
+/// 把按顺序给出的字节偏移换算为行号和列号（均从 1 开始，列号按字符计）。
+///
+/// 分词器按顺序返回单词，因此只需从上一次的位置继续向后扫描，整篇文档的换算是线性的；
+/// 偏移小于上一次时从头开始扫描。
+pub(crate) struct LineCursor<'a> {
+    text: &'a str,
+    offset: usize,
+    line: u32,
+    column: u32,
+}
+
+impl<'a> LineCursor<'a> {
+    pub(crate) fn new(text: &'a str) -> LineCursor<'a> {
+        LineCursor {
+            text,
+            offset: 0,
+            line: 1,
+            column: 1,
+        }
+    }
+
+    /// 返回字节偏移 `offset` 所在的行号和列号。
+    pub(crate) fn locate(&mut self, offset: usize) -> (u32, u32) {
+        if offset < self.offset {
+            *self = LineCursor::new(self.text);
+        }
+        for c in self.text[self.offset..offset].chars() {
+            if c == '\n' {
+                self.line += 1;
+                self.column = 1;
+            } else {
+                self.column += 1;
+            }
+        }
+        self.offset = offset;
+        (self.line, self.column)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::{AsciiTokenizer, Segmenter};
+
+    #[test]
+    fn test_line_cursor() {
+        let text = "first line\nsecond 以色列 line\n\nlast";
+        let mut cursor = LineCursor::new(text);
+        assert_eq!(cursor.locate(0), (1, 1));
+        assert_eq!(cursor.locate(6), (1, 7));
+        assert_eq!(cursor.locate(text.find("以色列").unwrap()), (2, 8));
+        assert_eq!(cursor.locate(text.find(" line\n\n").unwrap() + 1), (2, 12));
+        assert_eq!(cursor.locate(text.find("last").unwrap()), (4, 1));
+        // 偏移回退时从头扫描
+        assert_eq!(cursor.locate(11), (2, 1));
+    }
+
+    #[test]
+    fn test_concordance() {
+        let text = "The IDF said   Israeli troops\nentered the area. Israeli";
+        let concordance = Concordance::new(text.to_string(), &AsciiTokenizer::new());
+        let line = |span: Range<usize>, width| concordance.line(span, width).unwrap().to_string();
+        assert_eq!(line(15..22, 2), "IDF said [Israeli] troops entered");
+        assert_eq!(line(15..22, 0), "[Israeli]");
+        // 开头和结尾不足 width 个词时截取到原文的开头和结尾
+        assert_eq!(line(0..3, 3), "[The] IDF said Israeli");
+        assert_eq!(line(48..55, 2), "the area. [Israeli]");
+        assert!(concordance.line(48..60, 2).is_none());
+
+        // 中文按词典分词，上下文按词语计数
+        let text = "以色列国防军官员周六表示";
+        let concordance = Concordance::new(text.to_string(), &Segmenter::bundled());
+        let start = text.find("官员").unwrap();
+        let kwic = concordance.line(start..start + "官员".len(), 1).unwrap();
+        assert_eq!(kwic.to_string(), "以色列国防军[官员]周六");
+    }
+}
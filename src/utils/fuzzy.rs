@@ -0,0 +1,272 @@
+//! # 模糊查询模块
+//!
+//! 该模块按编辑距离在单词树中查找与查询词相近的单词，用于查询词拼错（如把 "hostage" 输成 "hostge"）时给出"您是不是要找"的建议。
+//!
+//! - `Metric`: 编辑距离的计算方式，`Levenshtein` 只允许插入、删除和替换，`Damerau` 还允许交换相邻的两个字符。
+//! - `edit_distance`: 按字符（而不是字节）计算两个字符串的编辑距离。
+//! - `fuzzy_search`: 返回树中与查询词编辑距离不超过 `max_distance` 的单词，按距离从小到大、出现次数从多到少排序。
+//!
+//! `fuzzy_search` 按字母序遍历整棵树，相邻单词共享的前缀只计算一次动态规划的行；某个前缀与查询词的距离已经超过上限时，
+//! 以它开头的单词都会被跳过，所以实际计算量远小于逐个单词计算编辑距离。
+//!
+//! ## 示例
+//!
+//! ```rust
+//! use bst_words::bst::BSTree;
+//! use bst_words::r#type::Word;
+//! use bst_words::utils::{fuzzy_search, Metric};
+//! let mut tree = BSTree::new();
+//! for (value, count) in [("hostage", 3), ("hostages", 18), ("host", 2), ("house", 7)] {
+//!     tree.insert_fn(Word::with_count(value.to_string(), count), |_| {});
+//! }
+//! let matches = fuzzy_search(&tree, "hostge", 2, Metric::Damerau, Word::count);
+//! let found: Vec<_> = matches.iter().map(|m| (m.item.value.as_str(), m.distance)).collect();
+//! assert_eq!(found, [("hostage", 1), ("hostages", 2), ("host", 2)]);
+//! ```
+// 引入标准库中的Borrow trait，用于把树中的数据当作字符串比较
+use std::borrow::Borrow;
+
+// 引入bst模块中的BSTree结构体，模糊查询在树上按字母序进行
+use crate::bst::BSTree;
+
+/// 编辑距离的计算方式。
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Metric {
+    /// Levenshtein 距离，每次插入、删除或替换一个字符计为 1
+    Levenshtein,
+    /// 限制型 Damerau-Levenshtein 距离（最优字符串对齐），交换相邻的两个字符也计为 1
+    #[default]
+    Damerau,
+}
+
+/// 模糊查询的一个结果。
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FuzzyMatch<'a, T> {
+    /// 树中的数据
+    pub item: &'a T,
+    /// 与查询词的编辑距离
+    pub distance: usize,
+}
+
+/// 计算两个字符串的编辑距离，按字符计算，"以色列" 与 "以列" 的距离为 1。
+///
+/// # 参数
+///
+/// * `a`, `b`: 要比较的两个字符串。
+/// * `metric`: 编辑距离的计算方式。
+///
+/// # 返回
+///
+/// 返回把 `a` 变为 `b` 所需的最少编辑次数。
+pub fn edit_distance(a: &str, b: &str, metric: Metric) -> usize {
+    let query: Vec<char> = b.chars().collect();
+    let mut rows = Rows::new(&query, metric);
+    for c in a.chars() {
+        rows.push(c);
+    }
+    rows.distance()
+}
+
+/// 在树中查找与查询词编辑距离不超过 `max_distance` 的数据。
+///
+/// # 参数
+///
+/// * `tree`: 要查找的树，数据按 `Borrow<str>` 得到的字符串参与比较。
+/// * `query`: 查询词。
+/// * `max_distance`: 允许的最大编辑距离。
+/// * `metric`: 编辑距离的计算方式。
+/// * `count`: 取得数据的出现次数，用于距离相同时排序。
+///
+/// # 返回
+///
+/// 返回 `Vec<FuzzyMatch<T>>`，按距离从小到大排序，距离相同时出现次数多的在前，再相同时按字母序。
+pub fn fuzzy_search<'a, T, F>(
+    tree: &'a BSTree<T>,
+    query: &str,
+    max_distance: usize,
+    metric: Metric,
+    count: F,
+) -> Vec<FuzzyMatch<'a, T>>
+where
+    T: Borrow<str>,
+    F: Fn(&T) -> u32,
+{
+    let query: Vec<char> = query.chars().collect();
+    let mut rows = Rows::new(&query, metric);
+    // 已计算的行对应的单词（的前缀）
+    let mut current: Vec<char> = Vec::new();
+    // 距离已超过上限的前缀长度，以 current[..dead] 开头的单词都可以跳过
+    let mut dead: Option<usize> = None;
+    let mut matches = Vec::new();
+    for item in tree {
+        let word: &str = item.borrow();
+        if let Some(dead) = dead {
+            if word.chars().take(dead).eq(current[..dead].iter().copied()) {
+                continue;
+            }
+        }
+        dead = None;
+        // 保留与上一个单词共同前缀的行，只计算其后的字符
+        let common = current
+            .iter()
+            .zip(word.chars())
+            .take_while(|(a, b)| *a == b)
+            .count();
+        rows.truncate(common);
+        current.truncate(common);
+        for c in word.chars().skip(common) {
+            current.push(c);
+            if rows.push(c) > max_distance {
+                dead = Some(current.len());
+                break;
+            }
+        }
+        if dead.is_none() && rows.distance() <= max_distance {
+            matches.push(FuzzyMatch {
+                item,
+                distance: rows.distance(),
+            });
+        }
+    }
+    // 稳定排序，距离和次数都相同的结果保持字母序
+    matches.sort_by_key(|m| (m.distance, std::cmp::Reverse(count(m.item))));
+    matches
+}
+
+/// 编辑距离动态规划的各行，第 i 行是单词的前 i 个字符与查询词各前缀的距离。
+///
+/// 由于每一行只依赖前面的行，按字母序遍历时可以保留相邻单词共同前缀的行。
+struct Rows<'q> {
+    query: &'q [char],
+    metric: Metric,
+    // 已加入的单词字符，交换相邻字符时需要比较前一个字符
+    chars: Vec<char>,
+    rows: Vec<Vec<usize>>,
+}
+
+impl<'q> Rows<'q> {
+    fn new(query: &'q [char], metric: Metric) -> Rows<'q> {
+        Rows {
+            query,
+            metric,
+            chars: Vec::new(),
+            rows: vec![(0..=query.len()).collect()],
+        }
+    }
+
+    /// 只保留单词前 `len` 个字符对应的行。
+    fn truncate(&mut self, len: usize) {
+        self.chars.truncate(len);
+        self.rows.truncate(len + 1);
+    }
+
+    /// 在单词末尾加入一个字符并计算新的一行，返回该行的最小值。
+    ///
+    /// 最小值不会随后续的行减小，超过上限时以当前前缀开头的单词都不可能符合条件。
+    fn push(&mut self, c: char) -> usize {
+        let i = self.rows.len();
+        let prev = &self.rows[i - 1];
+        let mut row = Vec::with_capacity(prev.len());
+        row.push(prev[0] + 1);
+        for (j, &q) in self.query.iter().enumerate().map(|(j, q)| (j + 1, q)) {
+            let cost = usize::from(c != q);
+            let mut value = (prev[j - 1] + cost).min(prev[j] + 1).min(row[j - 1] + 1);
+            if self.metric == Metric::Damerau
+                && i > 1
+                && j > 1
+                && c == self.query[j - 2]
+                && self.chars[i - 2] == q
+            {
+                value = value.min(self.rows[i - 2][j - 2] + 1);
+            }
+            row.push(value);
+        }
+        let min = row.iter().copied().min().unwrap_or(0);
+        self.chars.push(c);
+        self.rows.push(row);
+        min
+    }
+
+    /// 当前单词与整个查询词的距离。
+    fn distance(&self) -> usize {
+        self.rows[self.rows.len() - 1][self.query.len()]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::r#type::Word;
+
+    #[test]
+    fn test_edit_distance() {
+        assert_eq!(edit_distance("kitten", "sitting", Metric::Levenshtein), 3);
+        assert_eq!(edit_distance("", "abc", Metric::Levenshtein), 3);
+        assert_eq!(edit_distance("abc", "", Metric::Damerau), 3);
+        assert_eq!(edit_distance("same", "same", Metric::Damerau), 0);
+        // 交换相邻字符
+        assert_eq!(edit_distance("hostgae", "hostage", Metric::Levenshtein), 2);
+        assert_eq!(edit_distance("hostgae", "hostage", Metric::Damerau), 1);
+        // 最优字符串对齐不允许对交换过的字符再做编辑
+        assert_eq!(edit_distance("ca", "abc", Metric::Damerau), 3);
+        // 按字符而不是字节计算
+        assert_eq!(edit_distance("以色列", "以列", Metric::Levenshtein), 1);
+    }
+
+    #[test]
+    fn test_fuzzy_search() {
+        let mut tree = BSTree::new();
+        let words = [
+            ("hostage", 3),
+            ("hostages", 18),
+            ("host", 2),
+            ("hosts", 2),
+            ("house", 7),
+            ("ghost", 1),
+            ("kill", 5),
+        ];
+        for (value, count) in words {
+            tree.insert_fn(Word::with_count(value.to_string(), count), |_| {});
+        }
+        let search = |query: &str, max_distance, metric| {
+            fuzzy_search(&tree, query, max_distance, metric, Word::count)
+                .into_iter()
+                .map(|m| (m.item.value.as_str(), m.distance))
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(
+            search("hostge", 2, Metric::Damerau),
+            [("hostage", 1), ("hostages", 2), ("host", 2), ("hosts", 2)]
+        );
+        assert_eq!(search("hostge", 1, Metric::Damerau), [("hostage", 1)]);
+        // 距离相同时出现次数多的在前
+        assert_eq!(
+            search("hosts", 1, Metric::Levenshtein),
+            [("hosts", 0), ("host", 1)]
+        );
+        assert_eq!(search("huose", 1, Metric::Damerau), [("house", 1)]);
+        assert!(search("huose", 1, Metric::Levenshtein).is_empty());
+        assert!(search("xyz", 1, Metric::Damerau).is_empty());
+
+        // 与逐个单词计算编辑距离的结果一致
+        for query in ["", "h", "hots", "gohst", "kil", "hostagse", "xhost"] {
+            for max_distance in 0..4 {
+                let mut expected: Vec<_> = words
+                    .iter()
+                    .map(|&(value, count)| {
+                        (edit_distance(value, query, Metric::Damerau), count, value)
+                    })
+                    .filter(|&(distance, ..)| distance <= max_distance)
+                    .collect();
+                expected.sort_by_key(|&(distance, count, value)| {
+                    (distance, std::cmp::Reverse(count), value)
+                });
+                let expected: Vec<_> = expected
+                    .into_iter()
+                    .map(|(distance, _, value)| (value, distance))
+                    .collect();
+                assert_eq!(search(query, max_distance, Metric::Damerau), expected);
+            }
+        }
+    }
+}
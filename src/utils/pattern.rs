@@ -0,0 +1,262 @@
+//! # 模式查询模块
+//!
+//! 该模块按通配符或正则表达式在单词树中查找单词。
+//!
+//! - `Pattern::wildcard`: 通配符模式，`*` 匹配任意多个字符，`?` 匹配一个字符，其余字符按原样匹配，模式需要匹配整个单词。
+//! - `Pattern::regex`: 正则表达式，与 `Regex::is_match` 相同，只要单词中有一处匹配即可，需要匹配整个单词时用 `^` 和 `$` 锚定。
+//! - `Pattern::prefix`: 所有匹配的单词都具有的字面前缀，如 `kill*` 的 "kill"、`^hostage` 的 "hostage"。
+//! - `pattern_search`: 按字母序返回树中匹配模式的单词。
+//!
+//! 树按字母序排列，具有同一前缀的单词是连续的一段，所以 `pattern_search` 先用 `BSTree::prefix` 定位前缀对应的区间，
+//! 只对区间内的单词做匹配。没有字面前缀的模式（如 `*ing`、未锚定的正则表达式）需要检查所有单词。
+//!
+//! ## 示例
+//!
+//! ```rust
+//! use bst_words::bst::BSTree;
+//! use bst_words::r#type::Word;
+//! use bst_words::utils::{pattern_search, Pattern};
+//! let mut tree = BSTree::new();
+//! for value in ["hostage", "hostages", "hostile", "killed", "killing"] {
+//!     tree.insert_fn(Word::new(value.to_string()), |_| {});
+//! }
+//! let pattern = Pattern::wildcard("h?st?ge");
+//! assert_eq!(pattern.prefix(), "h");
+//! let found: Vec<_> = pattern_search(&tree, &pattern).map(|w| w.value.as_str()).collect();
+//! assert_eq!(found, ["hostage"]);
+//!
+//! let pattern = Pattern::regex("^kill(ed|ing)$").unwrap();
+//! assert_eq!(pattern.prefix(), "kill");
+//! assert_eq!(pattern_search(&tree, &pattern).count(), 2);
+//! ```
+// 引入标准库中的Borrow trait，用于把树中的数据当作字符串匹配
+use std::borrow::Borrow;
+
+// 引入anyhow库中的Error类型，用于表示无效的正则表达式
+use anyhow::Error;
+// 引入regex库中的Regex类型，通配符模式也会转换为正则表达式
+use regex::Regex;
+
+// 引入bst模块中的BSTree结构体，模式查询按前缀在树上定位区间
+use crate::bst::BSTree;
+
+/// 通配符模式或正则表达式，以及所有匹配的单词都具有的字面前缀。
+#[derive(Debug, Clone)]
+pub struct Pattern {
+    regex: Regex,
+    prefix: String,
+}
+
+impl Pattern {
+    /// 创建通配符模式，`*` 匹配任意多个字符（包括没有字符），`?` 匹配一个字符，模式需要匹配整个单词。
+    pub fn wildcard(pattern: &str) -> Pattern {
+        let mut source = String::from("^");
+        for c in pattern.chars() {
+            match c {
+                '*' => source.push_str(".*"),
+                '?' => source.push('.'),
+                c => source.push_str(&regex::escape(c.encode_utf8(&mut [0; 4]))),
+            }
+        }
+        source.push('$');
+        let prefix = pattern
+            .split(['*', '?'])
+            .next()
+            .unwrap_or_default()
+            .to_string();
+        Pattern {
+            regex: Regex::new(&source).expect("转义后的通配符模式总是有效的正则表达式"),
+            prefix,
+        }
+    }
+
+    /// 创建正则表达式模式，单词中有一处匹配即可。
+    ///
+    /// # 参数
+    ///
+    /// * `pattern`: 正则表达式，以 `^` 开头时其后的字面字符作为前缀，用于缩小查找的区间。
+    ///
+    /// # 返回
+    ///
+    /// 如果正则表达式有效，返回模式，否则返回错误。
+    pub fn regex(pattern: &str) -> Result<Pattern, Error> {
+        Ok(Pattern {
+            regex: Regex::new(pattern)?,
+            prefix: literal_prefix(pattern),
+        })
+    }
+
+    /// 所有匹配的单词都具有的字面前缀，没有时为空字符串。
+    pub fn prefix(&self) -> &str {
+        &self.prefix
+    }
+
+    /// 判断单词是否匹配模式。
+    pub fn is_match(&self, word: &str) -> bool {
+        self.regex.is_match(word)
+    }
+}
+
+/// 按字母序返回树中匹配模式的数据，只检查以模式的字面前缀开头的区间。
+///
+/// # 参数
+///
+/// * `tree`: 要查找的树，数据按 `Borrow<str>` 得到的字符串参与匹配。
+/// * `pattern`: 通配符模式或正则表达式。
+///
+/// # 返回
+///
+/// 返回按字母序产生匹配数据的迭代器。
+pub fn pattern_search<'a, 'p, T: Borrow<str>>(
+    tree: &'a BSTree<T>,
+    pattern: &'p Pattern,
+) -> impl DoubleEndedIterator<Item = &'a T> + 'p
+where
+    'a: 'p,
+{
+    tree.prefix(pattern.prefix())
+        .filter(move |item| pattern.is_match((*item).borrow()))
+}
+
+/// 取出以 `^` 开头的正则表达式中，所有匹配都必须以之开头的字面字符。
+///
+/// 只识别开头的普通字母、数字等字符，遇到分组、字符类、转义或其他元字符时停止；
+/// 后面跟着 `?`、`*` 或 `{` 的字符可能不出现，不计入前缀。最外层有 `|` 时各分支的前缀不同，不取前缀。
+fn literal_prefix(pattern: &str) -> String {
+    let Some(rest) = pattern.strip_prefix('^') else {
+        return String::new();
+    };
+    if has_top_level_alternation(rest) {
+        return String::new();
+    }
+    let mut prefix = String::new();
+    let mut chars = rest.chars().peekable();
+    while let Some(c) = chars.next() {
+        if is_meta(c) {
+            break;
+        }
+        match chars.peek() {
+            Some('?' | '*' | '{') => break,
+            Some('+') => {
+                prefix.push(c);
+                break;
+            }
+            _ => prefix.push(c),
+        }
+    }
+    prefix
+}
+
+/// 判断正则表达式的最外层（不在分组和字符类中）是否有 `|`。
+fn has_top_level_alternation(pattern: &str) -> bool {
+    let mut depth = 0usize;
+    let mut in_class = false;
+    let mut chars = pattern.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => {
+                chars.next();
+            }
+            '[' => in_class = true,
+            ']' => in_class = false,
+            _ if in_class => {}
+            '(' => depth += 1,
+            ')' => depth = depth.saturating_sub(1),
+            '|' if depth == 0 => return true,
+            _ => {}
+        }
+    }
+    false
+}
+
+/// 判断字符是否是正则表达式的元字符。
+fn is_meta(c: char) -> bool {
+    matches!(
+        c,
+        '\\' | '.' | '+' | '*' | '?' | '(' | ')' | '|' | '[' | ']' | '{' | '}' | '^' | '$'
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::r#type::Word;
+
+    #[test]
+    fn test_wildcard() {
+        let pattern = Pattern::wildcard("kill*");
+        assert_eq!(pattern.prefix(), "kill");
+        assert!(pattern.is_match("kill"));
+        assert!(pattern.is_match("killing"));
+        assert!(!pattern.is_match("skill"));
+
+        let pattern = Pattern::wildcard("*ing");
+        assert_eq!(pattern.prefix(), "");
+        assert!(pattern.is_match("killing"));
+        assert!(!pattern.is_match("kings"));
+
+        let pattern = Pattern::wildcard("h?st?ge");
+        assert_eq!(pattern.prefix(), "h");
+        assert!(pattern.is_match("hostage"));
+        assert!(!pattern.is_match("hostages"));
+
+        // 其余字符按原样匹配，包括正则表达式的元字符
+        let pattern = Pattern::wildcard("u.s.*");
+        assert_eq!(pattern.prefix(), "u.s.");
+        assert!(pattern.is_match("u.s.a"));
+        assert!(!pattern.is_match("uxsy"));
+        assert!(Pattern::wildcard("以色?").is_match("以色列"));
+    }
+
+    #[test]
+    fn test_literal_prefix() {
+        assert_eq!(literal_prefix("kill"), "");
+        assert_eq!(literal_prefix("^kill"), "kill");
+        assert_eq!(literal_prefix("^kill(ed|ing)$"), "kill");
+        assert_eq!(literal_prefix("^hostages?$"), "hostage");
+        assert_eq!(literal_prefix("^ho+st"), "ho");
+        assert_eq!(literal_prefix("^kil{2}"), "ki");
+        assert_eq!(literal_prefix("^h.st"), "h");
+        assert_eq!(literal_prefix("^[hk]ill"), "");
+        assert_eq!(literal_prefix("^(?i)kill"), "");
+        assert_eq!(literal_prefix("^\\d+"), "");
+        assert_eq!(literal_prefix("^kill|^host"), "");
+        assert_eq!(literal_prefix("^k(a|i)ll|x"), "");
+        assert_eq!(literal_prefix("^k[|]ll"), "k");
+        assert_eq!(literal_prefix("^three-hour"), "three-hour");
+        assert_eq!(literal_prefix("^以色列"), "以色列");
+    }
+
+    #[test]
+    fn test_pattern_search() {
+        let mut tree = BSTree::new();
+        let words = [
+            "hostage", "hostages", "hostile", "host", "kill", "killed", "killing", "skill", "king",
+        ];
+        for value in words {
+            tree.insert_fn(Word::new(value.to_string()), |_| {});
+        }
+        let search = |pattern: &Pattern| {
+            pattern_search(&tree, pattern)
+                .map(|word| word.value.as_str())
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(
+            search(&Pattern::wildcard("kill*")),
+            ["kill", "killed", "killing"]
+        );
+        assert_eq!(search(&Pattern::wildcard("*ing")), ["killing", "king"]);
+        assert_eq!(search(&Pattern::wildcard("h?st?ge")), ["hostage"]);
+        assert_eq!(search(&Pattern::wildcard("*")).len(), words.len());
+        assert!(search(&Pattern::wildcard("x*")).is_empty());
+
+        let regex = |pattern: &str| search(&Pattern::regex(pattern).unwrap());
+        assert_eq!(regex("^kill(ed|ing)$"), ["killed", "killing"]);
+        assert_eq!(regex("ill"), ["kill", "killed", "killing", "skill"]);
+        assert_eq!(
+            regex("^host|^king$"),
+            ["host", "hostage", "hostages", "hostile", "king"]
+        );
+        assert!(Pattern::regex("^kill(").is_err());
+    }
+}
@@ -0,0 +1,501 @@
+//! # 单词规则模块
+//!
+//! 该模块定义了缩写、撇号和连字符的处理规则，由 `AsciiTokenizer` 和 `UnicodeTokenizer` 使用。
+//!
+//! - `Contractions`: 缩写（如 "don't"、"It’s"）的处理方式，拆开、保留或展开（"don't" → "do" + "not"）。
+//! - `Hyphens`: 连字符复合词（如 "three-hour"）的处理方式，拆开或作为一个单词。
+//! - `Numbers`: 数字（如 "3"、"2023"、"1,000"、"3.14"）的处理方式，丢弃、保留或统一计为 `<NUM>`。
+//! - `Symbols`: 允许出现在单词中的符号类别，例如 "x86_64" 中的下划线、"node.js" 中的点号、"c++" 末尾的加号。
+//! - `WordRules`: 以上规则，以及是否将弯撇号 "’" 等统一为 "'"。
+//!
+//! 规则会记录在分词器的名称中，例如 `ascii-lowercase;contractions=keep;hyphens=keep;apostrophes=normalize`，
+//! 因此加载缓存后仍然使用相同的规则。
+//!
+//! ## 示例
+//!
+//! ```rust
+//! use bst_words::utils::{
+//!     AsciiTokenizer, Contractions, Hyphens, Numbers, Symbols, Tokenizer, WordRules,
+//! };
+//! let rules = WordRules {
+//!     contractions: Contractions::Expand,
+//!     hyphens: Hyphens::Keep,
+//!     normalize_apostrophes: true,
+//!     ..WordRules::SPLIT
+//! };
+//! let tokenizer = AsciiTokenizer::new().with_rules(rules);
+//! assert_eq!(
+//!     tokenizer.tokenize("It’s a three-hour march"),
+//!     ["it", "is", "a", "three-hour", "march"]
+//! );
+//!
+//! let rules = WordRules {
+//!     numbers: Numbers::Bucket,
+//!     symbols: Symbols::UNDERSCORE,
+//!     ..WordRules::SPLIT
+//! };
+//! let tokenizer = AsciiTokenizer::new().with_rules(rules);
+//! assert_eq!(
+//!     tokenizer.tokenize("x86_64 since 2023"),
+//!     ["x86_64", "since", "<NUM>"]
+//! );
+//! ```
+// 引入标准库中的Cow类型，用于表示借用或拥有的单词
+use std::borrow::Cow;
+// 引入标准库中的BitOr trait，用于组合符号类别
+use std::ops::BitOr;
+
+// 引入anyhow库中的Error类型和anyhow宏，用于表示无法识别的规则
+use anyhow::{anyhow, Error};
+
+// 引入tokenize模块中的lowercase函数和Token结构体，用于将单词转为小写并记录其在原文中的位置
+use super::tokenize::{lowercase, Token};
+
+/// 缩写的处理方式。
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Contractions {
+    /// 在撇号处拆开，"don't" 得到 "don" 和 "t"
+    Split,
+    /// 作为一个单词保留，"don't" 得到 "don't"
+    Keep,
+    /// 展开为完整的单词，"don't" 得到 "do" 和 "not"，所有格 "Israel’s" 得到 "israel"
+    Expand,
+}
+
+/// 连字符复合词的处理方式。
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Hyphens {
+    /// 在连字符处拆开，"three-hour" 得到 "three" 和 "hour"
+    Split,
+    /// 作为一个单词保留，"three-hour" 得到 "three-hour"
+    Keep,
+}
+
+/// 数字的处理方式。
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Numbers {
+    /// 丢弃纯数字，ASCII 分词不把数字看作单词的一部分，"x86" 得到 "x"
+    Drop,
+    /// 保留数字和字母数字混合的单词
+    Keep,
+    /// 保留字母数字混合的单词，纯数字统一计为 `<NUM>`
+    Bucket,
+}
+
+/// 允许出现在单词中的符号类别，可以用 `|` 组合。
+///
+/// 下划线、点号、`@` 和斜杠只在两个字母或数字之间时属于单词，`+` 和 `#` 只在单词末尾时属于单词。
+/// Unicode 分词按 UAX #29 已经把下划线和两个字母之间的点号看作单词的一部分。
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Symbols(u8);
+
+impl Symbols {
+    /// 不允许任何符号。
+    pub const NONE: Symbols = Symbols(0);
+    /// 下划线，如 "x86_64"、"snake_case"。
+    pub const UNDERSCORE: Symbols = Symbols(1);
+    /// 点号，如 "node.js"、"v1.2"。
+    pub const DOT: Symbols = Symbols(2);
+    /// `@`，如 "user@example"。
+    pub const AT: Symbols = Symbols(4);
+    /// 斜杠，如 "tcp/ip"、"and/or"。
+    pub const SLASH: Symbols = Symbols(8);
+    /// 单词末尾的 `+` 和 `#`，如 "c++"、"c#"。
+    pub const TRAILING: Symbols = Symbols(16);
+
+    /// 各个符号类别及其在分词器名称中的名称。
+    pub const CLASSES: [(Symbols, &'static str); 5] = [
+        (Symbols::UNDERSCORE, "underscore"),
+        (Symbols::DOT, "dot"),
+        (Symbols::AT, "at"),
+        (Symbols::SLASH, "slash"),
+        (Symbols::TRAILING, "trailing"),
+    ];
+
+    /// 判断是否包含 `other` 中的全部符号类别。
+    pub fn contains(self, other: Symbols) -> bool {
+        self.0 & other.0 == other.0
+    }
+
+    /// 判断是否为空。
+    pub fn is_empty(self) -> bool {
+        self.0 == 0
+    }
+
+    /// 判断字符 `c` 在两个字母或数字之间时是否属于单词。
+    pub fn joins(self, c: char) -> bool {
+        match c {
+            '_' => self.contains(Symbols::UNDERSCORE),
+            '.' => self.contains(Symbols::DOT),
+            '@' => self.contains(Symbols::AT),
+            '/' => self.contains(Symbols::SLASH),
+            _ => false,
+        }
+    }
+
+    /// 判断字符 `c` 在单词末尾时是否属于单词。
+    pub fn trails(self, c: char) -> bool {
+        matches!(c, '+' | '#') && self.contains(Symbols::TRAILING)
+    }
+}
+
+impl BitOr for Symbols {
+    type Output = Symbols;
+
+    fn bitor(self, other: Symbols) -> Symbols {
+        Symbols(self.0 | other.0)
+    }
+}
+
+/// 缩写、撇号、连字符、数字和符号的处理规则。
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct WordRules {
+    /// 缩写的处理方式
+    pub contractions: Contractions,
+    /// 连字符复合词的处理方式
+    pub hyphens: Hyphens,
+    /// 是否将保留下来的弯撇号 "’"、"‘" 和 "ʼ" 统一为 "'"，使 "it’s" 与 "it's" 计为同一个单词
+    pub normalize_apostrophes: bool,
+    /// 数字的处理方式
+    pub numbers: Numbers,
+    /// 允许出现在单词中的符号类别
+    pub symbols: Symbols,
+}
+
+impl WordRules {
+    /// 缩写和连字符都拆开，即 `filter_word` 的行为，是 `AsciiTokenizer` 的默认规则。
+    pub const SPLIT: WordRules = WordRules {
+        contractions: Contractions::Split,
+        hyphens: Hyphens::Split,
+        normalize_apostrophes: false,
+        numbers: Numbers::Drop,
+        symbols: Symbols::NONE,
+    };
+
+    /// 保留缩写，拆开连字符，即 Unicode 单词边界（UAX #29）的行为，是 `UnicodeTokenizer` 的默认规则。
+    pub const UAX29: WordRules = WordRules {
+        contractions: Contractions::Keep,
+        hyphens: Hyphens::Split,
+        normalize_apostrophes: false,
+        numbers: Numbers::Drop,
+        symbols: Symbols::NONE,
+    };
+
+    /// 规则在分词器名称中的后缀，例如 `;contractions=keep;hyphens=split;apostrophes=keep`，
+    /// 保留数字或允许符号时还会加上 `;numbers=keep;symbols=underscore,dot`。
+    pub fn suffix(&self) -> String {
+        let contractions = match self.contractions {
+            Contractions::Split => "split",
+            Contractions::Keep => "keep",
+            Contractions::Expand => "expand",
+        };
+        let hyphens = match self.hyphens {
+            Hyphens::Split => "split",
+            Hyphens::Keep => "keep",
+        };
+        let apostrophes = if self.normalize_apostrophes {
+            "normalize"
+        } else {
+            "keep"
+        };
+        let mut suffix =
+            format!(";contractions={contractions};hyphens={hyphens};apostrophes={apostrophes}");
+        match self.numbers {
+            Numbers::Drop => {}
+            Numbers::Keep => suffix.push_str(";numbers=keep"),
+            Numbers::Bucket => suffix.push_str(";numbers=bucket"),
+        }
+        if !self.symbols.is_empty() {
+            let names: Vec<&str> = Symbols::CLASSES
+                .iter()
+                .filter(|(class, _)| self.symbols.contains(*class))
+                .map(|(_, name)| *name)
+                .collect();
+            suffix.push_str(";symbols=");
+            suffix.push_str(&names.join(","));
+        }
+        suffix
+    }
+
+    /// 解析分词器名称中的规则后缀，后缀中没有出现的规则使用 `default` 中的值。
+    ///
+    /// # 参数
+    ///
+    /// * `suffix`: 规则后缀，由 `;` 分隔的 `key=value`，可以为空。
+    /// * `default`: 默认规则。
+    ///
+    /// # 返回
+    ///
+    /// 如果后缀可以识别，返回规则，否则返回错误。
+    pub fn parse(suffix: &str, default: WordRules) -> Result<WordRules, Error> {
+        let mut rules = default;
+        for item in suffix.split(';').filter(|item| !item.is_empty()) {
+            match item.split_once('=') {
+                Some(("contractions", "split")) => rules.contractions = Contractions::Split,
+                Some(("contractions", "keep")) => rules.contractions = Contractions::Keep,
+                Some(("contractions", "expand")) => rules.contractions = Contractions::Expand,
+                Some(("hyphens", "split")) => rules.hyphens = Hyphens::Split,
+                Some(("hyphens", "keep")) => rules.hyphens = Hyphens::Keep,
+                Some(("apostrophes", "keep")) => rules.normalize_apostrophes = false,
+                Some(("apostrophes", "normalize")) => rules.normalize_apostrophes = true,
+                Some(("numbers", "drop")) => rules.numbers = Numbers::Drop,
+                Some(("numbers", "keep")) => rules.numbers = Numbers::Keep,
+                Some(("numbers", "bucket")) => rules.numbers = Numbers::Bucket,
+                Some(("symbols", names)) => {
+                    rules.symbols = Symbols::NONE;
+                    for name in names.split(',').filter(|name| !name.is_empty()) {
+                        match Symbols::CLASSES.iter().find(|(_, n)| *n == name) {
+                            Some((class, _)) => rules.symbols = rules.symbols | *class,
+                            None => return Err(anyhow!("无法识别的符号类别: {}", name)),
+                        }
+                    }
+                }
+                _ => return Err(anyhow!("无法识别的单词规则: {}", item)),
+            }
+        }
+        Ok(rules)
+    }
+
+    /// 判断字符 `c` 是否可能连接两边的字母或数字，是否真的连接还要看两边的字符，见 `joins`。
+    pub(crate) fn may_join(&self, c: char) -> bool {
+        is_apostrophe(c)
+            || is_hyphen(c)
+            || self.symbols.joins(c)
+            || (self.numbers != Numbers::Drop && matches!(c, ',' | '.'))
+    }
+
+    /// 判断字符 `c` 位于 `prev` 和 `next` 之间时，是否将两边连成同一个片段。
+    ///
+    /// 撇号和连字符总是连接两边，由 `apply` 按规则拆开；符号按 `symbols` 连接；
+    /// 保留数字时，两个数字之间的 "," 和 "." 也连接两边，如 "1,000" 和 "3.14"。
+    pub(crate) fn joins(&self, prev: char, c: char, next: char) -> bool {
+        is_apostrophe(c)
+            || is_hyphen(c)
+            || self.symbols.joins(c)
+            || (self.numbers != Numbers::Drop
+                && matches!(c, ',' | '.')
+                && prev.is_numeric()
+                && next.is_numeric())
+    }
+
+    /// 按规则处理 `text` 中的一个片段，将得到的单词及其在 `text` 中的位置追加到 `tokens`。
+    ///
+    /// 片段由字母、数字和连接它们的撇号、连字符和符号组成，得到的单词都会转为小写。
+    pub(crate) fn apply<'a>(&self, text: &'a str, span: &'a str, tokens: &mut Vec<Token<'a>>) {
+        match self.hyphens {
+            Hyphens::Split => {
+                for part in span.split(is_hyphen).filter(|part| !part.is_empty()) {
+                    self.apply_contractions(text, part, tokens);
+                }
+            }
+            Hyphens::Keep => self.apply_contractions(text, span, tokens),
+        }
+    }
+
+    /// 按缩写规则处理一个不含需要拆开的连字符的片段。
+    fn apply_contractions<'a>(&self, text: &'a str, part: &'a str, tokens: &mut Vec<Token<'a>>) {
+        if !part.contains(is_apostrophe) {
+            self.push(text, part, tokens);
+            return;
+        }
+        match self.contractions {
+            Contractions::Split => {
+                for piece in part.split(is_apostrophe).filter(|piece| !piece.is_empty()) {
+                    self.push(text, piece, tokens);
+                }
+            }
+            Contractions::Keep => self.push(text, part, tokens),
+            Contractions::Expand => {
+                let key = normalize_apostrophes(&part.to_lowercase()).into_owned();
+                match expand_contraction(&key) {
+                    // 展开得到的单词都对应原文中的整个缩写
+                    Some(words) => tokens.extend(
+                        words
+                            .into_iter()
+                            .map(|word| Token::within(text, part, Cow::Owned(word), false)),
+                    ),
+                    None => self.push(text, part, tokens),
+                }
+            }
+        }
+    }
+
+    /// 按数字规则处理一个最终的单词，纯数字按 `numbers` 丢弃、保留或计为 `<NUM>`。
+    fn push<'a>(&self, text: &'a str, word: &'a str, tokens: &mut Vec<Token<'a>>) {
+        if !is_number(word) {
+            tokens.push(Token::within(text, word, self.finish(word), true));
+            return;
+        }
+        match self.numbers {
+            Numbers::Drop => {}
+            Numbers::Keep => tokens.push(Token::within(text, word, self.finish(word), true)),
+            Numbers::Bucket => {
+                tokens.push(Token::within(text, word, Cow::Borrowed(NUM_TOKEN), false))
+            }
+        }
+    }
+
+    /// 将保留下来的单词转为小写，并按规则统一撇号和连字符。
+    fn finish<'a>(&self, part: &'a str) -> Cow<'a, str> {
+        let convert = |c: char| {
+            (self.normalize_apostrophes && is_apostrophe(c) && c != '\'')
+                || (is_hyphen(c) && c != '-')
+        };
+        if !part.chars().any(convert) {
+            return lowercase(part);
+        }
+        let replaced: String = part
+            .chars()
+            .map(|c| match c {
+                c if !convert(c) => c,
+                c if is_hyphen(c) => '-',
+                _ => '\'',
+            })
+            .collect();
+        Cow::Owned(replaced.to_lowercase())
+    }
+}
+
+/// `Numbers::Bucket` 时所有纯数字计为的单词。
+pub const NUM_TOKEN: &str = "<NUM>";
+
+/// 判断一个单词是否是纯数字，即只由数字和数字之间的 "," 或 "." 组成。
+pub fn is_number(word: &str) -> bool {
+    word.chars().any(char::is_numeric)
+        && word.chars().all(|c| c.is_numeric() || c == ',' || c == '.')
+}
+
+/// 判断一个字符是否是撇号，包括直撇号 "'"、弯撇号 "’"、"‘" 和修饰字母撇号 "ʼ"。
+pub fn is_apostrophe(c: char) -> bool {
+    matches!(c, '\'' | '\u{2019}' | '\u{2018}' | '\u{02BC}')
+}
+
+/// 判断一个字符是否是连字符，包括 "-"、"‐" 和不换行连字符 "‑"，不包括破折号 "–" 和 "—"。
+pub fn is_hyphen(c: char) -> bool {
+    matches!(c, '-' | '\u{2010}' | '\u{2011}')
+}
+
+/// 将字符串中的弯撇号 "’"、"‘" 和 "ʼ" 统一为直撇号 "'"，没有需要替换的撇号时直接借用。
+pub fn normalize_apostrophes(s: &str) -> Cow<'_, str> {
+    if s.chars().all(|c| c == '\'' || !is_apostrophe(c)) {
+        Cow::Borrowed(s)
+    } else {
+        Cow::Owned(
+            s.chars()
+                .map(|c| if is_apostrophe(c) { '\'' } else { c })
+                .collect(),
+        )
+    }
+}
+
+/// 展开一个英语缩写。
+///
+/// "won't"、"can't"、"let's" 等不规则的缩写单独处理；
+/// 以 "n't"、"'re"、"'ve"、"'ll"、"'m"、"'d" 结尾的缩写展开为前面的单词加上完整的单词；
+/// 代词后的 "'s" 展开为 "is"，其余的 "'s" 视为所有格，只保留前面的单词。
+///
+/// # 参数
+///
+/// * `word`: 小写的单词，撇号已经统一为 "'"。
+///
+/// # 返回
+///
+/// 如果 `word` 是可以展开的缩写，返回展开后的单词，否则返回 `None`，如 "o'clock"。
+pub fn expand_contraction(word: &str) -> Option<Vec<String>> {
+    let irregular: &[&str] = match word {
+        "won't" => &["will", "not"],
+        "can't" => &["can", "not"],
+        "shan't" => &["shall", "not"],
+        "ain't" => &["is", "not"],
+        "let's" => &["let", "us"],
+        "y'all" => &["you", "all"],
+        _ => &[],
+    };
+    if !irregular.is_empty() {
+        return Some(irregular.iter().map(|w| w.to_string()).collect());
+    }
+    let (stem, full) = if let Some(stem) = word.strip_suffix("n't") {
+        (stem, "not")
+    } else {
+        let (stem, ending) = word.rsplit_once('\'')?;
+        let full = match ending {
+            "re" => "are",
+            "ve" => "have",
+            "ll" => "will",
+            "m" => "am",
+            "d" => "would",
+            "s" if PRONOUNS.contains(&stem) => "is",
+            "s" => "",
+            _ => return None,
+        };
+        (stem, full)
+    };
+    if stem.is_empty() {
+        return None;
+    }
+    // 前面的单词本身也可能是缩写，例如 "shouldn't've"
+    let mut words = expand_contraction(stem).unwrap_or_else(|| vec![stem.to_string()]);
+    if !full.is_empty() {
+        words.push(full.to_string());
+    }
+    Some(words)
+}
+
+/// 后面的 "'s" 表示 "is" 的代词和疑问词。
+const PRONOUNS: [&str; 12] = [
+    "it", "he", "she", "that", "what", "there", "here", "who", "where", "how", "when", "why",
+];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_expand_contraction() {
+        let expand = |word| expand_contraction(word).map(|words| words.join(" "));
+        assert_eq!(expand("don't").as_deref(), Some("do not"));
+        assert_eq!(expand("won't").as_deref(), Some("will not"));
+        assert_eq!(expand("it's").as_deref(), Some("it is"));
+        assert_eq!(expand("they're").as_deref(), Some("they are"));
+        assert_eq!(expand("i'm").as_deref(), Some("i am"));
+        assert_eq!(expand("shouldn't've").as_deref(), Some("should not have"));
+        // 所有格只保留前面的单词
+        assert_eq!(expand("israel's").as_deref(), Some("israel"));
+        assert_eq!(expand("o'clock"), None);
+        assert_eq!(expand("'s"), None);
+    }
+
+    #[test]
+    fn test_rules_suffix() {
+        let mut rules = WordRules {
+            contractions: Contractions::Expand,
+            hyphens: Hyphens::Keep,
+            normalize_apostrophes: true,
+            ..WordRules::SPLIT
+        };
+        assert_eq!(
+            rules.suffix(),
+            ";contractions=expand;hyphens=keep;apostrophes=normalize"
+        );
+        assert_eq!(
+            WordRules::parse(&rules.suffix(), WordRules::SPLIT).unwrap(),
+            rules
+        );
+        rules.numbers = Numbers::Bucket;
+        rules.symbols = Symbols::UNDERSCORE | Symbols::TRAILING;
+        assert_eq!(
+            rules.suffix(),
+            ";contractions=expand;hyphens=keep;apostrophes=normalize;numbers=bucket;symbols=underscore,trailing"
+        );
+        assert_eq!(
+            WordRules::parse(&rules.suffix(), WordRules::SPLIT).unwrap(),
+            rules
+        );
+        // 没有出现的规则使用默认值
+        let partial = WordRules::parse(";hyphens=keep", WordRules::UAX29).unwrap();
+        assert_eq!(partial.contractions, Contractions::Keep);
+        assert_eq!(partial.hyphens, Hyphens::Keep);
+        assert!(WordRules::parse(";hyphens=maybe", WordRules::SPLIT).is_err());
+        assert!(WordRules::parse(";symbols=dot,comma", WordRules::SPLIT).is_err());
+    }
+}
@@ -0,0 +1,540 @@
+//! # 词形归并模块
+//!
+//! 该模块在分词之后、插入单词树之前对单词做可选的归并，使 "kill"、"killed"、"killing" 计为同一个单词。
+//!
+//! - `Normalizer`: 归并方式 trait，`name` 记录在缓存文件头中，`normalize` 返回单词归并后的形式。
+//! - `PorterStemmer`: Porter 英文词干提取算法，只处理全部由 ASCII 小写字母组成的单词，其余单词原样返回。
+//! - `Lemmatizer`: 基于词典的词形还原，词典中没有的单词原样返回。
+//! - `normalizer_from_name`: 根据缓存文件头中记录的名称重新创建归并方式。
+//!
+//! 词形还原词典每行的第一个字段为词元，其后的字段为它的各种形式，字段之间用空白分隔，
+//! 以 `#` 开头的行为注释，因此每行一对 `词元<TAB>形式` 的常见格式也可以直接使用。
+//!
+//! ## 示例
+//!
+//! ```rust
+//! use bst_words::utils::{Lemmatizer, Normalizer, PorterStemmer};
+//! assert_eq!(PorterStemmer.normalize("killing"), "kill");
+//! assert_eq!(PorterStemmer.normalize("hostages"), "hostag");
+//!
+//! let mut lemmatizer = Lemmatizer::new();
+//! lemmatizer.load_dict("go went gone going\nchild children");
+//! assert_eq!(lemmatizer.normalize("went"), "go");
+//! assert_eq!(lemmatizer.normalize("children"), "child");
+//! assert_eq!(lemmatizer.normalize("killed"), "killed");
+//! ```
+// 引入标准库中的Cow类型，用于表示借用或拥有的单词
+use std::borrow::Cow;
+// 引入标准库中的HashMap类型，用于存储词形到词元的映射
+use std::collections::HashMap;
+// 引入标准库中的fs模块，用于取得词典文件的绝对路径
+use std::fs;
+
+// 引入anyhow库中的Error类型和bail宏，它们用于表示和返回错误
+use anyhow::{bail, Error};
+
+// 引入cache模块中的Fnv64哈希，用于在名称中记录不是从文件读取的词典的内容
+use super::cache::Fnv64;
+// 引入utils模块中的read_from_file函数，用于读取词典文件
+use super::read_from_file;
+
+/// 单词归并方式，在分词之后、插入单词树之前把单词的不同形式归并为同一个单词。
+pub trait Normalizer {
+    /// 归并方式的名称，记录在缓存文件头中，`normalizer_from_name` 可以根据名称重新创建归并方式。
+    fn name(&self) -> &str;
+
+    /// 返回单词归并后的形式，不需要归并时返回借用的原单词。
+    ///
+    /// # 参数
+    ///
+    /// * `word`: 分词器切分出的单词，已经转为小写。
+    fn normalize<'a>(&self, word: &'a str) -> Cow<'a, str>;
+}
+
+/// Porter 英文词干提取算法（M. F. Porter, 1980）。
+#[derive(Clone, Copy, Debug, Default)]
+pub struct PorterStemmer;
+
+impl PorterStemmer {
+    /// 归并方式的名称。
+    pub const NAME: &'static str = "porter";
+}
+
+impl Normalizer for PorterStemmer {
+    fn name(&self) -> &str {
+        Self::NAME
+    }
+
+    fn normalize<'a>(&self, word: &'a str) -> Cow<'a, str> {
+        porter_stem(word)
+    }
+}
+
+/// 返回单词的 Porter 词干。
+///
+/// 只处理全部由 ASCII 小写字母组成、长度大于 2 的单词，缩写、连字符复合词、数字和非 ASCII 的单词原样返回。
+///
+/// # 参数
+///
+/// * `word`: 要提取词干的单词。
+///
+/// # 返回
+///
+/// 返回单词的词干，词干与原单词相同时返回借用的原单词。
+pub fn porter_stem(word: &str) -> Cow<'_, str> {
+    if word.len() <= 2 || !word.bytes().all(|b| b.is_ascii_lowercase()) {
+        return Cow::Borrowed(word);
+    }
+    let mut w = word.as_bytes().to_vec();
+    step1a(&mut w);
+    step1b(&mut w);
+    if w.len() > 1 {
+        step1c(&mut w);
+        replace_first(&mut w, STEP2);
+        replace_first(&mut w, STEP3);
+        step4(&mut w);
+        step5(&mut w);
+    }
+    if w == word.as_bytes() {
+        Cow::Borrowed(word)
+    } else {
+        Cow::Owned(String::from_utf8(w).expect("词干只包含 ASCII 字母"))
+    }
+}
+
+/// 第 2 步的后缀及其替换，词干的度量大于 0 时替换。
+const STEP2: &[(&str, &str)] = &[
+    ("ational", "ate"),
+    ("tional", "tion"),
+    ("enci", "ence"),
+    ("anci", "ance"),
+    ("izer", "ize"),
+    ("bli", "ble"),
+    ("alli", "al"),
+    ("entli", "ent"),
+    ("eli", "e"),
+    ("ousli", "ous"),
+    ("ization", "ize"),
+    ("ation", "ate"),
+    ("ator", "ate"),
+    ("alism", "al"),
+    ("iveness", "ive"),
+    ("fulness", "ful"),
+    ("ousness", "ous"),
+    ("aliti", "al"),
+    ("iviti", "ive"),
+    ("biliti", "ble"),
+    ("logi", "log"),
+];
+
+/// 第 3 步的后缀及其替换，词干的度量大于 0 时替换。
+const STEP3: &[(&str, &str)] = &[
+    ("icate", "ic"),
+    ("ative", ""),
+    ("alize", "al"),
+    ("iciti", "ic"),
+    ("ical", "ic"),
+    ("ful", ""),
+    ("ness", ""),
+];
+
+/// 第 4 步删除的后缀，词干的度量大于 1 时删除。
+const STEP4: &[&str] = &[
+    "al", "ance", "ence", "er", "ic", "able", "ible", "ant", "ement", "ment", "ent", "ion", "ou",
+    "ism", "ate", "iti", "ous", "ive", "ize",
+];
+
+/// 判断第 `i` 个字母是否为辅音，`y` 在辅音之后为元音，在词首或元音之后为辅音。
+fn is_consonant(w: &[u8], i: usize) -> bool {
+    match w[i] {
+        b'a' | b'e' | b'i' | b'o' | b'u' => false,
+        b'y' => i == 0 || !is_consonant(w, i - 1),
+        _ => true,
+    }
+}
+
+/// 计算度量 m，即把单词写成 `[C](VC)^m[V]` 时 `VC` 的个数。
+fn measure(w: &[u8]) -> usize {
+    let mut m = 0;
+    let mut i = 0;
+    // 跳过开头的辅音
+    while i < w.len() && is_consonant(w, i) {
+        i += 1;
+    }
+    loop {
+        while i < w.len() && !is_consonant(w, i) {
+            i += 1;
+        }
+        if i == w.len() {
+            return m;
+        }
+        while i < w.len() && is_consonant(w, i) {
+            i += 1;
+        }
+        m += 1;
+    }
+}
+
+/// 判断单词中是否含有元音。
+fn has_vowel(w: &[u8]) -> bool {
+    (0..w.len()).any(|i| !is_consonant(w, i))
+}
+
+/// 判断单词是否以两个相同的辅音结尾。
+fn ends_double_consonant(w: &[u8]) -> bool {
+    let n = w.len();
+    n >= 2 && w[n - 1] == w[n - 2] && is_consonant(w, n - 1)
+}
+
+/// 判断单词是否以辅音-元音-辅音结尾，且最后的辅音不是 w、x、y，例如 hop、fil。
+fn ends_cvc(w: &[u8]) -> bool {
+    let n = w.len();
+    n >= 3
+        && is_consonant(w, n - 3)
+        && !is_consonant(w, n - 2)
+        && is_consonant(w, n - 1)
+        && !matches!(w[n - 1], b'w' | b'x' | b'y')
+}
+
+/// 将单词末尾长度为 `len` 的后缀替换为 `replacement`。
+fn set_suffix(w: &mut Vec<u8>, len: usize, replacement: &str) {
+    w.truncate(w.len() - len);
+    w.extend_from_slice(replacement.as_bytes());
+}
+
+/// 找到第一个匹配的后缀，去掉后缀的词干度量大于 0 时替换，无论是否替换都不再尝试其余后缀。
+fn replace_first(w: &mut Vec<u8>, rules: &[(&str, &str)]) {
+    if let Some((suffix, replacement)) = rules.iter().find(|(s, _)| w.ends_with(s.as_bytes())) {
+        if measure(&w[..w.len() - suffix.len()]) > 0 {
+            set_suffix(w, suffix.len(), replacement);
+        }
+    }
+}
+
+/// 第 1a 步：处理复数，sses → ss，ies → i，s → 空。
+fn step1a(w: &mut Vec<u8>) {
+    if w.ends_with(b"sses") || w.ends_with(b"ies") {
+        w.truncate(w.len() - 2);
+    } else if w.ends_with(b"s") && !w.ends_with(b"ss") {
+        w.pop();
+    }
+}
+
+/// 第 1b 步：处理 eed、ed、ing，去掉 ed、ing 后再修整词尾。
+fn step1b(w: &mut Vec<u8>) {
+    if w.ends_with(b"eed") {
+        if measure(&w[..w.len() - 3]) > 0 {
+            w.pop();
+        }
+        return;
+    }
+    let len = if w.ends_with(b"ed") {
+        2
+    } else if w.ends_with(b"ing") {
+        3
+    } else {
+        return;
+    };
+    if !has_vowel(&w[..w.len() - len]) {
+        return;
+    }
+    w.truncate(w.len() - len);
+    if w.ends_with(b"at") || w.ends_with(b"bl") || w.ends_with(b"iz") {
+        w.push(b'e');
+    } else if ends_double_consonant(w) && !matches!(w[w.len() - 1], b'l' | b's' | b'z') {
+        w.pop();
+    } else if measure(w) == 1 && ends_cvc(w) {
+        w.push(b'e');
+    }
+}
+
+/// 第 1c 步：词干含有元音时，词尾的 y 改为 i。
+fn step1c(w: &mut [u8]) {
+    let n = w.len();
+    if w[n - 1] == b'y' && has_vowel(&w[..n - 1]) {
+        w[n - 1] = b'i';
+    }
+}
+
+/// 第 4 步：词干的度量大于 1 时删除后缀，ion 只在前面是 s 或 t 时删除。
+fn step4(w: &mut Vec<u8>) {
+    let found = STEP4.iter().find(|s| {
+        w.ends_with(s.as_bytes())
+            && (**s != "ion" || matches!(w.len().checked_sub(4).map(|i| w[i]), Some(b's' | b't')))
+    });
+    if let Some(suffix) = found {
+        if measure(&w[..w.len() - suffix.len()]) > 1 {
+            w.truncate(w.len() - suffix.len());
+        }
+    }
+}
+
+/// 第 5 步：去掉词尾多余的 e，以及度量大于 1 时 ll 结尾的一个 l。
+fn step5(w: &mut Vec<u8>) {
+    if w.ends_with(b"e") {
+        let stem = &w[..w.len() - 1];
+        let m = measure(stem);
+        if m > 1 || (m == 1 && !ends_cvc(stem)) {
+            w.pop();
+        }
+    }
+    if w.ends_with(b"ll") && measure(w) > 1 {
+        w.pop();
+    }
+}
+
+/// 基于词典的词形还原，把单词的各种形式还原为词元，例如 went → go。
+#[derive(Clone, Debug)]
+pub struct Lemmatizer {
+    /// 单词形式到词元的映射
+    lemmas: HashMap<String, String>,
+    /// 归并方式的名称，从文件加载时包含词典的绝对路径，否则包含词典内容的哈希值
+    name: String,
+}
+
+impl Default for Lemmatizer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Lemmatizer {
+    /// 归并方式名称的前缀，其后为词典文件的绝对路径。
+    pub const PREFIX: &'static str = "lemma:";
+    /// 词典不是从文件读取时归并方式的名称，其后为 `DICT_PREFIX` 和词典内容的哈希值。
+    pub const NAME: &'static str = "lemma";
+    /// 名称中词典哈希值的前缀，位于 `NAME` 之后。
+    pub const DICT_PREFIX: &'static str = ";dict=";
+
+    /// 创建一个空词典的词形还原器。
+    pub fn new() -> Self {
+        let mut lemmatizer = Lemmatizer {
+            lemmas: HashMap::new(),
+            name: String::new(),
+        };
+        lemmatizer.update_name();
+        lemmatizer
+    }
+
+    /// 创建一个使用指定词典文件的词形还原器，名称中记录词典的绝对路径，以便在其他工作目录下加载缓存时重新读取词典。
+    ///
+    /// # 参数
+    ///
+    /// * `path`: 词典文件的路径，可以是相对路径。
+    ///
+    /// # 返回
+    ///
+    /// 如果读取成功，返回词形还原器，否则返回错误。
+    pub fn from_file(path: &str) -> Result<Self, Error> {
+        let mut lemmatizer = Self::new();
+        lemmatizer.load_dict(&read_from_file(path)?);
+        let path = fs::canonicalize(path)?;
+        lemmatizer.name = format!("{}{}", Self::PREFIX, path.display());
+        Ok(lemmatizer)
+    }
+
+    /// 将词典文本中的词形追加到当前词典。
+    ///
+    /// 每行的第一个字段为词元，其后的字段为它的各种形式，空行和以 `#` 开头的行会被跳过。
+    ///
+    /// # 返回
+    ///
+    /// 返回新追加的词形数。
+    pub fn load_dict(&mut self, dict: &str) -> usize {
+        let before = self.lemmas.len();
+        for line in dict.lines() {
+            let line = line.trim();
+            if line.starts_with('#') {
+                continue;
+            }
+            let mut fields = line.split_whitespace();
+            if let Some(lemma) = fields.next() {
+                for form in fields {
+                    self.add(form, lemma);
+                }
+            }
+        }
+        self.update_name();
+        self.lemmas.len() - before
+    }
+
+    /// 记录一个单词形式的词元，两者都会转为小写，以便与分词器切分出的单词匹配。
+    pub fn insert(&mut self, form: &str, lemma: &str) {
+        self.add(form, lemma);
+        self.update_name();
+    }
+
+    /// 记录一个单词形式的词元，不更新名称。
+    fn add(&mut self, form: &str, lemma: &str) {
+        self.lemmas
+            .insert(form.to_lowercase(), lemma.to_lowercase());
+    }
+
+    /// 按词典内容更新名称；从文件加载后又追加了词形时，词典不再只来自该文件，名称也改为哈希值。
+    fn update_name(&mut self) {
+        let mut pairs: Vec<(&String, &String)> = self.lemmas.iter().collect();
+        pairs.sort_unstable();
+        let mut hasher = Fnv64::new();
+        for (form, lemma) in pairs {
+            for field in [form.as_bytes(), b"\t", lemma.as_bytes(), b"\n"] {
+                hasher.update(field);
+            }
+        }
+        self.name = format!(
+            "{}{}{:016x}",
+            Self::NAME,
+            Self::DICT_PREFIX,
+            hasher.finish()
+        );
+    }
+
+    /// 词典中的词形数。
+    pub fn len(&self) -> usize {
+        self.lemmas.len()
+    }
+
+    /// 判断词典是否为空。
+    pub fn is_empty(&self) -> bool {
+        self.lemmas.is_empty()
+    }
+}
+
+impl Normalizer for Lemmatizer {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn normalize<'a>(&self, word: &'a str) -> Cow<'a, str> {
+        match self.lemmas.get(word) {
+            Some(lemma) => Cow::Owned(lemma.clone()),
+            None => Cow::Borrowed(word),
+        }
+    }
+}
+
+/// 根据缓存文件头中记录的名称创建归并方式。
+///
+/// # 参数
+///
+/// * `name`: 归并方式的名称，`porter` 或 `lemma:` 加词典的绝对路径。
+///
+/// # 返回
+///
+/// 如果名称可以识别，返回归并方式；名称无法识别、词典无法读取或词典不是从文件读取的（`lemma;dict=` 加哈希值）时返回错误。
+pub fn normalizer_from_name(name: &str) -> Result<Box<dyn Normalizer>, Error> {
+    if name == PorterStemmer::NAME {
+        return Ok(Box::new(PorterStemmer));
+    }
+    if let Some(path) = name.strip_prefix(Lemmatizer::PREFIX) {
+        let lemmatizer = Lemmatizer::from_file(path)
+            .map_err(|e| e.context(format!("无法读取词形还原词典 {path}")))?;
+        return Ok(Box::new(lemmatizer));
+    }
+    if name
+        .strip_prefix(Lemmatizer::NAME)
+        .is_some_and(|rest| rest.starts_with(Lemmatizer::DICT_PREFIX))
+    {
+        bail!("缓存使用的词形还原词典不是从文件读取的（{name}），无法重新创建");
+    }
+    bail!("无法识别的归并方式: {name}")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_porter_stem() {
+        let cases = [
+            ("caresses", "caress"),
+            ("ponies", "poni"),
+            ("ties", "ti"),
+            ("cats", "cat"),
+            ("feed", "feed"),
+            ("agreed", "agre"),
+            ("plastered", "plaster"),
+            ("motoring", "motor"),
+            ("sing", "sing"),
+            ("conflated", "conflat"),
+            ("troubled", "troubl"),
+            ("sized", "size"),
+            ("hopping", "hop"),
+            ("tanned", "tan"),
+            ("falling", "fall"),
+            ("hissing", "hiss"),
+            ("fizzed", "fizz"),
+            ("failing", "fail"),
+            ("filing", "file"),
+            ("happy", "happi"),
+            ("sky", "sky"),
+            ("relational", "relat"),
+            ("generalizations", "gener"),
+            ("oscillators", "oscil"),
+            ("adoption", "adopt"),
+            ("controlling", "control"),
+            ("roll", "roll"),
+            ("kill", "kill"),
+            ("killed", "kill"),
+            ("killing", "kill"),
+            ("kills", "kill"),
+        ];
+        for (word, stem) in cases {
+            assert_eq!(porter_stem(word), stem, "{word}");
+        }
+        // 短单词、缩写、连字符复合词、数字和非 ASCII 的单词不处理
+        for word in [
+            "is",
+            "don't",
+            "three-hour",
+            "<NUM>",
+            "2023",
+            "café",
+            "Killed",
+        ] {
+            assert!(matches!(porter_stem(word), Cow::Borrowed(w) if w == word));
+        }
+    }
+
+    #[test]
+    fn test_lemmatizer() {
+        let path = "target/test_lemmas.txt";
+        std::fs::write(path, "# 词元 形式\nkill killed killing kills\nbe\tWas\n").unwrap();
+        let lemmatizer = Lemmatizer::from_file(path).unwrap();
+        assert_eq!(lemmatizer.len(), 4);
+        assert_eq!(lemmatizer.normalize("killing"), "kill");
+        assert_eq!(lemmatizer.normalize("was"), "be");
+        assert_eq!(lemmatizer.normalize("hostages"), "hostages");
+
+        // 名称中记录词典的绝对路径，可以在其他工作目录下重新创建
+        let name = lemmatizer.name().to_string();
+        let absolute = std::fs::canonicalize(path).unwrap();
+        assert_eq!(name, format!("lemma:{}", absolute.display()));
+        let other = Lemmatizer::from_file("./target/../target/test_lemmas.txt").unwrap();
+        assert_eq!(other.name(), name);
+        assert_eq!(
+            normalizer_from_name(&name).unwrap().normalize("kills"),
+            "kill"
+        );
+        assert_eq!(normalizer_from_name("porter").unwrap().name(), "porter");
+        assert!(normalizer_from_name("lemma:target/no_such_lemmas.txt").is_err());
+        assert!(normalizer_from_name("snowball").is_err());
+
+        // 不是从文件读取的词典按内容命名，名称无法重新创建
+        let mut memory = Lemmatizer::new();
+        memory.load_dict("kill killed killing kills\nbe was");
+        assert!(
+            memory.name().starts_with("lemma;dict="),
+            "{}",
+            memory.name()
+        );
+        let err = normalizer_from_name(memory.name()).err().unwrap();
+        assert!(err.to_string().contains("不是从文件读取的"), "{err}");
+        let mut other = Lemmatizer::new();
+        other.insert("was", "be");
+        other.load_dict("kill kills killing killed");
+        assert_eq!(other.name(), memory.name());
+        other.insert("went", "go");
+        assert_ne!(other.name(), memory.name());
+        assert_ne!(Lemmatizer::new().name(), memory.name());
+    }
+}
@@ -0,0 +1,865 @@
+//! # 分词模块
+//!
+//! 该模块定义了 `Tokenizer` trait，语料、停用词和查询词都通过同一个分词器切分，保证它们得到的单词一致。
+//! 分词器不会修改输入的文本，切出的单词在不需要转换时直接借用原文，需要转换（如转为小写）时才分配新的字符串。
+//!
+//! 内置的分词器：
+//!
+//! - `AsciiTokenizer`: 按非 ASCII 字母切分并转为小写，默认与 `filter_word` 的结果相同，非 ASCII 字符会被丢弃。
+//! - `UnicodeTokenizer`: 按 Unicode 单词边界（UAX #29）切分并按 Unicode 规则转为小写，
+//!   "café"、"Zürich"、西里尔字母和希腊字母的单词都能完整保留，汉字按字切分。
+//!   `UnicodeTokenizer::folding` 在此基础上做大小写折叠，例如 "Straße" 与 "STRASSE" 都得到 "strasse"。
+//! - `WhitespaceTokenizer`: 只按空白切分并转为小写，标点会保留在单词中。
+//! - `RegexTokenizer`: 取正则表达式的每个匹配作为单词并转为小写。
+//! - `Segmenter`: 连续的汉字按词典正向最大匹配切分，其余文字按 Unicode 单词边界切分，详见 `cjk` 模块。
+//!
+//! `AsciiTokenizer` 和 `UnicodeTokenizer` 可以用 `with_rules` 设置缩写、撇号和连字符的规则，详见 `rules` 模块。
+//!
+//! 每个分词器都有一个名称，记录在缓存文件头的 `tokenizer` 字段中，`tokenizer_from_name` 根据名称重新创建分词器，
+//! 因此加载缓存后可以继续使用构建时的分词方式。
+//!
+//! `Tokenizer::tokens` 在切分的同时返回每个单词在原文中的字节范围（`Token`），用于记录单词的原始大小写。
+//!
+//! ## 示例
+//!
+//! ```rust
+//! use bst_words::utils::{tokenizer_from_name, Tokenizer, UnicodeTokenizer};
+//! let tokens = UnicodeTokenizer::new().tokenize("Café in Zürich, привет!");
+//! assert_eq!(tokens, ["café", "in", "zürich", "привет"]);
+//!
+//! let tokenizer = tokenizer_from_name(r"regex:(?i)[a-z]+(?:'[a-z]+)?").unwrap();
+//! assert_eq!(tokenizer.tokenize("Don't stop"), ["don't", "stop"]);
+//!
+//! let text = "IDF said";
+//! let tokens = tokenizer.tokens(text);
+//! assert_eq!(tokens[0].word, "idf");
+//! assert_eq!(&text[tokens[0].span.clone()], "IDF");
+//! ```
+// 引入标准库中的Cow类型，用于表示借用或拥有的单词
+use std::borrow::Cow;
+// 引入标准库中的Range类型，用于表示单词在原文中的字节范围
+use std::ops::Range;
+
+// 引入anyhow库中的Error类型和anyhow宏，用于表示无法识别的分词器名称和无效的正则表达式
+use anyhow::{anyhow, Error};
+// 引入regex库中的Regex类型，作为正则表达式分词的实现
+use regex::Regex;
+// 引入unicode-segmentation库中的UnicodeSegmentation trait，它按UAX #29规则切分单词
+use unicode_segmentation::UnicodeSegmentation;
+
+// 引入casefold模块中的fold_case函数，用于做大小写折叠的Unicode分词器
+use super::casefold::fold_case;
+// 引入cjk模块中的Segmenter结构体，作为中文分词的实现
+use super::cjk::Segmenter;
+// 引入rules模块中的单词规则，用于处理缩写、撇号和连字符
+use super::rules::{is_hyphen, Hyphens, Numbers, WordRules};
+
+/// 分词器，将文本切分为单词。
+pub trait Tokenizer {
+    /// 分词器的名称，记录在缓存文件头的 `tokenizer` 字段中，可以通过 `tokenizer_from_name` 重新创建分词器。
+    fn name(&self) -> &str;
+
+    /// 将文本切分为单词。
+    ///
+    /// # 参数
+    ///
+    /// * `text`: 要切分的文本，不会被修改。
+    ///
+    /// # 返回
+    ///
+    /// 返回切分出的单词，不需要转换的单词直接借用 `text`。
+    fn tokenize<'a>(&self, text: &'a str) -> Vec<Cow<'a, str>>;
+
+    /// 将文本切分为单词，同时返回每个单词在原文中的位置。
+    ///
+    /// 内置的分词器都直接记录位置；默认实现在原文中依次查找 `tokenize` 得到的单词，
+    /// 找不到的单词（例如被转为小写的单词）的位置为上一个单词之后的空范围，并且 `verbatim` 为 `false`。
+    ///
+    /// # 参数
+    ///
+    /// * `text`: 要切分的文本，不会被修改。
+    ///
+    /// # 返回
+    ///
+    /// 返回切分出的单词及其位置，单词与 `tokenize` 的结果相同。
+    fn tokens<'a>(&self, text: &'a str) -> Vec<Token<'a>> {
+        let mut cursor = 0;
+        self.tokenize(text)
+            .into_iter()
+            .map(|word| match text[cursor..].find(&*word) {
+                Some(i) => {
+                    let span = cursor + i..cursor + i + word.len();
+                    cursor = span.end;
+                    Token {
+                        word,
+                        span,
+                        verbatim: true,
+                    }
+                }
+                None => Token {
+                    word,
+                    span: cursor..cursor,
+                    verbatim: false,
+                },
+            })
+            .collect()
+    }
+}
+
+/// 分词得到的一个单词，以及它在原文中的位置。
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Token<'a> {
+    /// 转为小写并按规则处理后的单词
+    pub word: Cow<'a, str>,
+    /// 单词在原文中的字节范围，展开的缩写和计为 `<NUM>` 的数字对应原文中的整个缩写或数字
+    pub span: Range<usize>,
+    /// 原文中的这一段是否就是该单词的写法（可能有大小写、撇号或连字符的差别），展开的缩写和 `<NUM>` 为 `false`
+    pub verbatim: bool,
+}
+
+impl<'a> Token<'a> {
+    /// 创建一个对应原文片段 `part` 的单词，`part` 必须是 `text` 的切片。
+    pub(crate) fn within(text: &str, part: &str, word: Cow<'a, str>, verbatim: bool) -> Self {
+        let start = part.as_ptr() as usize - text.as_ptr() as usize;
+        Token {
+            word,
+            span: start..start + part.len(),
+            verbatim,
+        }
+    }
+}
+
+/// 取出单词，丢弃位置，用于由 `tokens` 实现 `tokenize`。
+pub(crate) fn words(tokens: Vec<Token<'_>>) -> Vec<Cow<'_, str>> {
+    tokens.into_iter().map(|token| token.word).collect()
+}
+
+/// 按非 ASCII 字母切分并转为小写的分词器。
+///
+/// 默认在撇号和连字符处拆开（`WordRules::SPLIT`），可以通过 `with_rules` 保留或展开缩写、保留连字符复合词。
+#[derive(Clone, Debug)]
+pub struct AsciiTokenizer {
+    /// 缩写、撇号和连字符的处理规则
+    rules: WordRules,
+    /// 分词器的名称，规则不是默认规则时带有规则后缀
+    name: String,
+}
+
+impl AsciiTokenizer {
+    /// 分词器的名称。
+    pub const NAME: &'static str = "ascii-lowercase";
+    /// 默认规则。
+    pub const DEFAULT_RULES: WordRules = WordRules::SPLIT;
+
+    /// 创建一个 ASCII 分词器。
+    pub fn new() -> Self {
+        AsciiTokenizer {
+            rules: Self::DEFAULT_RULES,
+            name: Self::NAME.to_string(),
+        }
+    }
+
+    /// 设置缩写、撇号和连字符的处理规则。
+    pub fn with_rules(mut self, rules: WordRules) -> Self {
+        self.rules = rules;
+        self.name = rules_name(Self::NAME, rules, Self::DEFAULT_RULES);
+        self
+    }
+}
+
+impl Default for AsciiTokenizer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Tokenizer for AsciiTokenizer {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn tokenize<'a>(&self, text: &'a str) -> Vec<Cow<'a, str>> {
+        words(self.tokens(text))
+    }
+
+    fn tokens<'a>(&self, text: &'a str) -> Vec<Token<'a>> {
+        let rules = &self.rules;
+        let is_word = |c: char| {
+            c.is_ascii_alphabetic() || (rules.numbers != Numbers::Drop && c.is_ascii_digit())
+        };
+        let mut tokens = Vec::new();
+        // 当前片段的起始位置、结束位置和最后一个字母或数字
+        let mut start = None;
+        let mut end = 0;
+        let mut last = ' ';
+        // 等待后面的字符确认是否连接两边的撇号、连字符或符号
+        let mut pending = None;
+        // 片段是否已经以 "+" 或 "#" 结尾，不能再延长
+        let mut closed = false;
+        for (i, c) in text.char_indices() {
+            if is_word(c) {
+                let extend =
+                    start.is_some() && !closed && pending.is_none_or(|j| rules.joins(last, j, c));
+                if !extend {
+                    if let Some(s) = start {
+                        rules.apply(text, &text[s..end], &mut tokens);
+                    }
+                    start = Some(i);
+                }
+                end = i + 1;
+                last = c;
+                pending = None;
+                closed = false;
+            } else if start.is_some() && pending.is_none() && rules.symbols.trails(c) {
+                end = i + 1;
+                closed = true;
+            } else if start.is_some() && pending.is_none() && !closed && rules.may_join(c) {
+                pending = Some(c);
+            } else if let Some(s) = start.take() {
+                rules.apply(text, &text[s..end], &mut tokens);
+                pending = None;
+                closed = false;
+            }
+        }
+        if let Some(s) = start {
+            rules.apply(text, &text[s..end], &mut tokens);
+        }
+        tokens
+    }
+}
+
+/// 按 Unicode 单词边界（UAX #29）切分的分词器，默认只保留至少包含一个字母的单词。
+///
+/// 默认保留缩写、拆开连字符（`WordRules::UAX29`），可以通过 `with_rules` 修改。
+#[derive(Clone, Debug)]
+pub struct UnicodeTokenizer {
+    /// 为 `true` 时做大小写折叠，否则按 Unicode 规则转为小写
+    fold: bool,
+    /// 缩写、撇号和连字符的处理规则
+    rules: WordRules,
+    /// 分词器的名称，规则不是默认规则时带有规则后缀
+    name: String,
+}
+
+impl UnicodeTokenizer {
+    /// 转为小写的分词器的名称。
+    pub const NAME: &'static str = "unicode-lowercase";
+    /// 做大小写折叠的分词器的名称。
+    pub const FOLD_NAME: &'static str = "unicode-casefold";
+    /// 默认规则。
+    pub const DEFAULT_RULES: WordRules = WordRules::UAX29;
+
+    /// 创建一个转为小写的 Unicode 分词器。
+    pub fn new() -> Self {
+        UnicodeTokenizer {
+            fold: false,
+            rules: Self::DEFAULT_RULES,
+            name: Self::NAME.to_string(),
+        }
+    }
+
+    /// 创建一个做大小写折叠的 Unicode 分词器。
+    pub fn folding() -> Self {
+        UnicodeTokenizer {
+            fold: true,
+            rules: Self::DEFAULT_RULES,
+            name: Self::FOLD_NAME.to_string(),
+        }
+    }
+
+    /// 设置缩写、撇号和连字符的处理规则。
+    pub fn with_rules(mut self, rules: WordRules) -> Self {
+        let base = if self.fold {
+            Self::FOLD_NAME
+        } else {
+            Self::NAME
+        };
+        self.rules = rules;
+        self.name = rules_name(base, rules, Self::DEFAULT_RULES);
+        self
+    }
+}
+
+impl Default for UnicodeTokenizer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Tokenizer for UnicodeTokenizer {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn tokenize<'a>(&self, text: &'a str) -> Vec<Cow<'a, str>> {
+        words(self.tokens(text))
+    }
+
+    fn tokens<'a>(&self, text: &'a str) -> Vec<Token<'a>> {
+        let rules = &self.rules;
+        // 按单词边界切分，将只隔着一个需要保留的连字符或符号的相邻单词合并为一个片段
+        let mut spans: Vec<(usize, usize)> = Vec::new();
+        for (start, word) in text.unicode_word_indices() {
+            let mut end = start + word.len();
+            // 单词末尾的 "+" 和 "#" 属于单词
+            for c in text[end..].chars() {
+                if !rules.symbols.trails(c) {
+                    break;
+                }
+                end += c.len_utf8();
+            }
+            if let Some(last) = spans.last_mut() {
+                let mut between = text[last.1..start].chars();
+                let joins = between.next().is_some_and(|c| {
+                    (is_hyphen(c) && rules.hyphens == Hyphens::Keep) || rules.symbols.joins(c)
+                });
+                if joins && between.next().is_none() {
+                    last.1 = end;
+                    continue;
+                }
+            }
+            spans.push((start, end));
+        }
+        let mut tokens = Vec::new();
+        for (start, end) in spans {
+            let span = &text[start..end];
+            if !span.chars().any(char::is_alphanumeric) {
+                continue;
+            }
+            let before = tokens.len();
+            self.rules.apply(text, span, &mut tokens);
+            if self.fold {
+                // 展开的缩写已经是小写，<NUM> 保持原样
+                for token in tokens[before..].iter_mut().filter(|t| t.verbatim) {
+                    let folded = fold_case(&token.word);
+                    if folded != token.word {
+                        token.word = Cow::Owned(folded);
+                    }
+                }
+            }
+        }
+        tokens
+    }
+}
+
+/// 只按空白切分并转为小写的分词器。
+#[derive(Clone, Copy, Debug, Default)]
+pub struct WhitespaceTokenizer;
+
+impl WhitespaceTokenizer {
+    /// 分词器的名称。
+    pub const NAME: &'static str = "whitespace-lowercase";
+
+    /// 创建一个按空白切分的分词器。
+    pub fn new() -> Self {
+        WhitespaceTokenizer
+    }
+}
+
+impl Tokenizer for WhitespaceTokenizer {
+    fn name(&self) -> &str {
+        Self::NAME
+    }
+
+    fn tokenize<'a>(&self, text: &'a str) -> Vec<Cow<'a, str>> {
+        words(self.tokens(text))
+    }
+
+    fn tokens<'a>(&self, text: &'a str) -> Vec<Token<'a>> {
+        text.split_whitespace()
+            .map(|word| Token::within(text, word, lowercase(word), true))
+            .collect()
+    }
+}
+
+/// 取正则表达式的每个匹配作为单词并转为小写的分词器。
+#[derive(Clone, Debug)]
+pub struct RegexTokenizer {
+    /// 正则表达式
+    regex: Regex,
+    /// 分词器的名称，为 `regex:` 加上正则表达式
+    name: String,
+}
+
+impl RegexTokenizer {
+    /// 分词器名称的前缀。
+    pub const PREFIX: &'static str = "regex:";
+
+    /// 根据正则表达式创建分词器。
+    ///
+    /// # 参数
+    ///
+    /// * `pattern`: 正则表达式，每个匹配是一个单词，空的匹配会被忽略。
+    ///
+    /// # 返回
+    ///
+    /// 如果正则表达式有效，返回分词器，否则返回错误。
+    pub fn new(pattern: &str) -> Result<Self, Error> {
+        Ok(RegexTokenizer {
+            regex: Regex::new(pattern)?,
+            name: format!("{}{}", Self::PREFIX, pattern),
+        })
+    }
+}
+
+impl Tokenizer for RegexTokenizer {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn tokenize<'a>(&self, text: &'a str) -> Vec<Cow<'a, str>> {
+        words(self.tokens(text))
+    }
+
+    fn tokens<'a>(&self, text: &'a str) -> Vec<Token<'a>> {
+        self.regex
+            .find_iter(text)
+            .filter(|m| !m.is_empty())
+            .map(|m| Token {
+                word: lowercase(m.as_str()),
+                span: m.range(),
+                verbatim: true,
+            })
+            .collect()
+    }
+}
+
+/// 根据名称创建分词器。
+///
+/// 支持的名称为各个分词器的 `NAME`，以及 `regex:` 加上正则表达式。
+/// ASCII 和 Unicode 分词器的名称后面可以带有 `WordRules::suffix` 生成的规则后缀。
+/// 旧版本的缓存没有记录分词方式，名称为 `unknown`，当时只有 ASCII 分词，因此返回 `AsciiTokenizer`。
+/// 中文分词器使用内置词典；名称带有 `Segmenter::DICT_PREFIX` 时统计使用了用户词典，无法只根据名称重新创建，返回错误，
+/// 这时请用 `Segmenter::load_file` 追加同一个词典，再检查名称是否一致。
+///
+/// # 参数
+///
+/// * `name`: 分词器的名称，通常来自缓存文件头的 `tokenizer` 字段。
+///
+/// # 返回
+///
+/// 如果名称可以识别，返回分词器，否则返回错误。
+pub fn tokenizer_from_name(name: &str) -> Result<Box<dyn Tokenizer>, Error> {
+    if let Some(pattern) = name.strip_prefix(RegexTokenizer::PREFIX) {
+        return Ok(Box::new(RegexTokenizer::new(pattern)?));
+    }
+    let (base, suffix) = name.split_once(';').unwrap_or((name, ""));
+    let rules = |default| WordRules::parse(suffix, default);
+    match base {
+        AsciiTokenizer::NAME | "unknown" => Ok(Box::new(
+            AsciiTokenizer::new().with_rules(rules(AsciiTokenizer::DEFAULT_RULES)?),
+        )),
+        UnicodeTokenizer::NAME => Ok(Box::new(
+            UnicodeTokenizer::new().with_rules(rules(UnicodeTokenizer::DEFAULT_RULES)?),
+        )),
+        UnicodeTokenizer::FOLD_NAME => Ok(Box::new(
+            UnicodeTokenizer::folding().with_rules(rules(UnicodeTokenizer::DEFAULT_RULES)?),
+        )),
+        WhitespaceTokenizer::NAME if suffix.is_empty() => Ok(Box::new(WhitespaceTokenizer)),
+        Segmenter::NAME if suffix.is_empty() => Ok(Box::new(Segmenter::bundled())),
+        Segmenter::NAME if name.contains(Segmenter::DICT_PREFIX) => Err(anyhow!(
+            "缓存使用了用户词典分词（{}），需要指定同一个用户词典",
+            name
+        )),
+        _ => Err(anyhow!("无法识别的分词方式: {}", name)),
+    }
+}
+
+/// 分词器的名称，规则不是默认规则时加上规则后缀。
+fn rules_name(base: &str, rules: WordRules, default: WordRules) -> String {
+    if rules == default {
+        base.to_string()
+    } else {
+        format!("{}{}", base, rules.suffix())
+    }
+}
+
+/// 将单词按 Unicode 规则转为小写，已经是小写时直接借用。
+pub(crate) fn lowercase(word: &str) -> Cow<'_, str> {
+    if word.chars().all(|c| c.to_lowercase().eq([c])) {
+        Cow::Borrowed(word)
+    } else {
+        Cow::Owned(word.to_lowercase())
+    }
+}
+
+/// 按 Unicode 单词边界（UAX #29）从字符串中过滤出单词，见 `UnicodeTokenizer`。
+///
+/// # 参数
+///
+/// * `text`: 要过滤的字符串，不会被修改。
+/// * `fold`: 为 `true` 时做大小写折叠，否则按 Unicode 规则转为小写。
+///
+/// # 返回
+///
+/// 返回一个迭代器，依次产生转换后的单词。
+pub fn filter_word_unicode(text: &str, fold: bool) -> impl Iterator<Item = String> + '_ {
+    let tokenizer = if fold {
+        UnicodeTokenizer::folding()
+    } else {
+        UnicodeTokenizer::new()
+    };
+    tokenizer.tokenize(text).into_iter().map(Cow::into_owned)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_unicode_tokenize() {
+        let unicode = |text| UnicodeTokenizer::new().tokenize(text);
+        // 带重音的拉丁字母
+        assert_eq!(unicode("Café, naïve Zürich!"), ["café", "naïve", "zürich"]);
+        assert_eq!(
+            AsciiTokenizer::new().tokenize("Café, Zürich"),
+            ["caf", "z", "rich"]
+        );
+        // 西里尔字母
+        assert_eq!(unicode("Привет, МИР! Ёлка"), ["привет", "мир", "ёлка"]);
+        // 希腊字母，词尾的 Σ 小写为 ς
+        assert_eq!(unicode("ΟΔΟΣ Σοφία"), ["οδος", "σοφία"]);
+        // 混合文字，汉字按字切分，纯数字被丢弃
+        assert_eq!(
+            unicode("Hello мир café 你好 2023"),
+            ["hello", "мир", "café", "你", "好"]
+        );
+        // data.txt 中英文和中文交替出现
+        assert_eq!(
+            unicode("By Tamar Michaelis，CNN 作者"),
+            ["by", "tamar", "michaelis", "cnn", "作", "者"]
+        );
+    }
+
+    #[test]
+    fn test_case_fold() {
+        let fold = |text| UnicodeTokenizer::folding().tokenize(text);
+        assert_eq!(
+            fold("Straße STRASSE strasse"),
+            ["strasse", "strasse", "strasse"]
+        );
+        assert_eq!(fold("ΟΔΟΣ οδοσ"), ["οδοσ", "οδοσ"]);
+        assert_eq!(fold("ÉCOLE école"), ["école", "école"]);
+        assert_eq!(fold("ẞ ς ﬃ ŉ"), ["ss", "σ", "ffi", "\u{2bc}n"]);
+    }
+
+    #[test]
+    fn test_tokenizers() {
+        let text = "Let's test the BST program. It’s well-known!";
+        // 分词器不修改输入，已经是小写的单词直接借用原文
+        let tokens = AsciiTokenizer::new().tokenize(text);
+        assert_eq!(
+            tokens,
+            ["let", "s", "test", "the", "bst", "program", "it", "s", "well", "known"]
+        );
+        assert!(matches!(tokens[1], Cow::Borrowed(_)));
+        assert!(matches!(tokens[4], Cow::Owned(_)));
+        let mut context = text.to_string();
+        assert!(super::super::filter_word(&mut context).eq(tokens.iter().map(|t| t.as_ref())));
+
+        assert_eq!(
+            WhitespaceTokenizer.tokenize(text),
+            [
+                "let's",
+                "test",
+                "the",
+                "bst",
+                "program.",
+                "it’s",
+                "well-known!"
+            ]
+        );
+        let regex = RegexTokenizer::new(r"\p{L}+(?:['’-]\p{L}+)*").unwrap();
+        assert_eq!(
+            regex.tokenize(text),
+            [
+                "let's",
+                "test",
+                "the",
+                "bst",
+                "program",
+                "it’s",
+                "well-known"
+            ]
+        );
+        assert!(RegexTokenizer::new("(").is_err());
+    }
+
+    #[test]
+    fn test_word_rules() {
+        use super::super::rules::Contractions;
+
+        let rules = |contractions, hyphens, normalize_apostrophes| WordRules {
+            contractions,
+            hyphens,
+            normalize_apostrophes,
+            ..WordRules::SPLIT
+        };
+        // data.txt 中的句子，使用弯撇号
+        let unclear = "It’s unclear which hostage survived initially";
+        let shout = "if they don’t shout, no one will bring their children back";
+        let possessive = "Israel’s military; Netanyahu’s critics; three hostages’ deaths";
+        let hyphen = "during a three-hour demonstration – the two key goals";
+
+        // 默认规则：ASCII 全部拆开，Unicode 保留缩写
+        let ascii = AsciiTokenizer::new();
+        assert_eq!(ascii.tokenize(unclear)[..3], ["it", "s", "unclear"]);
+        assert_eq!(ascii.tokenize(shout)[1..4], ["they", "don", "t"]);
+        assert_eq!(ascii.tokenize(hyphen)[2..4], ["three", "hour"]);
+        let unicode = UnicodeTokenizer::new();
+        assert_eq!(unicode.tokenize(unclear)[..2], ["it’s", "unclear"]);
+        assert_eq!(unicode.tokenize(hyphen)[2..4], ["three", "hour"]);
+
+        // 保留缩写和连字符复合词，并统一弯撇号
+        let keep = rules(Contractions::Keep, Hyphens::Keep, true);
+        for tokenizer in [
+            Box::new(AsciiTokenizer::new().with_rules(keep)) as Box<dyn Tokenizer>,
+            Box::new(UnicodeTokenizer::new().with_rules(keep)),
+        ] {
+            assert_eq!(tokenizer.tokenize(unclear)[..2], ["it's", "unclear"]);
+            assert_eq!(tokenizer.tokenize(shout)[1..3], ["they", "don't"]);
+            assert_eq!(
+                tokenizer.tokenize(possessive),
+                [
+                    "israel's",
+                    "military",
+                    "netanyahu's",
+                    "critics",
+                    "three",
+                    "hostages",
+                    "deaths"
+                ]
+            );
+            // 破折号不是连字符
+            assert_eq!(
+                tokenizer.tokenize(hyphen),
+                [
+                    "during",
+                    "a",
+                    "three-hour",
+                    "demonstration",
+                    "the",
+                    "two",
+                    "key",
+                    "goals"
+                ]
+            );
+        }
+        // 不统一弯撇号时保留原文
+        let curly =
+            AsciiTokenizer::new().with_rules(rules(Contractions::Keep, Hyphens::Split, false));
+        assert_eq!(curly.tokenize(unclear)[0], "it’s");
+        assert!(matches!(curly.tokenize(shout)[2], Cow::Borrowed("don’t")));
+
+        // 展开缩写，所有格只保留前面的单词
+        let expand = rules(Contractions::Expand, Hyphens::Split, false);
+        for tokenizer in [
+            Box::new(AsciiTokenizer::new().with_rules(expand)) as Box<dyn Tokenizer>,
+            Box::new(UnicodeTokenizer::folding().with_rules(expand)),
+        ] {
+            assert_eq!(tokenizer.tokenize(unclear)[..3], ["it", "is", "unclear"]);
+            assert_eq!(tokenizer.tokenize(shout)[1..4], ["they", "do", "not"]);
+            assert_eq!(
+                tokenizer.tokenize("What’s the reaction been?"),
+                ["what", "is", "the", "reaction", "been"]
+            );
+            assert_eq!(
+                tokenizer.tokenize(possessive),
+                [
+                    "israel",
+                    "military",
+                    "netanyahu",
+                    "critics",
+                    "three",
+                    "hostages",
+                    "deaths"
+                ]
+            );
+            assert_eq!(
+                tokenizer.tokenize("Let's test the BST program."),
+                ["let", "us", "test", "the", "bst", "program"]
+            );
+        }
+
+        // 规则记录在名称中，可以重新创建相同的分词器
+        let tokenizer = UnicodeTokenizer::folding().with_rules(keep);
+        assert_eq!(
+            tokenizer.name(),
+            "unicode-casefold;contractions=keep;hyphens=keep;apostrophes=normalize"
+        );
+        let rebuilt = tokenizer_from_name(tokenizer.name()).unwrap();
+        assert_eq!(rebuilt.tokenize(shout), tokenizer.tokenize(shout));
+        assert_eq!(
+            AsciiTokenizer::new().with_rules(WordRules::SPLIT).name(),
+            AsciiTokenizer::NAME
+        );
+        assert!(tokenizer_from_name("whitespace-lowercase;hyphens=keep").is_err());
+    }
+
+    #[test]
+    fn test_numbers_and_symbols() {
+        use super::super::rules::{Contractions, Symbols};
+
+        let text =
+            "In 2023, COVID-19 cost $1,000.50; IDF's x86_64 build uses C++ and C# at 3.5 GHz.";
+        let keep = WordRules {
+            numbers: Numbers::Keep,
+            ..WordRules::SPLIT
+        };
+        // 默认丢弃数字
+        assert_eq!(
+            AsciiTokenizer::new().tokenize(text)[..5],
+            ["in", "covid", "cost", "idf", "s"]
+        );
+        assert_eq!(
+            AsciiTokenizer::new().with_rules(keep).tokenize(text),
+            [
+                "in", "2023", "covid", "19", "cost", "1,000.50", "idf", "s", "x86", "64", "build",
+                "uses", "c", "and", "c", "at", "3.5", "ghz"
+            ]
+        );
+        // 纯数字计为 <NUM>，保留连字符、缩写、下划线和末尾的 + 与 #
+        let technical = WordRules {
+            contractions: Contractions::Keep,
+            hyphens: Hyphens::Keep,
+            normalize_apostrophes: true,
+            numbers: Numbers::Bucket,
+            symbols: Symbols::UNDERSCORE | Symbols::TRAILING,
+        };
+        let expected = [
+            "in", "<NUM>", "covid-19", "cost", "<NUM>", "idf's", "x86_64", "build", "uses", "c++",
+            "and", "c#", "at", "<NUM>", "ghz",
+        ];
+        assert_eq!(
+            AsciiTokenizer::new().with_rules(technical).tokenize(text),
+            expected
+        );
+        assert_eq!(
+            UnicodeTokenizer::new().with_rules(technical).tokenize(text),
+            expected
+        );
+        // Unicode 分词默认丢弃纯数字，但保留字母数字混合的单词
+        assert_eq!(
+            UnicodeTokenizer::new().tokenize("x86_64 3 hostages"),
+            ["x86_64", "hostages"]
+        );
+        // 点号、@ 和斜杠
+        let symbols = WordRules {
+            symbols: Symbols::DOT | Symbols::AT | Symbols::SLASH,
+            ..WordRules::SPLIT
+        };
+        assert_eq!(
+            AsciiTokenizer::new()
+                .with_rules(symbols)
+                .tokenize("node.js and/or admin@cnn. End"),
+            ["node.js", "and/or", "admin@cnn", "end"]
+        );
+        let name = AsciiTokenizer::new()
+            .with_rules(technical)
+            .name()
+            .to_string();
+        assert_eq!(tokenizer_from_name(&name).unwrap().tokenize(text), expected);
+    }
+
+    #[test]
+    fn test_tokenizer_from_name() {
+        let tokenizers: Vec<Box<dyn Tokenizer>> = vec![
+            Box::new(AsciiTokenizer::new()),
+            Box::new(UnicodeTokenizer::new()),
+            Box::new(UnicodeTokenizer::folding()),
+            Box::new(WhitespaceTokenizer),
+            Box::new(RegexTokenizer::new(r"\w+").unwrap()),
+            Box::new(Segmenter::bundled()),
+        ];
+        let text = "Straße 以色列国防军 don't";
+        for tokenizer in tokenizers {
+            let rebuilt = tokenizer_from_name(tokenizer.name()).unwrap();
+            assert_eq!(rebuilt.name(), tokenizer.name());
+            assert_eq!(rebuilt.tokenize(text), tokenizer.tokenize(text));
+        }
+        // 旧版本的缓存使用 ASCII 分词
+        assert_eq!(
+            tokenizer_from_name("unknown").unwrap().name(),
+            AsciiTokenizer::NAME
+        );
+        assert!(tokenizer_from_name("no-such-tokenizer").is_err());
+        assert!(tokenizer_from_name("regex:(").is_err());
+        // 使用了用户词典的中文分词无法只根据名称重新创建
+        let mut segmenter = Segmenter::bundled();
+        segmenter.insert("三名人质");
+        assert!(tokenizer_from_name(segmenter.name()).is_err());
+    }
+
+    #[test]
+    fn test_token_spans() {
+        use super::super::rules::Contractions;
+
+        // 每个单词的位置都对应原文中的写法
+        let text = "The IDF’s three-hour raid, 2023 — Zürich";
+        let surfaces = |tokenizer: &dyn Tokenizer| -> Vec<(String, String, bool)> {
+            tokenizer
+                .tokens(text)
+                .into_iter()
+                .map(|t| (t.word.into_owned(), text[t.span].to_string(), t.verbatim))
+                .collect()
+        };
+        let owned = |w: &str, s: &str, v| (w.to_string(), s.to_string(), v);
+        assert_eq!(
+            surfaces(&AsciiTokenizer::new())[..5],
+            [
+                owned("the", "The", true),
+                owned("idf", "IDF", true),
+                owned("s", "s", true),
+                owned("three", "three", true),
+                owned("hour", "hour", true)
+            ]
+        );
+        let expand = UnicodeTokenizer::folding().with_rules(WordRules {
+            contractions: Contractions::Expand,
+            hyphens: Hyphens::Keep,
+            numbers: Numbers::Bucket,
+            ..WordRules::UAX29
+        });
+        assert_eq!(
+            surfaces(&expand),
+            [
+                owned("the", "The", true),
+                owned("idf", "IDF’s", false),
+                owned("three-hour", "three-hour", true),
+                owned("raid", "raid", true),
+                owned("<NUM>", "2023", false),
+                owned("zürich", "Zürich", true)
+            ]
+        );
+        assert_eq!(
+            surfaces(&WhitespaceTokenizer)[1],
+            owned("idf’s", "IDF’s", true)
+        );
+        let regex = RegexTokenizer::new(r"\p{Lu}+").unwrap();
+        assert_eq!(
+            surfaces(&regex),
+            [
+                owned("t", "T", true),
+                owned("idf", "IDF", true),
+                owned("z", "Z", true)
+            ]
+        );
+
+        // 自定义分词器的默认实现在原文中查找单词，找不到时位置为空
+        struct Upper;
+        impl Tokenizer for Upper {
+            fn name(&self) -> &str {
+                "upper"
+            }
+            fn tokenize<'a>(&self, text: &'a str) -> Vec<Cow<'a, str>> {
+                text.split(' ')
+                    .map(|w| Cow::Owned(w.to_uppercase()))
+                    .collect()
+            }
+        }
+        let tokens = Upper.tokens("AB cd AB");
+        assert_eq!(tokens[0].span, 0..2);
+        assert_eq!((tokens[1].span.clone(), tokens[1].verbatim), (2..2, false));
+        assert_eq!(tokens[2].span, 6..8);
+    }
+}